@@ -35,6 +35,7 @@ pub enum AnyNode {
     NumLit(NumLit),
     CharLit(CharLit),
     StrLit(StrLit),
+    InterpStrLit(InterpStrLit),
     TupleLit(TupleLit),
     IfExpr(IfExpr),
     ElifExpr(ElifExpr),
@@ -43,6 +44,12 @@ pub enum AnyNode {
     UnExpr(UnExpr),
     AbstFnDecl(AbstFnDecl),
     ProdTy(ProdTy),
+    TyAliasDecl(TyAliasDecl),
+    TyApp(TyApp),
+    VariantDecl(VariantDecl),
+    RecordLit(RecordLit),
+    RecordUpd(RecordUpd),
+    FieldInit(FieldInit),
 }
 
 // It's important to support the size of AnyNode less than 64 to fit into a cache line
@@ -70,6 +77,7 @@ macro_rules! folding {
             AnyNode::NumLit($bind) => $usage,
             AnyNode::CharLit($bind) => $usage,
             AnyNode::StrLit($bind) => $usage,
+            AnyNode::InterpStrLit($bind) => $usage,
             AnyNode::TupleLit($bind) => $usage,
             AnyNode::IfExpr($bind) => $usage,
             AnyNode::ElifExpr($bind) => $usage,
@@ -78,6 +86,12 @@ macro_rules! folding {
             AnyNode::UnExpr($bind) => $usage,
             AnyNode::AbstFnDecl($bind) => $usage,
             AnyNode::ProdTy($bind) => $usage,
+            AnyNode::TyAliasDecl($bind) => $usage,
+            AnyNode::TyApp($bind) => $usage,
+            AnyNode::VariantDecl($bind) => $usage,
+            AnyNode::RecordLit($bind) => $usage,
+            AnyNode::RecordUpd($bind) => $usage,
+            AnyNode::FieldInit($bind) => $usage,
         }
     };
 }
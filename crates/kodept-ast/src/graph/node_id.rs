@@ -1,4 +1,5 @@
 use std::fmt::{Debug, Formatter};
+use std::hash::{Hash, Hasher};
 
 use crate::graph::any_node::{AnyNode};
 use derive_more::{Display, From};
@@ -124,6 +125,18 @@ impl<T> PartialEq for NodeId<T> {
 
 impl<T> Eq for NodeId<T> {}
 
+impl<T> Hash for NodeId<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            NodeId::Root => state.write_u8(0),
+            NodeId::Key(k) => {
+                state.write_u8(1);
+                k.hash(state)
+            }
+        }
+    }
+}
+
 impl<T> Clone for NodeId<T> {
     fn clone(&self) -> Self {
         *self
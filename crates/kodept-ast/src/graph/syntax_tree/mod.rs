@@ -43,7 +43,10 @@ impl<P> SyntaxTree<P> {
 
         Helper(
             self.inner.map(
-                |k, v| format!("{} [{}]", v.name(), k,),
+                |k, v| match v {
+                    AnyNode::EnumDecl(x) => format!("{} {:?} [{}]", v.name(), x.kind, k),
+                    _ => format!("{} [{}]", v.name(), k),
+                },
                 |tag| TAGS_DESC[*tag as usize],
             ),
             config,
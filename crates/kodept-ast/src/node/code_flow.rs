@@ -30,8 +30,8 @@ node! {
 node! {
     #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
     pub struct ElifExpr {;
-        pub condition: Identity<Operation>,
-        pub body: Identity<Body>,;
+        pub condition: Identity<Operation> as PRIMARY,
+        pub body: Identity<Body> as 0,;
         parent is [IfExpr]
     }
 }
@@ -22,6 +22,8 @@ node_sub_enum! {
         Unary(UnExpr),
         Binary(BinExpr),
         Block(Exprs),
+        Record(RecordLit),
+        Update(RecordUpd),
         Expr(forward Expression),
     }
 }
@@ -55,6 +57,31 @@ node! {
     }
 }
 
+node! {
+    #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+    pub struct RecordLit {
+        pub name: SharedStr,;
+        pub fields: Vec<FieldInit>,
+    }
+}
+
+node! {
+    #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+    pub struct RecordUpd {;
+        pub record: Identity<Operation> as PRIMARY,
+        pub fields: Vec<FieldInit> as SECONDARY,
+    }
+}
+
+node! {
+    #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+    pub struct FieldInit {
+        pub name: SharedStr,;
+        pub value: Identity<Operation>,;
+        parent is [RecordLit, RecordUpd]
+    }
+}
+
 node! {
     #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
     pub struct UnExpr {
@@ -188,10 +215,35 @@ impl<'a> PopulateTree<'a> for &'a rlt::Operation {
             } => build_binary(self, context, left, operation, right).cast(),
             rlt::Operation::Application(x) => x.convert(context).cast(),
             rlt::Operation::Expression(x) => x.convert(context).cast(),
+            rlt::Operation::Record(x) => x.convert(context).cast(),
+            rlt::Operation::Update { record, fields, .. } => {
+                SubSyntaxTree::new(RecordUpd::uninit().with_rlt(self))
+                    .with_children_from::<PRIMARY, _>([record.as_ref()], context)
+                    .with_children_from::<SECONDARY, _>(fields.inner.as_ref(), context)
+                    .cast()
+            }
         }
     }
 }
 
+impl<'a> PopulateTree<'a> for &'a rlt::Record {
+    type Root = RecordLit;
+
+    fn convert(self, context: impl CodeHolder<Str = SharedStr>) -> SubSyntaxTree<'a, Self::Root> {
+        SubSyntaxTree::new(RecordLit::uninit(context.get_chunk_located(&self.id)).with_rlt(self))
+            .with_children_from(self.fields.inner.as_ref(), context)
+    }
+}
+
+impl<'a> PopulateTree<'a> for &'a rlt::FieldInit {
+    type Root = FieldInit;
+
+    fn convert(self, context: impl CodeHolder<Str = SharedStr>) -> SubSyntaxTree<'a, Self::Root> {
+        SubSyntaxTree::new(FieldInit::uninit(context.get_chunk_located(&self.id)).with_rlt(self))
+            .with_children_from([&self.value], context)
+    }
+}
+
 fn build_binary<'a>(
     node: &'a rlt::Operation,
     context: impl CodeHolder<Str = SharedStr>,
@@ -54,6 +54,7 @@ impl<'a> PopulateTree<'a> for &'a rlt::Module {
             }
         };
         let node = ModDecl::uninit(kind, name).with_rlt(self);
-        SubSyntaxTree::new(node).with_children_from(rest.as_ref(), context)
+        let rest: Vec<_> = rest.iter().filter(|it| !it.is_error()).collect();
+        SubSyntaxTree::new(node).with_children_from(rest, context)
     }
 }
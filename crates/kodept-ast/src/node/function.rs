@@ -9,6 +9,7 @@ use crate::graph::SubSyntaxTree;
 use crate::traits::PopulateTree;
 use crate::{node, node_sub_enum, Body, ModDecl, Param, StructDecl, TyParam, Type};
 use crate::interning::SharedStr;
+use crate::node::types::generic_names;
 
 node_sub_enum! {
     #[derive(Debug, PartialEq)]
@@ -22,7 +23,8 @@ node_sub_enum! {
 node! {
     #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
     pub struct BodyFnDecl {
-        pub name: SharedStr,;
+        pub name: SharedStr,
+        pub generics: Vec<SharedStr>,;
         pub parameters: Vec<Param>,
         pub return_type: Option<Type>,
         pub body: Identity<Body>,;
@@ -33,9 +35,12 @@ node! {
 node! {
     #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
     pub struct AbstFnDecl {
-        pub name: SharedStr,;
+        pub name: SharedStr,
+        pub generics: Vec<SharedStr>,
+        pub foreign: bool,;
         pub parameters: Vec<TyParam>,
-        pub return_type: Option<Type>,
+        pub return_type: Option<Type>,;
+        parent is [ModDecl]
     }
 }
 
@@ -44,10 +49,32 @@ impl<'a> PopulateTree<'a> for &'a rlt::BodiedFunction {
 
     fn convert(self, context: impl CodeHolder<Str = SharedStr>) -> SubSyntaxTree<'a, Self::Root> {
         SubSyntaxTree::new(
-            BodyFnDecl::uninit(context.get_chunk_located(&self.id)).with_rlt(self),
+            BodyFnDecl::uninit(
+                context.get_chunk_located(&self.id),
+                generic_names(&self.generics, context),
+            )
+            .with_rlt(self),
         )
         .with_children_from(self.return_type.as_ref().map(|x| &x.1), context)
         .maybe_with_children_from(self.params.as_ref().map(|x| x.inner.as_ref()), context)
         .with_children_from([self.body.as_ref()], context)
     }
 }
+
+impl<'a> PopulateTree<'a> for &'a rlt::ForeignFunction {
+    type Root = AbstFnDecl;
+
+    fn convert(self, context: impl CodeHolder<Str = SharedStr>) -> SubSyntaxTree<'a, Self::Root> {
+        let function = &self.function;
+        SubSyntaxTree::new(
+            AbstFnDecl::uninit(
+                context.get_chunk_located(&function.id),
+                generic_names(&function.generics, context),
+                true,
+            )
+            .with_rlt(self),
+        )
+        .with_children_from(function.return_type.as_ref().map(|x| &x.1), context)
+        .maybe_with_children_from(function.params.as_ref().map(|x| x.inner.as_ref()), context)
+    }
+}
@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 use kodept_core::structure::rlt;
 use kodept_core::structure::span::CodeHolder;
 
+use crate::graph::tags::{PRIMARY, SECONDARY};
 use crate::graph::SubSyntaxTree;
 use crate::traits::PopulateTree;
 use crate::{node, node_sub_enum, Operation};
@@ -16,6 +17,7 @@ node_sub_enum! {
         Num(NumLit),
         Char(CharLit),
         Str(StrLit),
+        Interp(InterpStrLit),
         Tuple(TupleLit)
     }
 }
@@ -41,6 +43,16 @@ node! {
     }
 }
 
+node! {
+    /// String literal with embedded expressions.
+    /// Fragments and expressions alternate in children, starting and ending with a fragment.
+    #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+    pub struct InterpStrLit {;
+        pub fragments: Vec<StrLit> as PRIMARY,
+        pub expressions: Vec<Operation> as SECONDARY,
+    }
+}
+
 node! {
     #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
     pub struct TupleLit {;
@@ -71,6 +83,20 @@ impl<'a> PopulateTree<'a> for &'a rlt::Literal {
                 StrLit::uninit(context.get_chunk_located(x)).with_rlt(self),
             )
             .cast(),
+            rlt::Literal::Interpolated(x) => {
+                let from_fragment = |it: &'a rlt::Literal| {
+                    SubSyntaxTree::new(StrLit::uninit(context.get_chunk_located(it)).with_rlt(it))
+                };
+                let mut tree = SubSyntaxTree::new(InterpStrLit::uninit().with_rlt(self));
+                for (fragment, expression) in x.fragments.iter().zip(x.expressions.iter()) {
+                    tree.attach_subtree::<_, PRIMARY>(from_fragment(fragment));
+                    tree.attach_subtree::<_, SECONDARY>(expression.convert(context));
+                }
+                if let Some(last) = x.fragments.last() {
+                    tree.attach_subtree::<_, PRIMARY>(from_fragment(last));
+                }
+                tree.cast()
+            }
             rlt::Literal::Tuple(x) => SubSyntaxTree::new(TupleLit::uninit().with_rlt(self))
                 .with_children_from(x.inner.as_ref(), context)
                 .cast(),
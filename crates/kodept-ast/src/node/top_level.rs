@@ -1,18 +1,23 @@
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use kodept_core::structure::rlt::{Enum, Struct, TopLevelNode};
+use kodept_core::structure::rlt::{Enum, EnumVariant, Struct, TopLevelNode, TypeAlias, VariantPayload};
 use kodept_core::structure::span::CodeHolder;
 
-use crate::graph::SubSyntaxTree;
+use crate::graph::{Identity, SubSyntaxTree};
 use crate::traits::PopulateTree;
-use crate::{node, node_sub_enum, BodyFnDecl, ModDecl, TyName, TyParam};
+use crate::{node, node_sub_enum, AbstFnDecl, BodyFnDecl, ModDecl, TyParam, Type};
 use crate::interning::SharedStr;
+use crate::node::types::generic_names;
 
+/// Memory layout requested by the declaration, no backend lowers enums to it yet
 #[derive(Debug, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub enum EnumKind {
+    /// `enum struct`: values are stored inline, sized by the largest variant
     Stack,
+    /// `enum class`: values are stored behind a pointer, so variants may refer to the enum itself.
+    /// Recursion is checked by `RecursiveEnumChecker`, boxing of variants is left to backends
     Heap,
 }
 
@@ -22,6 +27,8 @@ node_sub_enum! {
     pub enum TopLevel {
         Enum(EnumDecl),
         Struct(StructDecl),
+        TyAlias(TyAliasDecl),
+        Foreign(AbstFnDecl),
         Fn(BodyFnDecl)
     }
 }
@@ -29,7 +36,8 @@ node_sub_enum! {
 node! {
     #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
     pub struct StructDecl {
-        pub name: SharedStr,;
+        pub name: SharedStr,
+        pub generics: Vec<SharedStr>,;
         pub parameters: Vec<TyParam>,
         pub contents: Vec<BodyFnDecl>,;
         parent is [ModDecl]
@@ -40,8 +48,28 @@ node! {
     #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
     pub struct EnumDecl {
         pub kind: EnumKind,
+        pub name: SharedStr,
+        pub generics: Vec<SharedStr>,;
+        pub contents: Vec<VariantDecl>,;
+        parent is [ModDecl]
+    }
+}
+
+node! {
+    #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+    pub struct VariantDecl {
+        pub name: SharedStr,;
+        pub fields: Vec<TyParam>,
+        pub payload: Vec<Type>,;
+        parent is [EnumDecl]
+    }
+}
+
+node! {
+    #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+    pub struct TyAliasDecl {
         pub name: SharedStr,;
-        pub contents: Vec<TyName>,;
+        pub aliased: Identity<Type>,;
         parent is [ModDecl]
     }
 }
@@ -51,7 +79,8 @@ impl<'a> PopulateTree<'a> for &'a Struct {
 
     fn convert(self, context: impl CodeHolder<Str = SharedStr>) -> SubSyntaxTree<'a, Self::Root> {
         let node =
-            StructDecl::uninit(context.get_chunk_located(&self.id)).with_rlt(self);
+            StructDecl::uninit(context.get_chunk_located(&self.id), generic_names(&self.generics, context))
+                .with_rlt(self);
         SubSyntaxTree::new(node)
             .maybe_with_children_from(self.body.as_ref().map(|x| x.inner.as_ref()), context)
             .maybe_with_children_from(self.parameters.as_ref().map(|x| x.inner.as_ref()), context)
@@ -62,17 +91,47 @@ impl<'a> PopulateTree<'a> for &'a Enum {
     type Root = EnumDecl;
 
     fn convert(self, context: impl CodeHolder<Str = SharedStr>) -> SubSyntaxTree<'a, Self::Root> {
-        let (kind, name, rest) = match self {
-            Enum::Stack { id, contents, .. } => (EnumKind::Stack, id, contents),
-            Enum::Heap { id, contents, .. } => (EnumKind::Heap, id, contents),
+        let (kind, name, generics, rest) = match self {
+            Enum::Stack { id, generics, contents, .. } => (EnumKind::Stack, id, generics, contents),
+            Enum::Heap { id, generics, contents, .. } => (EnumKind::Heap, id, generics, contents),
         };
-        let node =
-            EnumDecl::uninit(kind, context.get_chunk_located(name)).with_rlt(self);
+        let node = EnumDecl::uninit(
+            kind,
+            context.get_chunk_located(name),
+            generic_names(generics, context),
+        )
+        .with_rlt(self);
         SubSyntaxTree::new(node)
             .maybe_with_children_from(rest.as_ref().map(|it| it.inner.as_ref()), context)
     }
 }
 
+impl<'a> PopulateTree<'a> for &'a EnumVariant {
+    type Root = VariantDecl;
+
+    fn convert(self, context: impl CodeHolder<Str = SharedStr>) -> SubSyntaxTree<'a, Self::Root> {
+        let node = VariantDecl::uninit(context.get_chunk_located(&self.id)).with_rlt(self);
+        match &self.payload {
+            None => SubSyntaxTree::new(node),
+            Some(VariantPayload::Named(x)) => {
+                SubSyntaxTree::new(node).with_children_from(x.inner.as_ref(), context)
+            }
+            Some(VariantPayload::Positional(x)) => {
+                SubSyntaxTree::new(node).with_children_from(x.inner.as_ref(), context)
+            }
+        }
+    }
+}
+
+impl<'a> PopulateTree<'a> for &'a TypeAlias {
+    type Root = TyAliasDecl;
+
+    fn convert(self, context: impl CodeHolder<Str = SharedStr>) -> SubSyntaxTree<'a, Self::Root> {
+        let node = TyAliasDecl::uninit(context.get_chunk_located(&self.id)).with_rlt(self);
+        SubSyntaxTree::new(node).with_children_from([&self.aliased], context)
+    }
+}
+
 impl<'a> PopulateTree<'a> for &'a TopLevelNode {
     type Root = TopLevel;
 
@@ -80,7 +139,10 @@ impl<'a> PopulateTree<'a> for &'a TopLevelNode {
         match self {
             TopLevelNode::Enum(x) => x.convert(context).cast(),
             TopLevelNode::Struct(x) => x.convert(context).cast(),
+            TopLevelNode::TypeAlias(x) => x.convert(context).cast(),
+            TopLevelNode::ForeignFunction(x) => x.convert(context).cast(),
             TopLevelNode::BodiedFunction(x) => x.convert(context).cast(),
+            TopLevelNode::Error(_) => unreachable!("Erroneous declarations are skipped by module"),
         }
     }
 }
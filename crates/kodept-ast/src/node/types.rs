@@ -7,14 +7,17 @@ use kodept_core::structure::span::CodeHolder;
 use crate::graph::{Identity, SubSyntaxTree};
 use crate::interning::SharedStr;
 use crate::traits::{AsEnum, PopulateTree};
-use crate::{node, node_sub_enum, BodyFnDecl, EnumDecl, StructDecl};
+use crate::{
+    node, node_sub_enum, AbstFnDecl, BodyFnDecl, StructDecl, TyAliasDecl, VariantDecl,
+};
 
 node_sub_enum! {
     #[derive(Debug, PartialEq)]
     #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
     pub enum Type {
         TyName(TyName),
-        Tuple(ProdTy)
+        Tuple(ProdTy),
+        App(TyApp)
     }
 }
 
@@ -31,7 +34,7 @@ node! {
     #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
     pub struct TyName {
         pub name: SharedStr,;;
-        parent is [TyParam, BodyFnDecl, EnumDecl]
+        parent is [TyParam, BodyFnDecl, AbstFnDecl, VariantDecl, TyAliasDecl, ProdTy, TyApp]
     }
 }
 
@@ -39,7 +42,16 @@ node! {
     #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
     pub struct ProdTy {;
         pub types: Vec<Type>,;
-        parent is [TyParam, BodyFnDecl]
+        parent is [TyParam, BodyFnDecl, AbstFnDecl, VariantDecl, TyAliasDecl, ProdTy, TyApp]
+    }
+}
+
+node! {
+    #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+    pub struct TyApp {
+        pub name: SharedStr,;
+        pub arguments: Vec<Type>,;
+        parent is [TyParam, BodyFnDecl, AbstFnDecl, VariantDecl, TyAliasDecl, ProdTy, TyApp]
     }
 }
 
@@ -48,7 +60,7 @@ node! {
     pub struct TyParam {
         pub name: SharedStr,;
         pub parameter_type: Identity<Type>,;
-        parent is [StructDecl, BodyFnDecl]
+        parent is [StructDecl, BodyFnDecl, AbstFnDecl, VariantDecl]
     }
 }
 
@@ -59,6 +71,19 @@ node! {
     }
 }
 
+/// Names of type parameters introduced by a declaration, e.g. `T` in `fun id<T>(x: T): T`
+pub(crate) fn generic_names(
+    generics: &Option<rlt::new_types::Enclosed<Box<[rlt::new_types::TypeName]>>>,
+    context: impl CodeHolder<Str = SharedStr>,
+) -> Vec<SharedStr> {
+    generics.as_ref().map_or(vec![], |it| {
+        it.inner
+            .iter()
+            .map(|name| context.get_chunk_located(name))
+            .collect()
+    })
+}
+
 impl Param {
     pub fn name(&self) -> &str {
         match self.as_enum() {
@@ -96,6 +121,11 @@ impl<'a> PopulateTree<'a> for &'a rlt::Type {
             rlt::Type::Tuple(x) => SubSyntaxTree::new(ProdTy::uninit().with_rlt(self))
                 .with_children_from(x.inner.iter().as_slice(), context)
                 .cast(),
+            rlt::Type::Application { id, arguments } => SubSyntaxTree::new(
+                TyApp::uninit(context.get_chunk_located(id)).with_rlt(self),
+            )
+            .with_children_from(arguments.inner.iter().as_slice(), context)
+            .cast(),
         }
     }
 }
@@ -10,6 +10,9 @@ pub enum RLTFamily<'r> {
     Module(&'r rlt::Module),
     Struct(&'r rlt::Struct),
     Enum(&'r rlt::Enum),
+    EnumVariant(&'r rlt::EnumVariant),
+    TypeAlias(&'r rlt::TypeAlias),
+    ForeignFunction(&'r rlt::ForeignFunction),
     Type(&'r rlt::Type),
     TypeName(&'r rlt::new_types::TypeName),
     TypedParameter(&'r rlt::TypedParameter),
@@ -22,6 +25,8 @@ pub enum RLTFamily<'r> {
     ExpressionBlock(&'r rlt::ExpressionBlock),
     Operation(&'r rlt::Operation),
     Application(&'r rlt::Application),
+    Record(&'r rlt::Record),
+    FieldInit(&'r rlt::FieldInit),
     Expression(&'r rlt::Expression),
     Term(&'r rlt::Term),
     Reference(&'r rlt::Reference),
@@ -104,6 +109,9 @@ impl Located for RLTFamily<'_> {
             RLTFamily::Module(x) => x.location(),
             RLTFamily::Struct(x) => x.location(),
             RLTFamily::Enum(x) => x.location(),
+            RLTFamily::EnumVariant(x) => x.location(),
+            RLTFamily::TypeAlias(x) => x.location(),
+            RLTFamily::ForeignFunction(x) => x.location(),
             RLTFamily::Type(x) => x.location(),
             RLTFamily::TypeName(x) => x.location(),
             RLTFamily::TypedParameter(x) => x.location(),
@@ -116,6 +124,8 @@ impl Located for RLTFamily<'_> {
             RLTFamily::ExpressionBlock(x) => x.location(),
             RLTFamily::Operation(x) => x.location(),
             RLTFamily::Application(x) => x.location(),
+            RLTFamily::Record(x) => x.location(),
+            RLTFamily::FieldInit(x) => x.location(),
             RLTFamily::Expression(x) => x.location(),
             RLTFamily::Term(x) => x.location(),
             RLTFamily::Reference(x) => x.location(),
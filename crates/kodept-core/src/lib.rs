@@ -3,6 +3,7 @@ use std::ops::Deref;
 pub mod code_point;
 pub mod code_source;
 pub mod file_name;
+pub mod line_index;
 pub mod structure;
 
 pub mod macros {
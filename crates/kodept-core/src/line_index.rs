@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// Zero-based position of a character, columns are counted in both encodings
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Position {
+    pub line: u32,
+    /// Column in bytes
+    pub utf8_column: u32,
+    /// Column in UTF-16 code units, as expected by editors
+    pub utf16_column: u32,
+}
+
+/// Character that takes different number of units in UTF-8 and UTF-16
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+struct WideChar {
+    utf8_start: u32,
+    utf16_start: u32,
+    utf8_len: u8,
+    utf16_len: u8,
+}
+
+/// Converts byte offsets in the text to line and column positions and back.
+/// Lines are separated by `\n`, so with CRLF endings `\r` is the last character of a line
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct LineIndex {
+    /// Offset of the first byte of every line
+    line_starts: Vec<u32>,
+    /// Non-ASCII characters of every line that has them, sorted by position
+    wide_chars: HashMap<u32, Vec<WideChar>>,
+    len: u32,
+}
+
+impl WideChar {
+    fn utf8_end(&self) -> u32 {
+        self.utf8_start + u32::from(self.utf8_len)
+    }
+
+    fn utf16_end(&self) -> u32 {
+        self.utf16_start + u32::from(self.utf16_len)
+    }
+}
+
+impl LineIndex {
+    /// Texts larger than 4 GiB are not supported
+    #[must_use]
+    pub fn new(text: &str) -> Self {
+        let mut line_starts = vec![0];
+        let mut wide_chars: HashMap<u32, Vec<WideChar>> = HashMap::new();
+        let mut line_start = 0;
+        let mut utf16_column = 0;
+
+        for (offset, char) in text.char_indices() {
+            let offset = offset as u32;
+            let utf8_len = char.len_utf8() as u8;
+            let utf16_len = char.len_utf16() as u8;
+            if char == '\n' {
+                line_start = offset + 1;
+                utf16_column = 0;
+                line_starts.push(line_start);
+                continue;
+            }
+            if utf8_len > 1 {
+                wide_chars
+                    .entry(line_starts.len() as u32 - 1)
+                    .or_default()
+                    .push(WideChar {
+                        utf8_start: offset - line_start,
+                        utf16_start: utf16_column,
+                        utf8_len,
+                        utf16_len,
+                    });
+            }
+            utf16_column += u32::from(utf16_len);
+        }
+
+        Self {
+            line_starts,
+            wide_chars,
+            len: text.len() as u32,
+        }
+    }
+
+    #[must_use]
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Zero-based line containing the byte at `offset`
+    #[must_use]
+    pub fn line_of(&self, offset: u32) -> Option<u32> {
+        if offset > self.len {
+            return None;
+        }
+        let next_line = self.line_starts.partition_point(|it| *it <= offset);
+        Some(next_line as u32 - 1)
+    }
+
+    /// Byte range of the line including its terminator
+    #[must_use]
+    pub fn line_range(&self, line: u32) -> Option<Range<u32>> {
+        let start = *self.line_starts.get(line as usize)?;
+        let end = self
+            .line_starts
+            .get(line as usize + 1)
+            .copied()
+            .unwrap_or(self.len);
+        Some(start..end)
+    }
+
+    #[must_use]
+    pub fn position(&self, offset: u32) -> Option<Position> {
+        let line = self.line_of(offset)?;
+        let utf8_column = offset - self.line_starts[line as usize];
+        let utf16_column = match self.wide_chars.get(&line) {
+            None => utf8_column,
+            Some(chars) => {
+                let preceding = chars.partition_point(|it| it.utf8_start < utf8_column);
+                match preceding.checked_sub(1).map(|it| chars[it]) {
+                    None => utf8_column,
+                    // Offset points inside of the character
+                    Some(char) if utf8_column < char.utf8_end() => char.utf16_start,
+                    Some(char) => char.utf16_end() + (utf8_column - char.utf8_end()),
+                }
+            }
+        };
+        Some(Position {
+            line,
+            utf8_column,
+            utf16_column,
+        })
+    }
+
+    /// Byte offset of the column counted in bytes.
+    /// Fails if the column is past the end of the line or inside of a character
+    #[must_use]
+    pub fn offset(&self, line: u32, utf8_column: u32) -> Option<u32> {
+        let range = self.line_range(line)?;
+        let inside_char = self.wide_chars.get(&line).is_some_and(|chars| {
+            let preceding = chars.partition_point(|it| it.utf8_start < utf8_column);
+            preceding
+                .checked_sub(1)
+                .is_some_and(|it| utf8_column < chars[it].utf8_end())
+        });
+        let offset = range.start + utf8_column;
+        (offset <= self.line_end(line, range) && !inside_char).then_some(offset)
+    }
+
+    /// Byte offset of the column counted in UTF-16 code units.
+    /// Fails if the column is past the end of the line or inside of a surrogate pair
+    #[must_use]
+    pub fn offset_utf16(&self, line: u32, utf16_column: u32) -> Option<u32> {
+        let range = self.line_range(line)?;
+        let utf8_column = match self.wide_chars.get(&line) {
+            None => utf16_column,
+            Some(chars) => {
+                let preceding = chars.partition_point(|it| it.utf16_start < utf16_column);
+                match preceding.checked_sub(1).map(|it| chars[it]) {
+                    None => utf16_column,
+                    Some(char) if utf16_column < char.utf16_end() => return None,
+                    Some(char) => char.utf8_end() + (utf16_column - char.utf16_end()),
+                }
+            }
+        };
+        let offset = range.start + utf8_column;
+        (offset <= self.line_end(line, range)).then_some(offset)
+    }
+
+    /// Offset of the line terminator, or of the text end for the last line
+    fn line_end(&self, line: u32, range: Range<u32>) -> u32 {
+        if line as usize + 1 == self.line_starts.len() {
+            range.end
+        } else {
+            range.end - 1
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use crate::line_index::{LineIndex, Position};
+    use rstest::rstest;
+
+    fn position(line: u32, utf8_column: u32, utf16_column: u32) -> Position {
+        Position {
+            line,
+            utf8_column,
+            utf16_column,
+        }
+    }
+
+    #[rstest]
+    #[case::start("ab\ncd", 0, position(0, 0, 0))]
+    #[case::newline("ab\ncd", 2, position(0, 2, 2))]
+    #[case::next_line("ab\ncd", 3, position(1, 0, 0))]
+    #[case::end_of_text("ab\ncd", 5, position(1, 2, 2))]
+    #[case::crlf_terminator("ab\r\ncd", 2, position(0, 2, 2))]
+    #[case::after_crlf("ab\r\ncd", 5, position(1, 1, 1))]
+    #[case::after_cyrillic("яб x", 5, position(0, 5, 3))]
+    #[case::after_emoji("a😀b", 5, position(0, 5, 3))]
+    #[case::inside_emoji("a😀b", 3, position(0, 3, 1))]
+    #[case::multibyte_then_crlf("é\r\né", 6, position(1, 2, 1))]
+    fn test_position(#[case] text: &str, #[case] offset: u32, #[case] expected: Position) {
+        let index = LineIndex::new(text);
+
+        assert_eq!(index.position(offset), Some(expected));
+    }
+
+    #[rstest]
+    #[case::ascii("ab\ncd", 1, 1, Some(4))]
+    #[case::crlf("ab\r\ncd", 0, 2, Some(2))]
+    #[case::past_line_end("ab\r\ncd", 0, 4, None)]
+    #[case::past_text_end("ab\ncd", 1, 3, None)]
+    #[case::missing_line("ab", 1, 0, None)]
+    #[case::after_emoji("a😀b", 0, 3, Some(5))]
+    #[case::inside_surrogate_pair("a😀b", 0, 2, None)]
+    fn test_offset_utf16(
+        #[case] text: &str,
+        #[case] line: u32,
+        #[case] column: u32,
+        #[case] expected: Option<u32>,
+    ) {
+        let index = LineIndex::new(text);
+
+        assert_eq!(index.offset_utf16(line, column), expected);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let text = "module Ä {\r\n    val s = \"😀 ok\"\r\n}\n";
+        let index = LineIndex::new(text);
+
+        for (offset, _) in text.char_indices() {
+            let offset = offset as u32;
+            let position = index.position(offset).unwrap();
+            assert_eq!(index.offset(position.line, position.utf8_column), Some(offset));
+            assert_eq!(
+                index.offset_utf16(position.line, position.utf16_column),
+                Some(offset)
+            );
+        }
+        assert_eq!(index.line_count(), 4);
+        assert_eq!(index.offset(1, 14), None);
+    }
+}
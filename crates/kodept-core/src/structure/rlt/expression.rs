@@ -29,6 +29,26 @@ pub enum Operation {
     },
     Application(Box<Application>),
     Expression(Expression),
+    Record(Box<Record>),
+    Update {
+        record: Box<Operation>,
+        keyword: Keyword,
+        fields: Enclosed<Box<[FieldInit]>>,
+    },
+}
+
+/// Struct construction, e.g. `Point { x = 1, y = 2 }`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub id: TypeName,
+    pub fields: Enclosed<Box<[FieldInit]>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldInit {
+    pub id: Identifier,
+    pub equals: Symbol,
+    pub value: Operation,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -67,10 +87,24 @@ impl Located for Operation {
             Operation::Binary { operation, .. } => operation.location(),
             Operation::Application(x) => x.location(),
             Operation::Expression(x) => x.location(),
+            Operation::Record(x) => x.location(),
+            Operation::Update { keyword, .. } => keyword.location(),
         }
     }
 }
 
+impl Located for Record {
+    fn location(&self) -> CodePoint {
+        self.id.location()
+    }
+}
+
+impl Located for FieldInit {
+    fn location(&self) -> CodePoint {
+        self.id.location()
+    }
+}
+
 impl Located for Expression {
     fn location(&self) -> CodePoint {
         match self {
@@ -10,6 +10,7 @@ use crate::structure::rlt::types::{Parameter, Type, TypedParameter};
 pub struct BodiedFunction {
     pub keyword: Keyword,
     pub id: Identifier,
+    pub generics: Option<Enclosed<Box<[TypeName]>>>,
     pub params: Option<Enclosed<Box<[Parameter]>>>,
     pub return_type: Option<(Symbol, Type)>,
     pub body: Box<Body>,
@@ -19,10 +20,18 @@ pub struct BodiedFunction {
 pub struct AbstractFunction {
     pub keyword: Keyword,
     pub id: Identifier,
+    pub generics: Option<Enclosed<Box<[TypeName]>>>,
     pub params: Option<Enclosed<Box<[TypedParameter]>>>,
     pub return_type: Option<(Symbol, Type)>,
 }
 
+/// Function implemented outside of Kodept, e.g. in C
+#[derive(Clone, Debug, PartialEq)]
+pub struct ForeignFunction {
+    pub keyword: Keyword,
+    pub function: AbstractFunction,
+}
+
 #[derive(Clone, Debug, PartialEq, From)]
 pub enum Function {
     Abstract(AbstractFunction),
@@ -41,6 +50,12 @@ impl Located for AbstractFunction {
     }
 }
 
+impl Located for ForeignFunction {
+    fn location(&self) -> CodePoint {
+        self.keyword.location()
+    }
+}
+
 impl Located for Function {
     fn location(&self) -> CodePoint {
         match self {
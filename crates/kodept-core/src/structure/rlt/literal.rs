@@ -12,9 +12,19 @@ pub enum Literal {
     Floating(Span),
     Char(Span),
     String(Span),
+    Interpolated(InterpolatedString),
     Tuple(Enclosed<Box<[Operation]>>),
 }
 
+/// String literal with embedded expressions, e.g. `"Hello, ${name}!"`
+#[derive(Clone, Debug, PartialEq)]
+pub struct InterpolatedString {
+    /// Parts of the literal around expressions, including quotes and `${`, `}`.
+    /// There is always one more fragment than expressions.
+    pub fragments: Box<[Literal]>,
+    pub expressions: Box<[Operation]>,
+}
+
 impl Located for Literal {
     fn location(&self) -> CodePoint {
         match self {
@@ -24,7 +34,16 @@ impl Located for Literal {
             Literal::Floating(x) => x.location(),
             Literal::Char(x) => x.location(),
             Literal::String(x) => x.location(),
+            Literal::Interpolated(x) => x.location(),
             Literal::Tuple(x) => x.left.location(),
         }
     }
 }
+
+impl Located for InterpolatedString {
+    fn location(&self) -> CodePoint {
+        let start = self.fragments[0].location();
+        let end = self.fragments[self.fragments.len() - 1].location();
+        CodePoint::new(end.offset + end.length - start.offset, start.offset)
+    }
+}
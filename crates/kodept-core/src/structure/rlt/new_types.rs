@@ -29,7 +29,7 @@ macro_rules! make_wrappers {
     };
 }
 
-make_wrappers!(Keyword, Symbol, TypeName, Identifier,);
+make_wrappers!(Keyword, Symbol, TypeName, Identifier, Erroneous,);
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum UnaryOperationSymbol {
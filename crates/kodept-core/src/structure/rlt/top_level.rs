@@ -2,14 +2,15 @@ use derive_more::From;
 
 use crate::code_point::CodePoint;
 use crate::structure::Located;
-use crate::structure::rlt::function::BodiedFunction;
+use crate::structure::rlt::function::{BodiedFunction, ForeignFunction};
 use crate::structure::rlt::new_types::*;
-use crate::structure::rlt::types::TypedParameter;
+use crate::structure::rlt::types::{Type, TypedParameter};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Struct {
     pub keyword: Keyword,
     pub id: TypeName,
+    pub generics: Option<Enclosed<Box<[TypeName]>>>,
     pub parameters: Option<Enclosed<Box<[TypedParameter]>>>,
     pub body: Option<Enclosed<Box<[BodiedFunction]>>>,
 }
@@ -19,20 +20,46 @@ pub enum Enum {
     Stack {
         keyword: Keyword,
         id: TypeName,
-        contents: Option<Enclosed<Box<[TypeName]>>>,
+        generics: Option<Enclosed<Box<[TypeName]>>>,
+        contents: Option<Enclosed<Box<[EnumVariant]>>>,
     },
     Heap {
         keyword: Keyword,
         id: TypeName,
-        contents: Option<Enclosed<Box<[TypeName]>>>,
+        generics: Option<Enclosed<Box<[TypeName]>>>,
+        contents: Option<Enclosed<Box<[EnumVariant]>>>,
     },
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum VariantPayload {
+    Positional(Enclosed<Box<[Type]>>),
+    Named(Enclosed<Box<[TypedParameter]>>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnumVariant {
+    pub id: TypeName,
+    pub payload: Option<VariantPayload>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeAlias {
+    pub keyword: Keyword,
+    pub id: TypeName,
+    pub equals: Symbol,
+    pub aliased: Type,
+}
+
 #[derive(Debug, Clone, PartialEq, From)]
 pub enum TopLevelNode {
     Enum(Enum),
     Struct(Struct),
+    TypeAlias(TypeAlias),
+    ForeignFunction(ForeignFunction),
     BodiedFunction(BodiedFunction),
+    /// Tokens skipped by the parser while recovering from a syntax error
+    Error(Erroneous),
 }
 
 impl Located for Struct {
@@ -50,16 +77,37 @@ impl Located for Enum {
     }
 }
 
+impl Located for EnumVariant {
+    fn location(&self) -> CodePoint {
+        self.id.location()
+    }
+}
+
+impl Located for TypeAlias {
+    fn location(&self) -> CodePoint {
+        self.keyword.location()
+    }
+}
+
 impl Located for TopLevelNode {
     fn location(&self) -> CodePoint {
         match self {
             TopLevelNode::Enum(x) => x.location(),
             TopLevelNode::Struct(x) => x.location(),
+            TopLevelNode::TypeAlias(x) => x.location(),
+            TopLevelNode::ForeignFunction(x) => x.location(),
             TopLevelNode::BodiedFunction(x) => x.location(),
+            TopLevelNode::Error(x) => x.location(),
         }
     }
 }
 
+impl TopLevelNode {
+    pub fn is_error(&self) -> bool {
+        matches!(self, TopLevelNode::Error(_))
+    }
+}
+
 impl Enum {
     pub fn id(&self) -> &TypeName {
         match self {
@@ -8,7 +8,13 @@ use crate::structure::rlt::new_types::*;
 pub enum Type {
     Reference(TypeName),
     #[from(ignore)]
-    Tuple(Enclosed<Box<[Type]>>)
+    Tuple(Enclosed<Box<[Type]>>),
+    /// Type constructor applied to arguments, e.g. `Option<Int>`
+    #[from(ignore)]
+    Application {
+        id: TypeName,
+        arguments: Enclosed<Box<[Type]>>,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -32,7 +38,8 @@ impl Located for Type {
     fn location(&self) -> CodePoint {
         match self {
             Type::Reference(x) => x.location(),
-            Type::Tuple(x) => x.left.location()
+            Type::Tuple(x) => x.left.location(),
+            Type::Application { id, .. } => id.location(),
         }
     }
 }
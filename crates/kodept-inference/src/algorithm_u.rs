@@ -67,6 +67,7 @@ impl AlgorithmU {
             ),
             (Tuple(t1), Tuple(t2)) => Self::unify_vec(&t1.0, &t2.0),
             (Pointer(t1), Pointer(t2)) => t1.unify(t2),
+            (App(n1, a1), App(n2, a2)) if n1 == n2 => Self::unify_vec(a1, a2),
             _ => Err(UnificationFail(lhs.clone(), rhs.clone())),
         }
     }
@@ -97,7 +98,7 @@ mod tests {
     use nonempty_collections::nev;
 
     use crate::algorithm_u::AlgorithmUError;
-    use crate::r#type::{fun, fun1, MonomorphicType, PrimitiveType, Tuple, TVar, var};
+    use crate::r#type::{app, fun, fun1, MonomorphicType, PrimitiveType, Tuple, TVar, var};
     use crate::r#type::MonomorphicType::Constant;
     use crate::substitution::Substitutions;
     use crate::traits::Substitutable;
@@ -265,4 +266,25 @@ mod tests {
             fun1(fun1(h.clone(), h.clone()), fun1(h.clone(), h))
         )
     }
+
+    #[test]
+    fn test_type_constructors_unify_arguments() {
+        let a = TVar(0);
+        let lhs = app("Option", [var(a)]);
+        let rhs = app("Option", [PrimitiveType::Integral.into()]);
+
+        let s = lhs.unify(&rhs).unwrap();
+        assert_eq!(lhs.substitute(&s), rhs);
+    }
+
+    #[test]
+    fn test_type_constructors_with_different_arity_should_not_unify() {
+        let lhs = app("Option", [var(0)]);
+        let rhs = app("Option", [var(1), var(2)]);
+
+        assert!(matches!(
+            lhs.unify(&rhs),
+            Err(AlgorithmUError::UnificationMismatch(_))
+        ));
+    }
 }
@@ -8,12 +8,15 @@ use tracing::debug;
 use crate::algorithm_u::AlgorithmUError;
 use crate::algorithm_w::AlgorithmWError::UnknownVar;
 use crate::assumption::AssumptionSet;
-use crate::constraint::{eq_cst, explicit_cst, implicit_cst, Constraint, ConstraintsSolverError};
+use crate::class::{ClassEnvironment, Predicate, FRACTIONAL_CLASS, NUM_CLASS};
+use crate::constraint::{
+    class_cst, eq_cst, explicit_cst, implicit_cst, Constraint, ConstraintsSolverError,
+};
 use crate::language::{Language, Literal, Special, Var};
 use crate::r#type::PrimitiveType::Boolean;
-use crate::r#type::{fun1, MonomorphicType, PolymorphicType, PrimitiveType, TVar, Tuple};
+use crate::r#type::{fun1, MonomorphicType, PolymorphicType, TVar, Tuple};
 use crate::substitution::Substitutions;
-use crate::traits::{EnvironmentProvider, Substitutable};
+use crate::traits::{EnvironmentProvider, FreeTypeVars, Substitutable};
 use crate::{language, InferState};
 
 #[derive(Debug, Error)]
@@ -49,16 +52,8 @@ impl<'e> AlgorithmW<'e> {
             Language::Let(x) => self.apply_let(x),
             Language::Special(x) => self.apply_special(x),
             Language::Literal(x) => match x {
-                Literal::Integral => Ok((
-                    AssumptionSet::empty(),
-                    vec![],
-                    PrimitiveType::Integral.into(),
-                )),
-                Literal::Floating => Ok((
-                    AssumptionSet::empty(),
-                    vec![],
-                    PrimitiveType::Floating.into(),
-                )),
+                Literal::Integral => self.apply_overloaded_literal(NUM_CLASS),
+                Literal::Floating => self.apply_overloaded_literal(FRACTIONAL_CLASS),
                 Literal::Tuple(vec) => self.apply_tuple(vec),
             },
         }
@@ -121,9 +116,15 @@ impl<'e> AlgorithmW<'e> {
         let im_cs = as2
             .get(&bind.var)
             .iter()
-            .chain(as1.get(&bind.var).iter()) // support for fix
             .map(|it| implicit_cst(it.clone(), self.monomorphic_set.clone(), t1.clone()))
             .collect();
+        // support for fix: recursive usages are monomorphic,
+        // otherwise several of them wait for each other to be generalized
+        let rec_cs = as1
+            .get(&bind.var)
+            .iter()
+            .map(|it| eq_cst(it.clone(), t1.clone()))
+            .collect();
         let bound = bind.ty.as_ref().map_or(vec![], |it| {
             vec![implicit_cst(
                 it.clone(),
@@ -132,7 +133,16 @@ impl<'e> AlgorithmW<'e> {
             )]
         });
 
-        Ok((as_, concat([cs1, cs2, im_cs, bound]), t2))
+        Ok((as_, concat([cs1, cs2, im_cs, rec_cs, bound]), t2))
+    }
+
+    fn apply_overloaded_literal(&mut self, class: &str) -> AWResult {
+        let fresh = self.env.new_var();
+        Ok((
+            AssumptionSet::empty(),
+            vec![class_cst(class, fresh)],
+            fresh.into(),
+        ))
     }
 
     fn apply_tuple(&mut self, tuple: &[Language]) -> AWResult {
@@ -176,7 +186,8 @@ impl Language {
         &self,
         context: &mut AlgorithmW,
         table: &impl EnvironmentProvider<Var, Error = E>,
-    ) -> Result<(Substitutions, MonomorphicType), CompoundInferError<E>> {
+        classes: &ClassEnvironment,
+    ) -> Result<(Substitutions, Vec<Predicate>, MonomorphicType), CompoundInferError<E>> {
         let (a, c, t) = context.apply(self)?;
         let (errors, not_found, explicits) = a.keys().fold(
             (Vec::new(), Vec::new(), Vec::new()),
@@ -205,34 +216,55 @@ impl Language {
 
         debug!("Inferred raw type and constraints: ");
         debug!("{c:?} ++ {explicits:?}, {t}");
-        let substitutions = Constraint::solve(concat([c, explicits]), context.env)
-            .map_err(AlgorithmWError::FailedConstraints)?;
+        let (substitutions, predicates) =
+            Constraint::solve(concat([c, explicits]), context.env, classes)
+                .map_err(AlgorithmWError::FailedConstraints)?;
         let t = t.substitute(&substitutions);
-        debug!("Inferred type and substitutions: ");
-        debug!("{}, {}", substitutions, t);
-        Ok((substitutions, t))
+        debug!("Inferred type, predicates and substitutions: ");
+        debug!("{}, {:?}, {}", substitutions, predicates, t);
+        Ok((substitutions, predicates, t))
     }
 
     pub(crate) fn infer_with_env<E>(
         &self,
         context: &impl EnvironmentProvider<Var, Error = E>,
+        classes: &ClassEnvironment,
         env: &mut InferState,
     ) -> Result<PolymorphicType, CompoundInferError<E>> {
         let mut ctx = AlgorithmW {
             monomorphic_set: Default::default(),
             env,
         };
-        match self.infer_w(&mut ctx, context) {
-            Ok((s, t)) => Ok(t.substitute(&s).normalize()),
-            Err(e) => Err(e),
+        let (s, predicates, t) = self.infer_w(&mut ctx, context, classes)?;
+        let t = t.substitute(&s);
+        let free = t.free_types();
+        let (ambiguous, context): (Vec<_>, Vec<_>) = predicates
+            .into_iter()
+            .partition(|it| !it.free_types().is_subset(&free));
+        if !ambiguous.is_empty() {
+            return Err(AlgorithmWError::FailedConstraints(
+                ConstraintsSolverError::AmbiguousPredicates(ambiguous),
+            )
+            .into());
         }
+        Ok(t.generalize(&HashSet::new())
+            .with_context(context)
+            .normalize())
     }
 
     pub fn infer<E>(
         &self,
         table: &impl EnvironmentProvider<Var, Error = E>,
     ) -> Result<PolymorphicType, CompoundInferError<E>> {
-        self.infer_with_env(table, &mut InferState::default())
+        self.infer_with_classes(table, &ClassEnvironment::prelude())
+    }
+
+    pub fn infer_with_classes<E>(
+        &self,
+        table: &impl EnvironmentProvider<Var, Error = E>,
+        classes: &ClassEnvironment,
+    ) -> Result<PolymorphicType, CompoundInferError<E>> {
+        self.infer_with_env(table, classes, &mut InferState::default())
     }
 }
 
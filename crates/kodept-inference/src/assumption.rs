@@ -7,8 +7,10 @@ use std::ops::Add;
 
 use itertools::Itertools;
 
+use crate::class::{predicate, EQ_CLASS, NUM_CLASS, ORD_CLASS};
 use crate::language::Var;
-use crate::r#type::{MonomorphicType, PolymorphicType};
+use crate::r#type::PrimitiveType::Boolean;
+use crate::r#type::{fun1, var, MonomorphicType, PolymorphicType, TVar};
 use crate::substitution::Substitutions;
 use crate::traits::{EnvironmentProvider, Substitutable};
 
@@ -152,6 +154,46 @@ impl Environment {
     pub fn iter(&self) -> impl Iterator<Item = (&Var, &PolymorphicType)> {
         self.0.iter()
     }
+
+    /// Signatures of built-in overloaded operators from `Prelude`
+    pub fn prelude() -> Self {
+        let a = var(0);
+        let unary = |class: &str| {
+            PolymorphicType::from(fun1(a.clone(), a.clone()))
+                .quantified([TVar(0)])
+                .with_context([predicate(class, a.clone())])
+        };
+        let binary = |class: &str, output: MonomorphicType| {
+            PolymorphicType::from(fun1(a.clone(), fun1(a.clone(), output)))
+                .quantified([TVar(0)])
+                .with_context([predicate(class, a.clone())])
+        };
+        let logic = PolymorphicType::from(fun1(Boolean, fun1(Boolean, Boolean)));
+
+        let mut env = Self::empty();
+        env.push("__neg_internal", unary(NUM_CLASS));
+        env.push("__plus_internal", unary(NUM_CLASS));
+        env.push("__not_internal", fun1(Boolean, Boolean));
+        for name in [
+            "__add_internal",
+            "__sub_internal",
+            "__mul_internal",
+            "__div_internal",
+            "__mod_internal",
+            "__pow_internal",
+        ] {
+            env.push(name, binary(NUM_CLASS, a.clone()));
+        }
+        for name in ["__lt_internal", "__le_internal", "__gt_internal", "__ge_internal"] {
+            env.push(name, binary(ORD_CLASS, Boolean.into()));
+        }
+        for name in ["__eq_internal", "__neq_internal"] {
+            env.push(name, binary(EQ_CLASS, Boolean.into()));
+        }
+        env.push("__and_internal", logic.clone());
+        env.push("__or_internal", logic);
+        env
+    }
 }
 
 impl TypeTableOps for Environment {
@@ -0,0 +1,386 @@
+use std::collections::HashMap;
+use std::fmt::{Debug, Display, Formatter};
+
+use itertools::Itertools;
+use thiserror::Error;
+
+use crate::r#type::{MonomorphicType, PrimitiveType, TVar};
+use crate::substitution::Substitutions;
+use crate::traits::{FreeTypeVars, Substitutable};
+
+pub const EQ_CLASS: &str = "Eq";
+pub const ORD_CLASS: &str = "Ord";
+pub const NUM_CLASS: &str = "Num";
+pub const FRACTIONAL_CLASS: &str = "Fractional";
+
+/// States that type `ty` implements class `class`
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Predicate {
+    pub class: String,
+    pub ty: MonomorphicType,
+}
+
+/// Instance of a class, which can be used if all predicates from context hold
+#[derive(Clone, PartialEq)]
+pub struct Instance {
+    pub context: Vec<Predicate>,
+    pub head: Predicate,
+}
+
+#[derive(Clone, Default, Debug)]
+struct Class {
+    superclasses: Vec<String>,
+    instances: Vec<Instance>,
+}
+
+/// Holds all known classes and their instances
+#[derive(Clone, Default, Debug)]
+pub struct ClassEnvironment {
+    classes: HashMap<String, Class>,
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ClassEnvironmentError {
+    #[error("Class `{0}` is not defined")]
+    UnknownClass(String),
+    #[error("Class `{0}` is already defined")]
+    DuplicatedClass(String),
+    #[error("Instance `{0}` overlaps with an existing one")]
+    OverlappingInstance(Instance),
+    #[error("No instance for `{0}`")]
+    NoInstance(Predicate),
+}
+
+pub fn predicate(class: impl Into<String>, ty: impl Into<MonomorphicType>) -> Predicate {
+    Predicate {
+        class: class.into(),
+        ty: ty.into(),
+    }
+}
+
+/// One-way unification: only variables from `pattern` are bound
+fn match_type(
+    pattern: &MonomorphicType,
+    target: &MonomorphicType,
+    bound: &mut HashMap<TVar, MonomorphicType>,
+) -> bool {
+    use MonomorphicType::*;
+
+    match (pattern, target) {
+        (Var(v), _) => match bound.get(v) {
+            None => {
+                bound.insert(*v, target.clone());
+                true
+            }
+            Some(t) => t == target,
+        },
+        (Primitive(a), Primitive(b)) => a == b,
+        (Constant(a), Constant(b)) => a == b,
+        (Fn(i1, o1), Fn(i2, o2)) => match_type(i1, i2, bound) && match_type(o1, o2, bound),
+        (Tuple(a), Tuple(b)) if a.0.len() == b.0.len() => a
+            .0
+            .iter()
+            .zip(b.0.iter())
+            .all(|(a, b)| match_type(a, b, bound)),
+        (Pointer(a), Pointer(b)) => match_type(a, b, bound),
+        (App(n1, a1), App(n2, a2)) if n1 == n2 && a1.len() == a2.len() => a1
+            .iter()
+            .zip(a2.iter())
+            .all(|(a, b)| match_type(a, b, bound)),
+        _ => false,
+    }
+}
+
+impl Predicate {
+    /// Predicate is in head normal form if it constrains only a type variable
+    pub fn in_hnf(&self) -> bool {
+        matches!(self.ty, MonomorphicType::Var(_))
+    }
+
+    fn matches(&self, other: &Predicate) -> Option<Substitutions> {
+        if self.class != other.class {
+            return None;
+        }
+        let mut bound = HashMap::new();
+        match_type(&self.ty, &other.ty, &mut bound).then(|| Substitutions::from_iter(bound))
+    }
+}
+
+impl Instance {
+    pub fn new(context: Vec<Predicate>, head: Predicate) -> Self {
+        Self { context, head }
+    }
+}
+
+impl ClassEnvironment {
+    pub fn add_class(
+        &mut self,
+        name: impl Into<String>,
+        superclasses: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Result<(), ClassEnvironmentError> {
+        let name = name.into();
+        if self.classes.contains_key(&name) {
+            return Err(ClassEnvironmentError::DuplicatedClass(name));
+        }
+        let superclasses: Vec<_> = superclasses.into_iter().map(Into::into).collect();
+        if let Some(unknown) = superclasses
+            .iter()
+            .find(|it| !self.classes.contains_key(*it))
+        {
+            return Err(ClassEnvironmentError::UnknownClass(unknown.clone()));
+        }
+        self.classes.insert(
+            name,
+            Class {
+                superclasses,
+                instances: vec![],
+            },
+        );
+        Ok(())
+    }
+
+    pub fn add_instance(&mut self, instance: Instance) -> Result<(), ClassEnvironmentError> {
+        let Some(class) = self.classes.get_mut(&instance.head.class) else {
+            return Err(ClassEnvironmentError::UnknownClass(instance.head.class));
+        };
+        if class
+            .instances
+            .iter()
+            .any(|it| it.head.ty.unify(&instance.head.ty).is_ok())
+        {
+            return Err(ClassEnvironmentError::OverlappingInstance(instance));
+        }
+        class.instances.push(instance);
+        Ok(())
+    }
+
+    fn superclasses(&self, class: &str) -> &[String] {
+        self.classes
+            .get(class)
+            .map_or(&[], |it| it.superclasses.as_slice())
+    }
+
+    /// All predicates that follow from `p` using superclass relation, including `p` itself
+    pub fn by_super(&self, p: &Predicate) -> Vec<Predicate> {
+        let mut result = vec![p.clone()];
+        for class in self.superclasses(&p.class) {
+            result.extend(self.by_super(&predicate(class, p.ty.clone())));
+        }
+        result
+    }
+
+    /// Predicates that should hold to satisfy `p` using some instance
+    pub fn by_instance(&self, p: &Predicate) -> Option<Vec<Predicate>> {
+        let class = self.classes.get(&p.class)?;
+        class.instances.iter().find_map(|it| {
+            let s = it.head.matches(p)?;
+            Some(it.context.substitute(&s))
+        })
+    }
+
+    /// Checks if `p` holds whenever all predicates from `given` hold
+    pub fn entails(&self, given: &[Predicate], p: &Predicate) -> bool {
+        given.iter().any(|it| self.by_super(it).contains(p))
+            || self
+                .by_instance(p)
+                .is_some_and(|ps| ps.iter().all(|it| self.entails(given, it)))
+    }
+
+    /// Reduces `p` to predicates about type variables only
+    pub fn to_hnf(&self, p: Predicate) -> Result<Vec<Predicate>, ClassEnvironmentError> {
+        if p.in_hnf() {
+            return Ok(vec![p]);
+        }
+        match self.by_instance(&p) {
+            None => Err(ClassEnvironmentError::NoInstance(p)),
+            Some(ps) => ps
+                .into_iter()
+                .map(|it| self.to_hnf(it))
+                .flatten_ok()
+                .try_collect(),
+        }
+    }
+
+    /// Reduces predicates to head normal form and removes ones entailed by others
+    pub fn simplify(
+        &self,
+        ps: impl IntoIterator<Item = Predicate>,
+    ) -> Result<Vec<Predicate>, ClassEnvironmentError> {
+        let hnf: Vec<_> = ps
+            .into_iter()
+            .map(|it| self.to_hnf(it))
+            .flatten_ok()
+            .try_collect()?;
+        let mut result: Vec<Predicate> = hnf.into_iter().unique().collect();
+        let mut index = 0;
+        while index < result.len() {
+            let p = result.remove(index);
+            if !self.entails(&result, &p) {
+                result.insert(index, p);
+                index += 1;
+            }
+        }
+        Ok(result)
+    }
+
+    /// Classes and instances of built-in types available without any import
+    pub fn prelude() -> Self {
+        use PrimitiveType::*;
+
+        let mut env = Self::default();
+        let classes: [(&str, &[&str]); 4] = [
+            (EQ_CLASS, &[]),
+            (ORD_CLASS, &[EQ_CLASS]),
+            (NUM_CLASS, &[]),
+            (FRACTIONAL_CLASS, &[NUM_CLASS]),
+        ];
+        let instances = [
+            (EQ_CLASS, Integral),
+            (EQ_CLASS, Floating),
+            (EQ_CLASS, Boolean),
+            (ORD_CLASS, Integral),
+            (ORD_CLASS, Floating),
+            (NUM_CLASS, Integral),
+            (NUM_CLASS, Floating),
+            (FRACTIONAL_CLASS, Floating),
+        ];
+
+        for (name, superclasses) in classes {
+            env.add_class(name, superclasses.iter().copied())
+                .expect("Prelude classes should be well-formed");
+        }
+        for (class, ty) in instances {
+            env.add_instance(Instance::new(vec![], predicate(class, ty)))
+                .expect("Prelude instances should not overlap");
+        }
+        env
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Substitutable for Predicate {
+    type Output = Self;
+
+    fn substitute(&self, subst: &Substitutions) -> Self::Output {
+        Self {
+            class: self.class.clone(),
+            ty: self.ty.substitute(subst),
+        }
+    }
+}
+
+impl FreeTypeVars for &Predicate {
+    fn free_types(self) -> std::collections::HashSet<TVar> {
+        self.ty.free_types()
+    }
+}
+
+impl Display for Predicate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.ty {
+            MonomorphicType::Fn(_, _) | MonomorphicType::Pointer(_) => {
+                write!(f, "{} ({})", self.class, self.ty)
+            }
+            _ => write!(f, "{} {}", self.class, self.ty),
+        }
+    }
+}
+
+impl Display for Instance {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.context.as_slice() {
+            [] => write!(f, "{}", self.head),
+            [p] => write!(f, "{p} ⇒ {}", self.head),
+            ps => write!(f, "({}) ⇒ {}", ps.iter().join(", "), self.head),
+        }
+    }
+}
+
+impl Debug for Predicate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self}")
+    }
+}
+
+impl Debug for Instance {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self}")
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use crate::class::{
+        predicate, ClassEnvironment, ClassEnvironmentError, Instance, EQ_CLASS, NUM_CLASS,
+        ORD_CLASS,
+    };
+    use crate::r#type::PrimitiveType::{Boolean, Integral};
+    use crate::r#type::{var, Tuple};
+
+    #[test]
+    fn test_superclass_entailment() {
+        let env = ClassEnvironment::prelude();
+
+        assert!(env.entails(&[predicate(ORD_CLASS, var(0))], &predicate(EQ_CLASS, var(0))));
+        assert!(!env.entails(&[predicate(EQ_CLASS, var(0))], &predicate(ORD_CLASS, var(0))));
+        assert!(env.entails(&[], &predicate(NUM_CLASS, Integral)));
+        assert!(!env.entails(&[], &predicate(NUM_CLASS, Boolean)));
+    }
+
+    #[test]
+    fn test_simplify_removes_entailed() {
+        let env = ClassEnvironment::prelude();
+        let result = env
+            .simplify([
+                predicate(EQ_CLASS, var(0)),
+                predicate(ORD_CLASS, var(0)),
+                predicate(NUM_CLASS, Integral),
+                predicate(ORD_CLASS, var(0)),
+            ])
+            .unwrap();
+
+        assert_eq!(result, vec![predicate(ORD_CLASS, var(0))]);
+    }
+
+    #[test]
+    fn test_simplify_through_instance_context() {
+        let mut env = ClassEnvironment::prelude();
+        let pair = Tuple(vec![var(0), var(1)]);
+        env.add_instance(Instance::new(
+            vec![predicate(EQ_CLASS, var(0)), predicate(EQ_CLASS, var(1))],
+            predicate(EQ_CLASS, pair),
+        ))
+        .unwrap();
+
+        let result = env
+            .simplify([predicate(EQ_CLASS, Tuple(vec![var(5), Integral.into()]))])
+            .unwrap();
+        assert_eq!(result, vec![predicate(EQ_CLASS, var(5))]);
+    }
+
+    #[test]
+    fn test_unresolved_predicate() {
+        let env = ClassEnvironment::prelude();
+        let result = env.simplify([predicate(NUM_CLASS, Boolean)]);
+
+        assert_eq!(
+            result,
+            Err(ClassEnvironmentError::NoInstance(predicate(
+                NUM_CLASS, Boolean
+            )))
+        );
+    }
+
+    #[test]
+    fn test_overlapping_instance() {
+        let mut env = ClassEnvironment::prelude();
+        let result = env.add_instance(Instance::new(vec![], predicate(NUM_CLASS, Integral)));
+
+        assert!(matches!(
+            result,
+            Err(ClassEnvironmentError::OverlappingInstance(_))
+        ));
+    }
+}
@@ -6,10 +6,11 @@ use itertools::Either::{Left, Right};
 use itertools::{Either, Itertools};
 use thiserror::Error;
 
-use Constraint::{ExplicitInstance, ImplicitInstance};
-use ConstraintsSolverError::AlgorithmU;
+use Constraint::{Class, ExplicitInstance, ImplicitInstance};
+use ConstraintsSolverError::{AlgorithmU, AmbiguousPredicates, Unresolved};
 
 use crate::algorithm_u::AlgorithmUError;
+use crate::class::{predicate, ClassEnvironment, ClassEnvironmentError, Predicate};
 use crate::constraint::Constraint::Eq;
 use crate::constraint::ConstraintsSolverError::Ambiguous;
 use crate::r#type::{MonomorphicType, PolymorphicType, TVar};
@@ -22,6 +23,9 @@ pub enum ConstraintsSolverError {
     #[error(transparent)]
     AlgorithmU(#[from] AlgorithmUError),
     Ambiguous(Vec<Constraint>),
+    #[error(transparent)]
+    Unresolved(#[from] ClassEnvironmentError),
+    AmbiguousPredicates(Vec<Predicate>),
 }
 
 #[derive(Debug, PartialEq, Clone, Display)]
@@ -47,6 +51,15 @@ pub enum Constraint {
         ctx: HashSet<TVar>,
         t2: MonomorphicType,
     },
+    /// t should be an instance of class
+    Class(Predicate),
+}
+
+struct Solver<'a> {
+    env: &'a mut InferState,
+    classes: &'a ClassEnvironment,
+    deferred: Vec<Predicate>,
+    generalized: HashSet<TVar>,
 }
 
 impl Display for ConstraintsSolverError {
@@ -68,9 +81,18 @@ impl Display for ConstraintsSolverError {
                         ImplicitInstance { t1, ctx, t2 } => {
                             write!(f, "Cannot match expected type `{t1}` with generalization of type `{t2}` in context {{{}}}", ctx.iter().join(", "))?;
                         }
+                        Class(p) => {
+                            write!(f, "Cannot resolve constraint `{p}`")?;
+                        }
                     }
                 }
             }
+            Unresolved(x) => write!(f, "{x}")?,
+            AmbiguousPredicates(ps) => write!(
+                f,
+                "Ambiguous type variables in constraints: {}",
+                ps.iter().map(|it| format!("`{it}`")).join(", ")
+            )?,
         }
         Ok(())
     }
@@ -86,48 +108,99 @@ impl Constraint {
                 let active = cs.active_vars();
                 (&v1 & &active).is_empty()
             }
+            Class(_) => true,
         }
     }
 
-    fn solve_pair(
-        c: Constraint,
-        env: &mut InferState,
-    ) -> Result<Either<Substitutions, Constraint>, ConstraintsSolverError> {
-        match c {
-            Eq(EqConstraint { t1, t2 }) => Ok(Left(t1.unify(&t2)?)),
-            ExplicitInstance { t, s } => {
-                let t2 = s.instantiate(env);
-                Ok(Right(Eq(EqConstraint { t1: t, t2 })))
-            }
-            ImplicitInstance { t1, ctx, t2 } => {
-                let s = t2.generalize(&ctx);
-                Ok(Right(ExplicitInstance { t: t1, s }))
-            }
-        }
-    }
-
+    /// Solves constraints, returning substitutions and simplified predicates
+    /// that are not captured by any generalization
     pub(crate) fn solve(
         constraints: Vec<Constraint>,
         env: &mut InferState,
-    ) -> Result<Substitutions, ConstraintsSolverError> {
+        classes: &ClassEnvironment,
+    ) -> Result<(Substitutions, Vec<Predicate>), ConstraintsSolverError> {
         let mut cs = VecDeque::from(constraints);
         let mut s0 = Substitutions::empty();
+        let mut solver = Solver {
+            env,
+            classes,
+            deferred: vec![],
+            generalized: HashSet::new(),
+        };
 
         // solver should always find suitable constraint to solve
         while let Some(c) = cs.pop_back() {
             if Self::solvable(&c, &cs) {
-                match Self::solve_pair(c, env)? {
+                match solver.solve_pair(c)? {
                     Left(s) => {
                         cs = cs.make_contiguous().substitute(&s).into();
+                        solver.deferred = solver.deferred.substitute(&s);
                         s0 = s0 + s;
                     }
-                    Right(c) => cs.push_front(c),
+                    Right(new) => new.into_iter().for_each(|c| cs.push_front(c)),
                 }
             } else {
                 cs.push_front(c)
             }
         }
-        Ok(s0)
+
+        let Solver {
+            deferred,
+            generalized,
+            ..
+        } = solver;
+        let remaining = deferred.into_iter().filter(|it| {
+            let free = it.free_types();
+            free.is_empty() || !free.is_subset(&generalized)
+        });
+        Ok((s0, classes.simplify(remaining)?))
+    }
+}
+
+impl Solver<'_> {
+    fn solve_pair(
+        &mut self,
+        c: Constraint,
+    ) -> Result<Either<Substitutions, Vec<Constraint>>, ConstraintsSolverError> {
+        match c {
+            Eq(EqConstraint { t1, t2 }) => Ok(Left(t1.unify(&t2)?)),
+            ExplicitInstance { t, s } => {
+                let (context, t2) = s.instantiate(self.env);
+                let mut result = vec![Eq(EqConstraint { t1: t, t2 })];
+                result.extend(context.into_iter().map(Class));
+                Ok(Right(result))
+            }
+            ImplicitInstance { t1, ctx, t2 } => {
+                let s = self.generalize(&t2, &ctx)?;
+                Ok(Right(vec![ExplicitInstance { t: t1, s }]))
+            }
+            Class(p) => {
+                self.deferred.push(p);
+                Ok(Right(vec![]))
+            }
+        }
+    }
+
+    /// Generalizes `t` capturing all deferred predicates about quantified variables
+    fn generalize(
+        &mut self,
+        t: &MonomorphicType,
+        ctx: &HashSet<TVar>,
+    ) -> Result<PolymorphicType, ConstraintsSolverError> {
+        let scheme = t.generalize(ctx);
+        let bindings: HashSet<_> = scheme.bindings.iter().copied().collect();
+        let captured = self.classes.simplify(
+            self.deferred
+                .iter()
+                .filter(|it| !(&it.free_types() & &bindings).is_empty())
+                .cloned(),
+        )?;
+        self.generalized.extend(bindings.iter().copied());
+        Ok(scheme.with_context(
+            captured
+                .into_iter()
+                .filter(|it| it.free_types().is_subset(&bindings)),
+        ))
     }
 }
 
@@ -139,6 +212,7 @@ impl Display for Constraint {
             ImplicitInstance { t1, ctx, t2 } => {
                 write!(f, "{t1} ≤{{{}}} {t2}", ctx.iter().join(", "))
             }
+            Class(p) => write!(f, "{p}"),
         }
     }
 }
@@ -175,12 +249,18 @@ pub fn explicit_cst(t: impl Into<MonomorphicType>, s: impl Into<PolymorphicType>
     }
 }
 
+pub fn class_cst(class: impl Into<String>, t: impl Into<MonomorphicType>) -> Constraint {
+    Class(predicate(class, t))
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod tests {
-    use crate::constraint::{eq_cst, implicit_cst, Constraint};
+    use crate::class::{predicate, ClassEnvironment, NUM_CLASS, ORD_CLASS};
+    use crate::constraint::{class_cst, eq_cst, implicit_cst, Constraint, ConstraintsSolverError};
     use crate::r#type::MonomorphicType::Var;
-    use crate::r#type::PrimitiveType::Boolean;
+    use crate::r#type::PrimitiveType::{Boolean, Integral};
+    use crate::traits::Substitutable;
     use crate::r#type::{fun1, TVar};
     use crate::substitution::Substitutions;
     use crate::InferState;
@@ -197,7 +277,9 @@ mod tests {
             eq_cst(t5, t1),
         ];
 
-        let result = Constraint::solve(cs, &mut env).unwrap();
+        let (result, predicates) =
+            Constraint::solve(cs, &mut env, &ClassEnvironment::default()).unwrap();
+        assert!(predicates.is_empty());
         assert_eq!(
             result,
             Substitutions::from_iter([
@@ -222,7 +304,9 @@ mod tests {
             eq_cst(t0, t1)
         ];
         
-        let result = Constraint::solve(cs, &mut env).unwrap();
+        let (result, predicates) =
+            Constraint::solve(cs, &mut env, &ClassEnvironment::default()).unwrap();
+        assert!(predicates.is_empty());
         assert_eq!(result, Substitutions::from_iter([
             (t0, fun1(t3, t3)),
             (t2, t3.into()),
@@ -230,4 +314,54 @@ mod tests {
             (t1, fun1(t3, t3))
         ]));
     }
+
+    #[test]
+    fn test_predicates_are_simplified() {
+        let mut env = InferState::default();
+        let [t0, t1] = [0, 1].map(TVar);
+        env.variable_index = 2;
+
+        let cs = vec![
+            class_cst(NUM_CLASS, t1),
+            class_cst(ORD_CLASS, t1),
+            class_cst(NUM_CLASS, t0),
+            eq_cst(t0, Integral),
+        ];
+
+        let (_, predicates) = Constraint::solve(cs, &mut env, &ClassEnvironment::prelude()).unwrap();
+        assert_eq!(
+            predicates,
+            vec![predicate(ORD_CLASS, t1), predicate(NUM_CLASS, t1)]
+        );
+    }
+
+    #[test]
+    fn test_generalization_captures_predicates() {
+        let mut env = InferState::default();
+        let [t0, t1] = [0, 1].map(TVar);
+        env.variable_index = 2;
+
+        let cs = vec![implicit_cst(t1, [], t0), class_cst(NUM_CLASS, t0)];
+
+        let (s, predicates) =
+            Constraint::solve(cs, &mut env, &ClassEnvironment::prelude()).unwrap();
+        let instantiated = Var(t1).substitute(&s);
+        assert_eq!(predicates, vec![predicate(NUM_CLASS, instantiated)]);
+    }
+
+    #[test]
+    fn test_unresolved_predicate() {
+        let mut env = InferState::default();
+        let [t0, t1] = [0, 1].map(TVar);
+        env.variable_index = 2;
+
+        let cs = vec![
+            eq_cst(t1, Boolean),
+            implicit_cst(t1, [], t0),
+            class_cst(NUM_CLASS, t0),
+        ];
+
+        let result = Constraint::solve(cs, &mut env, &ClassEnvironment::prelude());
+        assert!(matches!(result, Err(ConstraintsSolverError::Unresolved(_))));
+    }
 }
@@ -234,9 +234,13 @@ impl<S: Into<String>> From<S> for Var {
 #[allow(clippy::unwrap_used)]
 mod tests {
     use std::collections::HashSet;
+    use crate::algorithm_w::{AlgorithmWError, CompoundInferError};
     use crate::assumption::Environment;
-    use crate::language::{app, lambda, Language, Literal, r#let, var};
-    use crate::r#type::{fun1, Tuple, var as t_var};
+    use crate::class::{predicate, ClassEnvironmentError, NUM_CLASS, ORD_CLASS};
+    use crate::constraint::ConstraintsSolverError;
+    use crate::language::{app, bounded, lambda, Language, Literal, r#if, r#let, var};
+    use crate::r#type::PrimitiveType::{Boolean, Integral};
+    use crate::r#type::{app as t_app, fun1, PolymorphicType, TVar, Tuple, var as t_var};
 
     #[test]
     fn test_infer_language() {
@@ -302,4 +306,116 @@ mod tests {
 
         println!("{}\n{}\n\n{}\n{}\n\n{}\n{}", zero, zt, one, ot, plus, pt);
     }
+
+    #[test]
+    fn test_overloaded_operators_produce_constrained_type() {
+        // let fib = λn. if (n < 2) 1 (fib (n - 1) + fib (n - 2)) in fib
+        // ∀a, b => (Num a, Ord a, Num b) ⇒ a -> b
+        let call = |func: &str, a: Language, b: Language| -> Language {
+            app(b, app(a, var(func))).into()
+        };
+        let fib_of = |offset: Language| -> Language {
+            app(call("__sub_internal", var("n").into(), offset), var("fib")).into()
+        };
+        let expr: Language = r#let(
+            "fib",
+            lambda(
+                "n",
+                r#if(
+                    call("__lt_internal", var("n").into(), Literal::Integral.into()),
+                    Literal::Integral,
+                    call(
+                        "__add_internal",
+                        fib_of(Literal::Integral.into()),
+                        fib_of(Literal::Integral.into()),
+                    ),
+                ),
+            ),
+            var("fib"),
+        )
+        .into();
+
+        let t = expr.infer(&Environment::prelude()).unwrap();
+        println!("{}\n{}", expr, t);
+        assert_eq!(
+            t,
+            fun1(t_var(0), t_var(1))
+                .normalize()
+                .with_context([
+                    predicate(NUM_CLASS, t_var(0)),
+                    predicate(ORD_CLASS, t_var(0)),
+                    predicate(NUM_CLASS, t_var(1)),
+                ])
+        );
+        assert_eq!(t.to_string(), "∀τ0, τ1 => (Num τ0, Ord τ0, Num τ1) ⇒ τ0 -> τ1");
+    }
+
+    #[test]
+    fn test_unresolved_predicate() {
+        // if 1 then 2 else 3
+        let expr: Language = r#if(Literal::Integral, Literal::Integral, Literal::Integral).into();
+
+        let result = expr.infer(&Environment::prelude());
+        assert!(matches!(
+            result,
+            Err(CompoundInferError::AlgoW(AlgorithmWError::FailedConstraints(
+                ConstraintsSolverError::Unresolved(ClassEnvironmentError::NoInstance(_))
+            )))
+        ));
+    }
+
+    #[test]
+    fn test_ambiguous_predicate() {
+        // λx. (λy. x) 1
+        let expr: Language = lambda("x", app(Literal::Integral, lambda("y", var("x")))).into();
+
+        let result = expr.infer(&Environment::prelude());
+        assert!(matches!(
+            result,
+            Err(CompoundInferError::AlgoW(AlgorithmWError::FailedConstraints(
+                ConstraintsSolverError::AmbiguousPredicates(_)
+            )))
+        ));
+    }
+
+    fn option_environment() -> Environment {
+        let mut env = Environment::empty();
+        env.push("flag", Boolean);
+        env.push(
+            "some",
+            PolymorphicType::from(fun1(t_var(0), t_app("Option", [t_var(0)]))).quantified([TVar(0)]),
+        );
+        env
+    }
+
+    #[test]
+    fn test_type_arguments_in_annotation() {
+        // (λ(x: Option<Boolean>). x) (some flag)
+        let expr: Language = app(
+            app(var("flag"), var("some")),
+            lambda(bounded("x", t_app("Option", [Boolean.into()])), var("x")),
+        )
+        .into();
+
+        let t = expr.infer(&option_environment()).unwrap();
+        assert_eq!(t.to_string(), "Option<Boolean>");
+    }
+
+    #[test]
+    fn test_type_arguments_mismatch_in_annotation() {
+        // (λ(x: Option<Integral>). x) (some flag)
+        let expr: Language = app(
+            app(var("flag"), var("some")),
+            lambda(bounded("x", t_app("Option", [Integral.into()])), var("x")),
+        )
+        .into();
+
+        let result = expr.infer(&option_environment());
+        assert!(matches!(
+            result,
+            Err(CompoundInferError::AlgoW(AlgorithmWError::FailedConstraints(
+                ConstraintsSolverError::AlgorithmU(_)
+            )))
+        ));
+    }
 }
@@ -6,6 +6,7 @@ pub mod assumption;
 pub mod language;
 pub mod substitution;
 pub mod r#type;
+pub mod class;
 pub mod constraint;
 pub mod traits;
 
@@ -3,8 +3,8 @@ use std::collections::HashSet;
 use std::fmt::Debug;
 use std::hash::Hash;
 
-use Constraint::{ExplicitInstance, ImplicitInstance};
-use MonomorphicType::{Constant, Pointer, Primitive, Tuple, Var};
+use Constraint::{Class, ExplicitInstance, ImplicitInstance};
+use MonomorphicType::{App, Constant, Pointer, Primitive, Tuple, Var};
 
 use crate::constraint::{Constraint, EqConstraint};
 use crate::constraint::Constraint::Eq;
@@ -60,6 +60,7 @@ impl Substitutable for MonomorphicType {
             ),
             Tuple(inner) => Tuple(crate::r#type::Tuple(inner.0.substitute(subst))),
             Pointer(inner) => Pointer(Box::new(inner.substitute(subst))),
+            App(name, args) => App(name.clone(), args.substitute(subst).into()),
         }
     }
 }
@@ -72,6 +73,7 @@ impl Substitutable for PolymorphicType {
         self.bindings.iter().for_each(|it| s.remove(it));
         Self {
             bindings: self.bindings.clone(),
+            context: self.context.substitute(&s),
             binding_type: self.binding_type.substitute(&s),
         }
     }
@@ -95,6 +97,7 @@ impl Substitutable for Constraint {
                 ctx: ctx.substitute(subst),
                 t2: t2.substitute(subst),
             },
+            Class(p) => Class(p.substitute(subst)),
         }
     }
 }
@@ -144,13 +147,14 @@ impl FreeTypeVars for &MonomorphicType {
             Fn(input, output) => &input.free_types() | &output.free_types(),
             Tuple(crate::r#type::Tuple(vec)) => vec.free_types(),
             Pointer(x) => x.free_types(),
+            App(_, args) => args.free_types(),
         }
     }
 }
 
 impl FreeTypeVars for &PolymorphicType {
     fn free_types(self) -> HashSet<TVar> {
-        let mut free = self.binding_type.free_types();
+        let mut free = &self.binding_type.free_types() | &self.context.free_types();
         self.bindings.iter().for_each(|it| {
             free.remove(it);
         });
@@ -176,6 +180,7 @@ impl ActiveTVars for &Constraint {
                 let set = &ctx.free_types() & &t2.free_types();
                 &t1.free_types() | &set
             }
+            Class(p) => p.free_types(),
         }
     }
 }
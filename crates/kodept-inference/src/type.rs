@@ -6,6 +6,7 @@ use derive_more::{Constructor, Display as DeriveDisplay, From};
 use itertools::{concat, Itertools};
 use nonempty_collections::NEVec;
 
+use crate::class::Predicate;
 use crate::InferState;
 use crate::substitution::Substitutions;
 use crate::traits::{FreeTypeVars, Substitutable};
@@ -53,11 +54,15 @@ pub enum MonomorphicType {
     Tuple(Tuple),
     Pointer(Box<MonomorphicType>),
     Constant(String),
+    /// Type constructor applied to arguments, e.g. `Option<Int>`
+    #[from(ignore)]
+    App(String, Box<[MonomorphicType]>),
 }
 
 #[derive(Clone, PartialEq)]
 pub struct PolymorphicType {
     pub(crate) bindings: Vec<TVar>,
+    pub(crate) context: Vec<Predicate>,
     pub(crate) binding_type: MonomorphicType,
 }
 
@@ -80,6 +85,13 @@ pub fn fun<M: Into<MonomorphicType>>(input: NEVec<MonomorphicType>, output: M) -
     }
 }
 
+pub fn app(
+    constructor: impl Into<String>,
+    arguments: impl IntoIterator<Item = MonomorphicType>,
+) -> MonomorphicType {
+    MonomorphicType::App(constructor.into(), arguments.into_iter().collect())
+}
+
 pub fn var<V: Into<TVar>>(id: V) -> MonomorphicType {
     MonomorphicType::Var(id.into())
 }
@@ -103,10 +115,14 @@ impl MonomorphicType {
                 vec.into_iter().map(|it| it.rename(old, new)).collect(),
             )),
             MonomorphicType::Pointer(t) => MonomorphicType::Pointer(Box::new(t.rename(old, new))),
+            MonomorphicType::App(name, args) => MonomorphicType::App(
+                name,
+                args.into_vec().into_iter().map(|it| it.rename(old, new)).collect(),
+            ),
         }
     }
 
-    fn extract_vars(&self) -> Vec<usize> {
+    pub(crate) fn extract_vars(&self) -> Vec<usize> {
         match self {
             MonomorphicType::Primitive(_) | MonomorphicType::Constant(_) => vec![],
             MonomorphicType::Var(TVar(x)) => vec![*x],
@@ -117,6 +133,9 @@ impl MonomorphicType {
                 vec.iter().flat_map(MonomorphicType::extract_vars).collect()
             }
             MonomorphicType::Pointer(t) => t.extract_vars(),
+            MonomorphicType::App(_, args) => {
+                args.iter().flat_map(MonomorphicType::extract_vars).collect()
+            }
         }
     }
 
@@ -124,6 +143,7 @@ impl MonomorphicType {
         let diff: Vec<_> = self.free_types().difference(free).copied().collect();
         PolymorphicType {
             bindings: diff,
+            context: vec![],
             binding_type: self.clone(),
         }
     }
@@ -134,15 +154,51 @@ impl MonomorphicType {
 }
 
 impl PolymorphicType {
+    /// Adds predicates that should hold for every instantiation of this type
+    #[must_use]
+    pub fn with_context(mut self, context: impl IntoIterator<Item = Predicate>) -> Self {
+        self.context.extend(context);
+        self
+    }
+
+    /// Binds passed type variables
+    #[must_use]
+    pub fn quantified(mut self, bindings: impl IntoIterator<Item = TVar>) -> Self {
+        self.bindings.extend(bindings);
+        self
+    }
+
+    pub fn context(&self) -> &[Predicate] {
+        &self.context
+    }
+
     pub(crate) fn normalize(self) -> Self {
-        let mut free = self.binding_type.extract_vars();
+        let mut free = concat(
+            std::iter::once(&self.binding_type)
+                .chain(self.context.iter().map(|it| &it.ty))
+                .map(MonomorphicType::extract_vars),
+        );
         free.sort_unstable();
         free.dedup();
         let len = free.len();
-        let binding_type = free
-            .iter()
-            .zip(0usize..)
-            .fold(self.binding_type, |acc, (&old, new)| acc.rename(old, new));
+        let rename = |t: MonomorphicType| {
+            free.iter()
+                .zip(0usize..)
+                .fold(t, |acc, (&old, new)| acc.rename(old, new))
+        };
+        let binding_type = rename(self.binding_type);
+        let mut context: Vec<_> = self
+            .context
+            .into_iter()
+            .map(|it| Predicate {
+                ty: rename(it.ty),
+                ..it
+            })
+            .collect();
+        context.sort_by(|a, b| {
+            (a.ty.extract_vars(), &a.class).cmp(&(b.ty.extract_vars(), &b.class))
+        });
+        context.dedup();
         let bindings = free
             .into_iter()
             .zip(0usize..len)
@@ -150,14 +206,15 @@ impl PolymorphicType {
             .collect();
         Self {
             bindings,
+            context,
             binding_type,
         }
     }
 
-    pub(crate) fn instantiate(&self, env: &mut InferState) -> MonomorphicType {
+    pub(crate) fn instantiate(&self, env: &mut InferState) -> (Vec<Predicate>, MonomorphicType) {
         let fresh = self.bindings.iter().map(|it| (*it, env.new_var()));
         let s0 = Substitutions::from_iter(fresh);
-        self.binding_type.substitute(&s0)
+        (self.context.substitute(&s0), self.binding_type.substitute(&s0))
     }
 }
 
@@ -165,6 +222,7 @@ impl<S: Into<MonomorphicType>> From<S> for PolymorphicType {
     fn from(value: S) -> Self {
         Self {
             bindings: vec![],
+            context: vec![],
             binding_type: value.into(),
         }
     }
@@ -263,21 +321,22 @@ impl Display for MonomorphicType {
             MonomorphicType::Tuple(Tuple(vec)) => write!(f, "({})", vec.iter().join(", ")),
             MonomorphicType::Pointer(t) => write!(f, "*{t}"),
             MonomorphicType::Constant(id) => write!(f, "{id}"),
+            MonomorphicType::App(name, args) => write!(f, "{name}<{}>", args.iter().join(", ")),
         }
     }
 }
 
 impl Display for PolymorphicType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        if self.bindings.is_empty() {
-            return write!(f, "{}", self.binding_type);
+        if !self.bindings.is_empty() {
+            write!(f, "∀{} => ", self.bindings.iter().join(", "))?;
+        }
+        match self.context.as_slice() {
+            [] => {}
+            [p] => write!(f, "{p} ⇒ ")?,
+            ps => write!(f, "({}) ⇒ ", ps.iter().join(", "))?,
         }
-        write!(
-            f,
-            "∀{} => {}",
-            self.bindings.iter().join(", "),
-            self.binding_type
-        )
+        write!(f, "{}", self.binding_type)
     }
 }
 
@@ -0,0 +1,130 @@
+use kodept_ast::graph::SyntaxTree;
+use kodept_ast::interning::SharedStr;
+use kodept_ast::{EnumDecl, ModDecl, StructDecl, TyParam, VariantDecl};
+use kodept_inference::r#type::{app, fun1, var, MonomorphicType, PolymorphicType};
+
+use crate::type_alias::AliasResolver;
+
+/// Type of the function that builds `variant` of `decl`, e.g. `Cons :: a -> List<a> -> List<a>`.
+/// Variants without payload are values of the enum type itself.
+/// Returns the name of the alias a cycle was found at.
+pub(crate) fn constructor_type<'a>(
+    module: &'a ModDecl,
+    decl: &'a EnumDecl,
+    variant: &'a VariantDecl,
+    ast: &'a SyntaxTree,
+) -> Result<PolymorphicType, &'a str> {
+    let enum_type = declared_type(&decl.name, &decl.generics);
+    let mut resolver = AliasResolver::new(module, ast).with_generics(&decl.generics);
+    let fields = variant.fields(ast);
+    let payload = fields
+        .iter()
+        .map(|it| it.parameter_type(ast))
+        .chain(variant.payload(ast))
+        .map(|it| resolver.expand(it))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let constructor = payload
+        .into_iter()
+        .rev()
+        .fold(enum_type, |acc, next| fun1(next, acc));
+    Ok(constructor.normalize())
+}
+
+/// Type of reading `field` from a value of `decl`, e.g. `x :: Point -> Int`.
+/// Returns the name of the alias a cycle was found at.
+pub(crate) fn projection_type<'a>(
+    module: &'a ModDecl,
+    decl: &'a StructDecl,
+    field: &'a TyParam,
+    ast: &'a SyntaxTree,
+) -> Result<PolymorphicType, &'a str> {
+    let struct_type = declared_type(&decl.name, &decl.generics);
+    let field_type = AliasResolver::new(module, ast)
+        .with_generics(&decl.generics)
+        .expand(field.parameter_type(ast))?;
+    Ok(fun1(struct_type, field_type).normalize())
+}
+
+/// Type a declaration introduces, with its type parameters as variables
+fn declared_type(name: &str, generics: &[SharedStr]) -> MonomorphicType {
+    if generics.is_empty() {
+        MonomorphicType::Constant(name.to_string())
+    } else {
+        app(name, (0..generics.len()).map(var))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::constructor::{constructor_type, projection_type};
+    use crate::testing::{build, module, parse};
+    use kodept_ast::traits::AsEnum;
+    use kodept_ast::TopLevelEnum;
+    use rstest::rstest;
+
+    const SOURCE: &str = "module Shapes {
+        type Size = (Int, Int)
+        type Loop = Loop
+        enum class List<T> { Nil, Cons(T, List<T>) }
+        enum struct Shape { Circle(radius: Int), Rect(size: Size), Broken(Loop) }
+        struct Sized<T>(value: T, size: Size)
+    }";
+
+    #[rstest]
+    #[case("List", "Nil", Ok("∀τ0 => List<τ0>"))]
+    #[case("List", "Cons", Ok("∀τ0 => τ0 -> List<τ0> -> List<τ0>"))]
+    #[case("Shape", "Circle", Ok("Int -> Shape"))]
+    #[case("Shape", "Rect", Ok("(Int, Int) -> Shape"))]
+    #[case("Shape", "Broken", Err("Loop"))]
+    fn test_constructor_type(
+        #[case] name: &str,
+        #[case] variant: &str,
+        #[case] expected: Result<&str, &str>,
+    ) {
+        let rlt = parse(SOURCE);
+        let ast = build(&rlt, SOURCE);
+        let module = module(&ast, "Shapes");
+        let decl = module
+            .contents(&ast)
+            .into_iter()
+            .find_map(|it| match it.as_enum() {
+                TopLevelEnum::Enum(x) if x.name.as_ref() == name => Some(x),
+                _ => None,
+            })
+            .unwrap();
+        let variant = decl
+            .contents(&ast)
+            .into_iter()
+            .find(|it| it.name.as_ref() == variant)
+            .unwrap();
+
+        let actual = constructor_type(module, decl, variant, &ast).map(|it| it.to_string());
+        assert_eq!(actual.as_deref().map_err(|it| *it), expected);
+    }
+
+    #[rstest]
+    #[case("value", "∀τ0 => Sized<τ0> -> τ0")]
+    #[case("size", "∀τ0 => Sized<τ0> -> (Int, Int)")]
+    fn test_projection_type(#[case] field: &str, #[case] expected: &str) {
+        let rlt = parse(SOURCE);
+        let ast = build(&rlt, SOURCE);
+        let module = module(&ast, "Shapes");
+        let decl = module
+            .contents(&ast)
+            .into_iter()
+            .find_map(|it| match it.as_enum() {
+                TopLevelEnum::Struct(x) => Some(x),
+                _ => None,
+            })
+            .unwrap();
+        let field = decl
+            .parameters(&ast)
+            .into_iter()
+            .find(|it| it.name.as_ref() == field)
+            .unwrap();
+
+        let actual = projection_type(module, decl, field, &ast).unwrap();
+        assert_eq!(actual.to_string(), expected);
+    }
+}
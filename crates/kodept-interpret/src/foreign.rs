@@ -0,0 +1,79 @@
+use kodept_ast::graph::AnyNode;
+use kodept_ast::utils::Skip;
+use kodept_ast::utils::Skip::Skipped;
+use kodept_ast::AbstFnDecl;
+use kodept_inference::assumption::Environment;
+use kodept_inference::r#type::{fun1, unit_type, MonomorphicType};
+use kodept_macros::context::Context;
+use kodept_macros::visit_guard::VisitGuard;
+use kodept_macros::{Macro, MacroExt};
+use std::convert::Infallible;
+
+use crate::type_alias::TypeAliasExpander;
+
+/// Collects signatures of `foreign` functions, which are taken as declared instead of being inferred
+/// once type checking is enabled. No backend lowers them to calls of external symbols yet
+pub struct ForeignFnCollector<'a> {
+    aliases: &'a TypeAliasExpander,
+    signatures: Environment,
+}
+
+impl<'a> ForeignFnCollector<'a> {
+    /// Aliases in signatures are replaced with the given expansions
+    pub fn new(aliases: &'a TypeAliasExpander) -> Self {
+        Self {
+            aliases,
+            signatures: Environment::default(),
+        }
+    }
+
+    pub fn signatures(&self) -> &Environment {
+        &self.signatures
+    }
+
+    pub fn into_signatures(self) -> Environment {
+        self.signatures
+    }
+}
+
+impl Macro for ForeignFnCollector<'_> {
+    type Error = Infallible;
+    type Node = AbstFnDecl;
+    type Ctx<'a> = Context<'a>;
+
+    fn apply(
+        &mut self,
+        guard: VisitGuard<Self::Node>,
+        ctx: &mut Self::Ctx<'_>,
+    ) -> Result<(), Skip<Self::Error>> {
+        let id = guard.allow_last().ok_or(Skipped)?;
+        let node = self.resolve(id, ctx);
+        if !node.foreign {
+            return Err(Skipped);
+        }
+        let Some(AnyNode::ModDecl(module)) = ctx.ast.parent_of(id) else {
+            return Err(Skipped);
+        };
+
+        // Cyclic aliases are reported by `TypeAliasExpander`
+        let resolve = |ty| self.aliases.resolve_type(module, ty, &node.generics, &ctx.ast);
+        let return_type = match node.return_type(&ctx.ast) {
+            None => unit_type(),
+            Some(ty) => resolve(ty).ok_or(Skipped)?,
+        };
+        let parameters: Vec<MonomorphicType> = node
+            .parameters(&ctx.ast)
+            .into_iter()
+            .map(|it| resolve(it.parameter_type(&ctx.ast)))
+            .collect::<Option<_>>()
+            .ok_or(Skipped)?;
+
+        let signature = parameters
+            .into_iter()
+            .rev()
+            .fold(return_type, |acc, next| fun1(next, acc));
+        // Type parameters are the only free variables, so they all get quantified
+        self.signatures.push(node.name.to_string(), signature.normalize());
+        Ok(())
+    }
+}
@@ -0,0 +1,60 @@
+use std::convert::Infallible;
+
+use kodept_ast::graph::tags;
+use kodept_ast::interning::SharedStr;
+use kodept_ast::utils::Skip;
+use kodept_ast::utils::Skip::Skipped;
+use kodept_ast::visit_side::VisitSide;
+use kodept_ast::{
+    Appl, Expression, Identifier, InterpStrLit, Operation, Ref, ReferenceContext, Term,
+};
+use kodept_macros::context::Context;
+use kodept_macros::visit_guard::VisitGuard;
+use kodept_macros::Macro;
+
+/// Expands `"Hello, ${name}!"` to `Prelude::__concat_internal("Hello, ", name, "!")`
+#[derive(Default)]
+pub struct InterpolationExpander;
+
+impl InterpolationExpander {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Macro for InterpolationExpander {
+    type Error = Infallible;
+    type Node = InterpStrLit;
+    type Ctx<'a> = Context<'a>;
+
+    fn apply(
+        &mut self,
+        guard: VisitGuard<Self::Node>,
+        ctx: &mut Self::Ctx<'_>,
+    ) -> Result<(), Skip<Self::Error>> {
+        let id = guard.allow_only(VisitSide::Entering).ok_or(Skipped)?;
+
+        ctx.replace(id.widen().cast::<Operation>(), Appl::uninit().map_into())
+            .ok_or(Skipped)?;
+        // Fragments and expressions already alternate, so they become arguments in source order
+        ctx.ast
+            .update_children_tag::<_, _, Appl, _, { tags::PRIMARY }, { tags::SECONDARY }>(id);
+        let id = id.widen().coerce::<Appl>();
+        let rlt = ctx.rlt.get_unknown(id).ok_or(Skipped)?;
+        ctx.add_child::<_, _, { tags::PRIMARY }>(
+            id,
+            Ref::uninit(
+                ReferenceContext::global(["Prelude"]),
+                Identifier::Reference {
+                    name: SharedStr::new("__concat_internal"),
+                },
+            )
+            .with_rlt(rlt)
+            .map_into::<Term>()
+            .map_into::<Expression>()
+            .map_into::<Operation>(),
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,288 @@
+use std::collections::HashMap;
+use std::iter;
+
+use kodept_ast::graph::{AnyNode, AnyNodeId, Identifiable, SyntaxTree};
+use kodept_ast::interning::SharedStr;
+use kodept_ast::traits::AsEnum;
+use kodept_ast::utils::Skip;
+use kodept_ast::utils::Skip::{Failed, Skipped};
+use kodept_ast::{ModDecl, ParamEnum, TopLevelEnum, Type, TypeEnum};
+use kodept_core::structure::Located;
+use kodept_macros::context::Context;
+use kodept_macros::error::code::{CodedError, ErrorCode, KC040, KC041};
+use kodept_macros::error::traits::SpannedError;
+use kodept_macros::visit_guard::VisitGuard;
+use kodept_macros::{warn_about_broken_rlt, Macro, MacroExt};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum KindError {
+    #[error("Type `{name}` expects {expected} type argument(s), but {actual} were given")]
+    ArityMismatch {
+        name: String,
+        expected: usize,
+        actual: usize,
+    },
+    #[error(
+        "Type `{name}` takes {actual} more type argument(s), but {expected} are expected here"
+    )]
+    KindMismatch {
+        name: String,
+        expected: usize,
+        actual: usize,
+    },
+}
+
+impl CodedError for KindError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            KindError::ArityMismatch { .. } => KC040,
+            KindError::KindMismatch { .. } => KC041,
+        }
+    }
+}
+
+/// Checks that type constructors are applied to as many arguments as they declare.
+/// Kind of a type is the number of type arguments it takes.
+/// Kinds of type parameters are inferred from their first use in the declaration,
+/// so `F` in `struct Wrap<F>(value: F<Int>)` takes one argument and `Wrap<Int>` is rejected.
+/// Type arguments are not compared with inferred types, as type checking is disabled
+#[derive(Default)]
+pub struct KindChecker {
+    // module name -> type name -> declaration
+    declarations: HashMap<String, HashMap<String, AnyNodeId>>,
+    // declaration -> kinds of its type parameters
+    parameter_kinds: HashMap<AnyNodeId, Vec<usize>>,
+}
+
+impl KindChecker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn declaration(&mut self, module: &ModDecl, name: &str, ast: &SyntaxTree) -> Option<AnyNodeId> {
+        self.declarations
+            .entry(module.name.to_string())
+            .or_insert_with(|| {
+                module
+                    .contents(ast)
+                    .into_iter()
+                    .filter_map(|it| match it.as_enum() {
+                        TopLevelEnum::Enum(x) => Some((x.name.to_string(), x.get_id().widen())),
+                        TopLevelEnum::Struct(x) => Some((x.name.to_string(), x.get_id().widen())),
+                        TopLevelEnum::TyAlias(x) => Some((x.name.to_string(), x.get_id().widen())),
+                        TopLevelEnum::Foreign(_) | TopLevelEnum::Fn(_) => None,
+                    })
+                    .collect()
+            })
+            .get(name)
+            .copied()
+    }
+
+    /// Kinds of type parameters of the declaration.
+    /// Parameters that are never used, or used only in declarations referring to each other,
+    /// take no arguments
+    fn parameter_kinds(&mut self, module: &ModDecl, id: AnyNodeId, ast: &SyntaxTree) -> Vec<usize> {
+        if let Some(kinds) = self.parameter_kinds.get(&id) {
+            return kinds.clone();
+        }
+        let Some(decl) = ast.get(id) else {
+            return vec![];
+        };
+        let generics = generics(decl);
+        self.parameter_kinds.insert(id, vec![0; generics.len()]);
+
+        let mut kinds = vec![None; generics.len()];
+        for ty in annotations(decl, ast) {
+            self.infer(module, generics, ty, 0, &mut kinds, ast);
+        }
+        let kinds: Vec<_> = kinds.into_iter().map(Option::unwrap_or_default).collect();
+        self.parameter_kinds.insert(id, kinds.clone());
+        kinds
+    }
+
+    /// Records kinds of type parameters used in `ty`, which is placed where a type taking
+    /// `expected` arguments is expected
+    fn infer(
+        &mut self,
+        module: &ModDecl,
+        generics: &[SharedStr],
+        ty: &Type,
+        expected: usize,
+        kinds: &mut [Option<usize>],
+        ast: &SyntaxTree,
+    ) {
+        let (name, arguments) = match ty.as_enum() {
+            TypeEnum::TyName(x) => (&x.name, vec![]),
+            TypeEnum::App(x) => (&x.name, x.arguments(ast)),
+            TypeEnum::Tuple(x) => {
+                for item in x.types(ast) {
+                    self.infer(module, generics, item, 0, kinds, ast);
+                }
+                return;
+            }
+        };
+        let argument_kinds = match generics.iter().position(|it| it == name) {
+            Some(index) => {
+                kinds[index].get_or_insert(arguments.len() + expected);
+                vec![]
+            }
+            None => match self.declaration(module, name, ast) {
+                None => vec![],
+                Some(id) => self.parameter_kinds(module, id, ast),
+            },
+        };
+        for (index, argument) in arguments.into_iter().enumerate() {
+            let expected = argument_kinds.get(index).copied().unwrap_or_default();
+            self.infer(module, generics, argument, expected, kinds, ast);
+        }
+    }
+
+    /// Kinds of arguments of type `name` visible from `at`, `None` if the type is unknown
+    fn argument_kinds(
+        &mut self,
+        name: &str,
+        at: AnyNodeId,
+        ast: &SyntaxTree,
+    ) -> Option<Vec<usize>> {
+        let parents: Vec<_> =
+            iter::successors(ast.parent_of(at), |it| ast.parent_of(it.get_id())).collect();
+        let module = parents.iter().find_map(|it| match it {
+            AnyNode::ModDecl(x) => Some(x),
+            _ => None,
+        })?;
+
+        for parent in &parents {
+            if let Some(index) = generics(parent).iter().position(|it| it.as_ref() == name) {
+                let kind = self.parameter_kinds(module, parent.get_id(), ast)[index];
+                // Type parameters are applied only to types that take no arguments
+                return Some(vec![0; kind]);
+            }
+        }
+        // unknown types are reported by the scope analyzer
+        let id = self.declaration(module, name, ast)?;
+        Some(self.parameter_kinds(module, id, ast))
+    }
+
+    /// Kind of the type expected in place of type `id`
+    fn expected_kind(&mut self, id: AnyNodeId, ast: &SyntaxTree) -> usize {
+        let Some(AnyNode::TyApp(parent)) = ast.parent_of(id) else {
+            return 0;
+        };
+        let index = parent
+            .arguments(ast)
+            .into_iter()
+            .position(|it| it.get_id().widen() == id);
+        match (
+            index,
+            self.argument_kinds(&parent.name, parent.get_id().widen(), ast),
+        ) {
+            (Some(index), Some(kinds)) => kinds.get(index).copied().unwrap_or_default(),
+            _ => 0,
+        }
+    }
+}
+
+fn generics(decl: &AnyNode) -> &[SharedStr] {
+    match decl {
+        AnyNode::EnumDecl(x) => &x.generics,
+        AnyNode::StructDecl(x) => &x.generics,
+        AnyNode::BodyFnDecl(x) => &x.generics,
+        AnyNode::AbstFnDecl(x) => &x.generics,
+        _ => &[],
+    }
+}
+
+/// Types written in the declaration itself, not in its nested declarations
+fn annotations<'a>(decl: &'a AnyNode, ast: &'a SyntaxTree) -> Vec<&'a Type> {
+    match decl {
+        AnyNode::StructDecl(x) => x
+            .parameters(ast)
+            .into_iter()
+            .map(|it| it.parameter_type(ast))
+            .collect(),
+        AnyNode::EnumDecl(x) => x
+            .contents(ast)
+            .into_iter()
+            .flat_map(|variant| {
+                let fields = variant.fields(ast);
+                fields
+                    .into_iter()
+                    .map(|it| it.parameter_type(ast))
+                    .chain(variant.payload(ast))
+                    .collect::<Vec<_>>()
+            })
+            .collect(),
+        AnyNode::AbstFnDecl(x) => x
+            .parameters(ast)
+            .into_iter()
+            .map(|it| it.parameter_type(ast))
+            .chain(x.return_type(ast))
+            .collect(),
+        AnyNode::BodyFnDecl(x) => x
+            .parameters(ast)
+            .into_iter()
+            .filter_map(|it| match it.as_enum() {
+                ParamEnum::Ty(x) => Some(x.parameter_type(ast)),
+                ParamEnum::NonTy(_) => None,
+            })
+            .chain(x.return_type(ast))
+            .collect(),
+        AnyNode::TyAliasDecl(x) => vec![x.aliased(ast)],
+        _ => vec![],
+    }
+}
+
+fn check(name: &str, kinds: &[usize], applied: usize, expected: usize) -> Result<(), KindError> {
+    let arity = kinds.len();
+    if applied + expected == arity {
+        Ok(())
+    } else if applied > arity || expected == 0 {
+        Err(KindError::ArityMismatch {
+            name: name.to_string(),
+            expected: arity,
+            actual: applied,
+        })
+    } else {
+        Err(KindError::KindMismatch {
+            name: name.to_string(),
+            expected,
+            actual: arity - applied,
+        })
+    }
+}
+
+impl Macro for KindChecker {
+    type Error = SpannedError<KindError>;
+    type Node = Type;
+    type Ctx<'a> = Context<'a>;
+
+    fn apply(
+        &mut self,
+        guard: VisitGuard<Self::Node>,
+        ctx: &mut Self::Ctx<'_>,
+    ) -> Result<(), Skip<Self::Error>> {
+        let id = guard.allow_last().ok_or(Skipped)?;
+        let node = self.resolve(id, ctx);
+        let (name, applied) = match node.as_enum() {
+            TypeEnum::TyName(x) => (&x.name, 0),
+            TypeEnum::App(x) => (&x.name, x.arguments(&ctx.ast).len()),
+            TypeEnum::Tuple(_) => return Err(Skipped),
+        };
+
+        let kinds = self
+            .argument_kinds(name, id.widen(), &ctx.ast)
+            .ok_or(Skipped)?;
+        let expected = self.expected_kind(id.widen(), &ctx.ast);
+        match check(name, &kinds, applied, expected) {
+            Ok(()) => Ok(()),
+            Err(error) => match ctx.rlt.get_unknown(id) {
+                Some(node) => Err(Failed(SpannedError::new(error, node.location()))),
+                None => {
+                    warn_about_broken_rlt::<Type>();
+                    Err(Skipped)
+                }
+            },
+        }
+    }
+}
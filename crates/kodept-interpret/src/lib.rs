@@ -1,10 +1,21 @@
+mod constructor;
 // mod convert_model;
+pub mod foreign;
+pub mod interpolation;
+pub mod kind_checker;
+pub mod lints;
+pub mod literal;
 // mod node_family;
 pub mod operator_desugaring;
+pub mod record;
+pub mod recursive_enum;
 mod scope;
 // pub mod semantic_analyzer;
 mod symbol;
 // pub mod type_checker;
 pub mod scope_analyzer;
+#[cfg(test)]
+mod testing;
+pub mod type_alias;
 
 pub(crate) type Path = String;
@@ -0,0 +1,109 @@
+use kodept_ast::graph::{AnyNode, AnyNodeId, Identifiable, SyntaxTree};
+use kodept_ast::interning::SharedStr;
+use kodept_ast::rlt_accessor::{RLTAccessor, RLTFamily};
+use kodept_ast::visit_side::VisitSide;
+use kodept_ast::Identifier;
+use kodept_core::code_point::CodePoint;
+use kodept_core::structure::{rlt, Located};
+use kodept_macros::lint::LintRegistry;
+
+pub mod non_pascal_case_types;
+pub mod redundant_else;
+pub mod shadowed_bindings;
+pub mod unused_functions;
+pub mod unused_variables;
+
+pub use non_pascal_case_types::NonPascalCaseTypes;
+pub use redundant_else::RedundantElse;
+pub use shadowed_bindings::ShadowedBindings;
+pub use unused_functions::UnusedFunctions;
+pub use unused_variables::UnusedVariables;
+
+/// All lints implemented by the compiler
+pub fn registry() -> LintRegistry {
+    LintRegistry::new()
+        .register::<UnusedVariables>()
+        .register::<UnusedFunctions>()
+        .register::<ShadowedBindings>()
+        .register::<RedundantElse>()
+        .register::<NonPascalCaseTypes>()
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum BindingKind {
+    Variable,
+    Parameter,
+}
+
+/// Local name introduced by a variable or a parameter
+struct Binding {
+    name: SharedStr,
+    id: AnyNodeId,
+    kind: BindingKind,
+}
+
+/// Whether the node delimits visibility of local bindings
+fn opens_scope(node: &AnyNode) -> bool {
+    matches!(
+        node,
+        AnyNode::BodyFnDecl(_) | AnyNode::Lambda(_) | AnyNode::Exprs(_)
+    )
+}
+
+/// Binding introduced by the node, if it is visited for the last time.
+/// Variables become visible only after their initializer
+fn declared_binding(node: &AnyNode, side: VisitSide, ast: &SyntaxTree) -> Option<Binding> {
+    if side == VisitSide::Entering {
+        return None;
+    }
+    let is_parameter = || {
+        matches!(
+            ast.parent_of(node.get_id()),
+            Some(AnyNode::BodyFnDecl(_) | AnyNode::Lambda(_))
+        )
+    };
+
+    match node {
+        AnyNode::InitVar(init) => {
+            let variable = init.variable(ast);
+            Some(Binding {
+                name: variable.name.clone(),
+                id: variable.get_id().widen(),
+                kind: BindingKind::Variable,
+            })
+        }
+        AnyNode::TyParam(param) if is_parameter() => Some(Binding {
+            name: param.name.clone(),
+            id: node.get_id(),
+            kind: BindingKind::Parameter,
+        }),
+        AnyNode::NonTyParam(param) if is_parameter() => Some(Binding {
+            name: param.name.clone(),
+            id: node.get_id(),
+            kind: BindingKind::Parameter,
+        }),
+        _ => None,
+    }
+}
+
+/// Name of a value the node refers to
+fn referenced_name(node: &AnyNode) -> Option<&str> {
+    match node {
+        AnyNode::Ref(x) => match &x.ident {
+            Identifier::Reference { name } => Some(name),
+            Identifier::TypeReference { .. } => None,
+        },
+        _ => None,
+    }
+}
+
+/// Location of the name of a declaration, or of the whole node if it has no name
+fn name_location(rlt: &RLTAccessor, id: AnyNodeId) -> Option<CodePoint> {
+    Some(match rlt.get_unknown(id)? {
+        RLTFamily::Struct(x) => x.id.location(),
+        RLTFamily::Enum(rlt::Enum::Stack { id, .. } | rlt::Enum::Heap { id, .. }) => id.location(),
+        RLTFamily::TypeAlias(x) => x.id.location(),
+        RLTFamily::BodiedFunction(x) => x.id.location(),
+        other => other.location(),
+    })
+}
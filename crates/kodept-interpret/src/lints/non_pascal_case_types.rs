@@ -0,0 +1,90 @@
+use crate::lints::name_location;
+use kodept_ast::graph::AnyNode;
+use kodept_ast::utils::Skip;
+use kodept_ast::utils::Skip::Skipped;
+use kodept_macros::context::Context;
+use kodept_macros::error::code::KC094;
+use kodept_macros::error::report::Label;
+use kodept_macros::error::suggestion::{Applicability, Suggestion};
+use kodept_macros::lint::{Lint, LintInfo, LintLevel};
+use kodept_macros::visit_guard::VisitGuard;
+use kodept_macros::{Macro, MacroExt};
+use std::convert::Infallible;
+
+/// Reports names of structures, enums, their variants and type aliases that contain underscores
+#[derive(Default)]
+pub struct NonPascalCaseTypes;
+
+impl NonPascalCaseTypes {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// Joins the words separated by underscores, i.e. `HTTP_Client` becomes `HttpClient`
+fn to_pascal_case(name: &str) -> String {
+    name.split('_')
+        .filter(|it| !it.is_empty())
+        .map(|word| {
+            let all_caps = word.chars().all(|it| !it.is_lowercase());
+            let mut chars = word.chars();
+            let first = chars.next().into_iter().flat_map(char::to_uppercase);
+            if all_caps {
+                first
+                    .chain(chars.flat_map(char::to_lowercase))
+                    .collect::<String>()
+            } else {
+                first.chain(chars).collect()
+            }
+        })
+        .collect()
+}
+
+impl Lint for NonPascalCaseTypes {
+    const INFO: LintInfo = LintInfo {
+        name: "non_pascal_case_types",
+        default_level: LintLevel::Warn,
+        description: "Detects type names that are not written in PascalCase",
+        code: KC094,
+    };
+}
+
+impl Macro for NonPascalCaseTypes {
+    type Error = Infallible;
+    type Node = AnyNode;
+    type Ctx<'a> = Context<'a>;
+
+    fn apply(
+        &mut self,
+        guard: VisitGuard<Self::Node>,
+        ctx: &mut Self::Ctx<'_>,
+    ) -> Result<(), Skip<Self::Error>> {
+        let id = guard.allow_last().ok_or(Skipped)?;
+        let (what, name) = match self.resolve(id, ctx) {
+            AnyNode::StructDecl(x) => ("Structure", &x.name),
+            AnyNode::EnumDecl(x) => ("Enum", &x.name),
+            AnyNode::VariantDecl(x) => ("Enum variant", &x.name),
+            AnyNode::TyAliasDecl(x) => ("Type alias", &x.name),
+            _ => return Err(Skipped),
+        };
+        let pascal_case = to_pascal_case(name);
+        if !name.contains('_') || pascal_case.is_empty() {
+            return Ok(());
+        }
+        let Some(at) = name_location(&ctx.rlt, id) else {
+            return Ok(());
+        };
+
+        ctx.lint(&Self::INFO, at, |it| {
+            it.with_message(format!("{what} `{name}` should have a PascalCase name"))
+                .with_label(Label::primary("contains underscores", at))
+                .with_suggestion(Suggestion::replace(
+                    "rename it, along with its usages",
+                    at,
+                    pascal_case,
+                    Applicability::MaybeIncorrect,
+                ))
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,162 @@
+use kodept_ast::graph::{AnyNode, Identifiable, SyntaxTree};
+use kodept_ast::utils::Skip;
+use kodept_ast::utils::Skip::Skipped;
+use kodept_ast::{
+    BinaryExpressionKind, ComparisonKind, EqKind, IfExpr, Operation, UnaryExpressionKind,
+};
+use kodept_core::structure::Located;
+use kodept_macros::context::Context;
+use kodept_macros::error::code::KC093;
+use kodept_macros::error::report::Label;
+use kodept_macros::lint::{Lint, LintInfo, LintLevel};
+use kodept_macros::visit_guard::VisitGuard;
+use kodept_macros::{Macro, MacroExt};
+use std::convert::Infallible;
+
+/// Reports `else` branches that cannot be reached because an `elif` condition
+/// is the negation of one of the previous conditions
+#[derive(Default)]
+pub struct RedundantElse;
+
+impl RedundantElse {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// Kind of comparison that holds exactly when the given one does not
+fn negated(kind: &BinaryExpressionKind) -> Option<BinaryExpressionKind> {
+    use BinaryExpressionKind::{Cmp, Eq};
+
+    Some(match kind {
+        Cmp(ComparisonKind::Less) => Cmp(ComparisonKind::GreaterEq),
+        Cmp(ComparisonKind::LessEq) => Cmp(ComparisonKind::Greater),
+        Cmp(ComparisonKind::Greater) => Cmp(ComparisonKind::LessEq),
+        Cmp(ComparisonKind::GreaterEq) => Cmp(ComparisonKind::Less),
+        Eq(EqKind::Eq) => Eq(EqKind::NEq),
+        Eq(EqKind::NEq) => Eq(EqKind::Eq),
+        _ => return None,
+    })
+}
+
+/// Kind of comparison that holds for the swapped operands, i.e. `a < b` is `b > a`
+fn flipped(kind: &BinaryExpressionKind) -> Option<BinaryExpressionKind> {
+    use BinaryExpressionKind::{Cmp, Eq};
+
+    Some(match kind {
+        Cmp(ComparisonKind::Less) => Cmp(ComparisonKind::Greater),
+        Cmp(ComparisonKind::LessEq) => Cmp(ComparisonKind::GreaterEq),
+        Cmp(ComparisonKind::Greater) => Cmp(ComparisonKind::Less),
+        Cmp(ComparisonKind::GreaterEq) => Cmp(ComparisonKind::LessEq),
+        Eq(kind) => Eq(kind.clone()),
+        _ => return None,
+    })
+}
+
+fn node<'a>(operation: &Operation, ast: &'a SyntaxTree) -> Option<&'a AnyNode> {
+    ast.get(operation.get_id().widen())
+}
+
+/// Whether both operations are written the same way, only side-effect free ones are compared
+fn same(a: &Operation, b: &Operation, ast: &SyntaxTree) -> bool {
+    match (node(a, ast), node(b, ast)) {
+        (Some(AnyNode::Ref(a)), Some(AnyNode::Ref(b))) => {
+            a.context == b.context && a.ident == b.ident
+        }
+        (Some(AnyNode::NumLit(a)), Some(AnyNode::NumLit(b))) => a.value == b.value,
+        (Some(AnyNode::CharLit(a)), Some(AnyNode::CharLit(b))) => a.value == b.value,
+        (Some(AnyNode::StrLit(a)), Some(AnyNode::StrLit(b))) => a.value == b.value,
+        (Some(AnyNode::UnExpr(a)), Some(AnyNode::UnExpr(b))) => {
+            a.kind == b.kind && same(a.expr(ast), b.expr(ast), ast)
+        }
+        (Some(AnyNode::BinExpr(a)), Some(AnyNode::BinExpr(b))) => {
+            a.kind == b.kind
+                && same(a.left(ast), b.left(ast), ast)
+                && same(a.right(ast), b.right(ast), ast)
+        }
+        _ => false,
+    }
+}
+
+/// Whether `b` holds exactly when `a` does not
+fn complementary(a: &Operation, b: &Operation, ast: &SyntaxTree) -> bool {
+    match (node(a, ast), node(b, ast)) {
+        (Some(AnyNode::UnExpr(x)), _) if x.kind == UnaryExpressionKind::Not => {
+            same(x.expr(ast), b, ast)
+        }
+        (_, Some(AnyNode::UnExpr(x))) if x.kind == UnaryExpressionKind::Not => {
+            same(a, x.expr(ast), ast)
+        }
+        (Some(AnyNode::BinExpr(a)), Some(AnyNode::BinExpr(b))) => {
+            let Some(negation) = negated(&a.kind) else {
+                return false;
+            };
+            let direct = b.kind == negation
+                && same(a.left(ast), b.left(ast), ast)
+                && same(a.right(ast), b.right(ast), ast);
+            let swapped = flipped(&negation).is_some_and(|it| it == b.kind)
+                && same(a.left(ast), b.right(ast), ast)
+                && same(a.right(ast), b.left(ast), ast);
+            direct || swapped
+        }
+        _ => false,
+    }
+}
+
+impl Lint for RedundantElse {
+    const INFO: LintInfo = LintInfo {
+        name: "redundant_else",
+        default_level: LintLevel::Warn,
+        description: "Detects `else` branches after an `elif` chain that covers all cases",
+        code: KC093,
+    };
+}
+
+impl Macro for RedundantElse {
+    type Error = Infallible;
+    type Node = IfExpr;
+    type Ctx<'a> = Context<'a>;
+
+    fn apply(
+        &mut self,
+        guard: VisitGuard<Self::Node>,
+        ctx: &mut Self::Ctx<'_>,
+    ) -> Result<(), Skip<Self::Error>> {
+        let id = guard.allow_last().ok_or(Skipped)?;
+        let node = self.resolve(id, ctx);
+        let Some(else_branch) = node.elses(&ctx.ast) else {
+            return Err(Skipped);
+        };
+        let elifs = node.elifs(&ctx.ast);
+        let conditions: Vec<_> = std::iter::once(node.condition(&ctx.ast))
+            .chain(elifs.iter().map(|it| it.condition(&ctx.ast)))
+            .collect();
+
+        let exhaustive = elifs.iter().enumerate().find(|(index, elif)| {
+            conditions[..=*index]
+                .iter()
+                .any(|it| complementary(it, elif.condition(&ctx.ast), &ctx.ast))
+        });
+        let Some((_, elif)) = exhaustive else {
+            return Ok(());
+        };
+        let location = |id| ctx.rlt.get_unknown(id).map(|it| it.location());
+        let (Some(at), Some(elif_at)) = (
+            location(else_branch.get_id().widen()),
+            location(elif.get_id().widen()),
+        ) else {
+            return Ok(());
+        };
+
+        ctx.lint(&Self::INFO, at, |it| {
+            it.with_message("`else` branch is never executed")
+                .with_label(Label::primary("unreachable branch", at))
+                .with_label(Label::secondary(
+                    "this condition always holds when it is checked",
+                    elif_at,
+                ))
+                .with_note("turn the `elif` into `else` or remove this branch".into())
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,99 @@
+use crate::lints::{declared_binding, opens_scope, Binding};
+use kodept_ast::graph::AnyNode;
+use kodept_ast::utils::Skip;
+use kodept_ast::visit_side::VisitSide;
+use kodept_core::structure::Located;
+use kodept_macros::context::Context;
+use kodept_macros::error::code::KC092;
+use kodept_macros::error::report::Label;
+use kodept_macros::lint::{Lint, LintInfo, LintLevel};
+use kodept_macros::visit_guard::VisitGuard;
+use kodept_macros::{Macro, MacroExt};
+use std::convert::Infallible;
+
+/// Reports variables and parameters that hide another binding with the same name
+#[derive(Default)]
+pub struct ShadowedBindings {
+    scopes: Vec<Vec<Binding>>,
+}
+
+impl ShadowedBindings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn check(&self, binding: &Binding, ctx: &Context) {
+        if binding.name.starts_with('_') {
+            return;
+        }
+        let Some(shadowed) = self
+            .scopes
+            .iter()
+            .rev()
+            .flat_map(|it| it.iter().rev())
+            .find(|it| it.name == binding.name)
+        else {
+            return;
+        };
+        let location = |it: &Binding| ctx.rlt.get_unknown(it.id).map(|it| it.location());
+        let Some(at) = location(binding) else {
+            return;
+        };
+
+        ctx.lint(&Self::INFO, at, |it| {
+            let it = it
+                .with_message(format!(
+                    "`{}` shadows a binding with the same name",
+                    binding.name
+                ))
+                .with_label(Label::primary("shadows the outer binding", at));
+            match location(shadowed) {
+                Some(previous) => {
+                    it.with_label(Label::secondary("previously bound here", previous))
+                }
+                None => it,
+            }
+        });
+    }
+}
+
+impl Lint for ShadowedBindings {
+    const INFO: LintInfo = LintInfo {
+        name: "shadowed_bindings",
+        default_level: LintLevel::Allow,
+        description: "Detects variables and parameters that hide a binding with the same name",
+        code: KC092,
+    };
+}
+
+impl Macro for ShadowedBindings {
+    type Error = Infallible;
+    type Node = AnyNode;
+    type Ctx<'a> = Context<'a>;
+
+    fn apply(
+        &mut self,
+        guard: VisitGuard<Self::Node>,
+        ctx: &mut Self::Ctx<'_>,
+    ) -> Result<(), Skip<Self::Error>> {
+        let (id, side) = guard.allow_all();
+        let node = self.resolve(id, ctx);
+
+        if let Some(binding) = declared_binding(node, side, &ctx.ast) {
+            self.check(&binding, ctx);
+            if let Some(scope) = self.scopes.last_mut() {
+                scope.push(binding);
+            }
+        }
+        if opens_scope(node) {
+            match side {
+                VisitSide::Entering => self.scopes.push(vec![]),
+                VisitSide::Exiting => {
+                    self.scopes.pop();
+                }
+                VisitSide::Leaf => {}
+            }
+        }
+        Ok(())
+    }
+}
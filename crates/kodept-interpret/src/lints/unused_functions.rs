@@ -0,0 +1,126 @@
+use crate::lints::{name_location, referenced_name};
+use kodept_ast::graph::{AnyNode, AnyNodeId};
+use kodept_ast::utils::Skip;
+use kodept_ast::visit_side::VisitSide;
+use kodept_macros::context::Context;
+use kodept_macros::error::code::KC091;
+use kodept_macros::error::report::Label;
+use kodept_macros::lint::{Lint, LintInfo, LintLevel};
+use kodept_macros::visit_guard::VisitGuard;
+use kodept_macros::{Macro, MacroExt};
+use std::collections::HashMap;
+use std::convert::Infallible;
+
+struct Function {
+    id: AnyNodeId,
+    name: String,
+    /// Number of references to the function from its own body
+    recursive_calls: usize,
+}
+
+/// Reports functions that are never called outside of their own body.
+/// Functions named `main`, methods and names starting with an underscore are skipped
+pub struct UnusedFunctions {
+    /// Number of references by name inside each function being visited, the first one is the whole file
+    references: Vec<HashMap<String, usize>>,
+    functions: Vec<Function>,
+}
+
+impl Default for UnusedFunctions {
+    fn default() -> Self {
+        Self {
+            references: vec![HashMap::new()],
+            functions: vec![],
+        }
+    }
+}
+
+impl UnusedFunctions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn is_exempt(name: &str, parent: Option<&AnyNode>) -> bool {
+        name == "main" || name.starts_with('_') || matches!(parent, Some(AnyNode::StructDecl(_)))
+    }
+
+    fn leave_function(&mut self, id: AnyNodeId, name: &str, ctx: &Context) {
+        if self.references.len() < 2 {
+            return;
+        }
+        let inner = self.references.pop().unwrap_or_default();
+        let recursive_calls = inner.get(name).copied().unwrap_or_default();
+        if let Some(outer) = self.references.last_mut() {
+            for (name, count) in inner {
+                *outer.entry(name).or_default() += count;
+            }
+        }
+        if !Self::is_exempt(name, ctx.ast.parent_of(id)) {
+            self.functions.push(Function {
+                id,
+                name: name.to_string(),
+                recursive_calls,
+            });
+        }
+    }
+
+    fn report_unused(&mut self, ctx: &Context) {
+        let total = self.references.first();
+        for function in self.functions.drain(..) {
+            let calls = total
+                .and_then(|it| it.get(&function.name))
+                .copied()
+                .unwrap_or_default();
+            if calls > function.recursive_calls {
+                continue;
+            }
+            let Some(location) = name_location(&ctx.rlt, function.id) else {
+                continue;
+            };
+            ctx.lint(&Self::INFO, location, |it| {
+                it.with_message(format!("Function `{}` is never called", function.name))
+                    .with_label(Label::primary("declared here", location))
+            });
+        }
+    }
+}
+
+impl Lint for UnusedFunctions {
+    const INFO: LintInfo = LintInfo {
+        name: "unused_functions",
+        default_level: LintLevel::Warn,
+        description: "Detects functions that are never called",
+        code: KC091,
+    };
+}
+
+impl Macro for UnusedFunctions {
+    type Error = Infallible;
+    type Node = AnyNode;
+    type Ctx<'a> = Context<'a>;
+
+    fn apply(
+        &mut self,
+        guard: VisitGuard<Self::Node>,
+        ctx: &mut Self::Ctx<'_>,
+    ) -> Result<(), Skip<Self::Error>> {
+        let (id, side) = guard.allow_all();
+        let node = self.resolve(id, ctx);
+
+        if let Some(name) = referenced_name(node) {
+            if let Some(references) = self.references.last_mut() {
+                *references.entry(name.to_string()).or_default() += 1;
+            }
+        }
+        match (node, side) {
+            (AnyNode::BodyFnDecl(_), VisitSide::Entering) => self.references.push(HashMap::new()),
+            (AnyNode::BodyFnDecl(function), VisitSide::Exiting) => {
+                let name = function.name.to_string();
+                self.leave_function(id, &name, ctx)
+            }
+            (AnyNode::FileDecl(_), VisitSide::Exiting) => self.report_unused(ctx),
+            _ => {}
+        }
+        Ok(())
+    }
+}
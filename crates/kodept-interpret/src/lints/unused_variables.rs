@@ -0,0 +1,107 @@
+use crate::lints::{declared_binding, opens_scope, referenced_name, Binding, BindingKind};
+use kodept_ast::graph::AnyNode;
+use kodept_ast::utils::Skip;
+use kodept_ast::visit_side::VisitSide;
+use kodept_core::structure::Located;
+use kodept_macros::context::Context;
+use kodept_macros::error::code::KC090;
+use kodept_macros::error::report::Label;
+use kodept_macros::error::suggestion::{Applicability, Suggestion};
+use kodept_macros::lint::{Lint, LintInfo, LintLevel};
+use kodept_macros::visit_guard::VisitGuard;
+use kodept_macros::{Macro, MacroExt};
+use std::convert::Infallible;
+
+/// Reports variables and parameters that are never referred to.
+/// Names starting with an underscore are considered intentionally unused
+#[derive(Default)]
+pub struct UnusedVariables {
+    scopes: Vec<Vec<(Binding, bool)>>,
+}
+
+impl UnusedVariables {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn mark_used(&mut self, name: &str) {
+        let binding = self
+            .scopes
+            .iter_mut()
+            .rev()
+            .flat_map(|it| it.iter_mut().rev())
+            .find(|(binding, _)| binding.name.as_ref() == name);
+        if let Some((_, used)) = binding {
+            *used = true;
+        }
+    }
+
+    fn report_unused(scope: Vec<(Binding, bool)>, ctx: &Context) {
+        for (binding, _) in scope.into_iter().filter(|(_, used)| !used) {
+            if binding.name.starts_with('_') {
+                continue;
+            }
+            let Some(location) = ctx.rlt.get_unknown(binding.id).map(|it| it.location()) else {
+                continue;
+            };
+            let what = match binding.kind {
+                BindingKind::Variable => "variable",
+                BindingKind::Parameter => "parameter",
+            };
+            ctx.lint(&Self::INFO, location, |it| {
+                it.with_message(format!("Unused {what} `{}`", binding.name))
+                    .with_label(Label::primary("never used", location))
+                    .with_suggestion(Suggestion::replace(
+                        "if this is intentional, prefix it with an underscore",
+                        location,
+                        format!("_{}", binding.name),
+                        Applicability::MaybeIncorrect,
+                    ))
+            });
+        }
+    }
+}
+
+impl Lint for UnusedVariables {
+    const INFO: LintInfo = LintInfo {
+        name: "unused_variables",
+        default_level: LintLevel::Warn,
+        description: "Detects variables and parameters that are never used",
+        code: KC090,
+    };
+}
+
+impl Macro for UnusedVariables {
+    type Error = Infallible;
+    type Node = AnyNode;
+    type Ctx<'a> = Context<'a>;
+
+    fn apply(
+        &mut self,
+        guard: VisitGuard<Self::Node>,
+        ctx: &mut Self::Ctx<'_>,
+    ) -> Result<(), Skip<Self::Error>> {
+        let (id, side) = guard.allow_all();
+        let node = self.resolve(id, ctx);
+
+        if let Some(name) = referenced_name(node) {
+            self.mark_used(name);
+        }
+        if let Some(binding) = declared_binding(node, side, &ctx.ast) {
+            if let Some(scope) = self.scopes.last_mut() {
+                scope.push((binding, false));
+            }
+        }
+        if opens_scope(node) {
+            match side {
+                VisitSide::Entering => self.scopes.push(vec![]),
+                VisitSide::Exiting => {
+                    let scope = self.scopes.pop().unwrap_or_default();
+                    Self::report_unused(scope, ctx);
+                }
+                VisitSide::Leaf => {}
+            }
+        }
+        Ok(())
+    }
+}
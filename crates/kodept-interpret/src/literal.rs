@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+use std::num::{IntErrorKind, ParseIntError};
+
+use kodept_ast::graph::NodeId;
+use kodept_ast::traits::AsEnum;
+use kodept_ast::utils::Skip;
+use kodept_ast::utils::Skip::{Failed, Skipped};
+use kodept_ast::{Lit, LitEnum};
+use kodept_core::structure::Located;
+use kodept_macros::context::Context;
+use kodept_macros::error::code::{CodedError, ErrorCode, KC060, KC061, KC062, KC063, KC064};
+use kodept_macros::error::traits::SpannedError;
+use kodept_macros::visit_guard::VisitGuard;
+use kodept_macros::{warn_about_broken_rlt, Macro, MacroExt};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum LiteralError {
+    #[error("Integer literal `{0}` does not fit in 64 bits")]
+    IntegerOverflow(String),
+    #[error("Floating literal `{0}` is out of range")]
+    FloatingOverflow(String),
+    #[error("Unknown escape sequence `{0}`")]
+    InvalidEscape(String),
+    #[error("`{0}` is not a valid unicode scalar value")]
+    InvalidUnicode(String),
+    #[error("Integer literal `{0}` has no valid digits")]
+    InvalidDigits(String),
+}
+
+impl CodedError for LiteralError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            LiteralError::IntegerOverflow(_) => KC060,
+            LiteralError::FloatingOverflow(_) => KC061,
+            LiteralError::InvalidEscape(_) => KC062,
+            LiteralError::InvalidUnicode(_) => KC063,
+            LiteralError::InvalidDigits(_) => KC064,
+        }
+    }
+}
+
+/// Value a literal stands for
+#[derive(Debug, Clone, PartialEq)]
+pub enum LiteralValue {
+    Integral(i64),
+    Floating(f64),
+    Char(char),
+    String(String),
+}
+
+/// Decodes numeric, char and string literals to the values they denote
+#[derive(Default)]
+pub struct LiteralDecoder {
+    values: HashMap<NodeId<Lit>, LiteralValue>,
+}
+
+impl LiteralDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the decoded value of literal `id`, unless it is a tuple or was malformed
+    pub fn value(&self, id: NodeId<Lit>) -> Option<&LiteralValue> {
+        self.values.get(&id)
+    }
+
+    /// Decoded values of all literals, in no particular order
+    pub fn values(&self) -> impl Iterator<Item = (NodeId<Lit>, &LiteralValue)> {
+        self.values.iter().map(|(id, value)| (*id, value))
+    }
+}
+
+fn strip_separators(digits: &str) -> String {
+    digits.chars().filter(|it| *it != '_').collect()
+}
+
+fn decode_number(text: &str) -> Result<LiteralValue, LiteralError> {
+    match text.get(..2).map(str::to_ascii_lowercase).as_deref() {
+        Some("0b") => decode_radix(text, 2),
+        Some("0c") => decode_radix(text, 8),
+        Some("0x") => decode_radix(text, 16),
+        _ => decode_decimal(text),
+    }
+}
+
+fn integer_error(text: &str, error: ParseIntError) -> LiteralError {
+    match error.kind() {
+        IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => {
+            LiteralError::IntegerOverflow(text.to_string())
+        }
+        _ => LiteralError::InvalidDigits(text.to_string()),
+    }
+}
+
+fn decode_radix(text: &str, radix: u32) -> Result<LiteralValue, LiteralError> {
+    let digits = strip_separators(&text[2..]);
+    i64::from_str_radix(&digits, radix)
+        .map(LiteralValue::Integral)
+        .map_err(|e| integer_error(text, e))
+}
+
+fn decode_decimal(text: &str) -> Result<LiteralValue, LiteralError> {
+    // Sign may be separated from digits by whitespace
+    let number: String = text.chars().filter(|it| !it.is_whitespace()).collect();
+    if number.contains(['.', 'e', 'E']) {
+        match number.parse::<f64>() {
+            Ok(x) if x.is_finite() => Ok(LiteralValue::Floating(x)),
+            _ => Err(LiteralError::FloatingOverflow(text.to_string())),
+        }
+    } else {
+        number
+            .parse()
+            .map(LiteralValue::Integral)
+            .map_err(|e| integer_error(text, e))
+    }
+}
+
+/// Replaces escape sequences in contents of a char or string literal
+fn unescape(text: &str) -> Result<String, LiteralError> {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        let escaped = match chars.next() {
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('0') => '\0',
+            Some(c @ ('\\' | '\'' | '"' | '$')) => c,
+            Some('u') => {
+                let rest = chars.as_str();
+                let code = rest
+                    .strip_prefix('{')
+                    .and_then(|it| it.split_once('}'))
+                    .map(|(code, _)| code)
+                    .ok_or_else(|| LiteralError::InvalidEscape("\\u".to_string()))?;
+                let sequence = format!("\\u{{{code}}}");
+                let scalar = u32::from_str_radix(&strip_separators(code), 16)
+                    .map_err(|_| LiteralError::InvalidEscape(sequence.clone()))?;
+                // Skip braces and hex digits
+                chars = rest[code.len() + 2..].chars();
+                char::from_u32(scalar).ok_or(LiteralError::InvalidUnicode(sequence))?
+            }
+            Some(c) => return Err(LiteralError::InvalidEscape(format!("\\{c}"))),
+            None => return Err(LiteralError::InvalidEscape("\\".to_string())),
+        };
+        result.push(escaped);
+    }
+    Ok(result)
+}
+
+/// Strips quotes the lexer keeps in char and string literals,
+/// as well as `${` and `}` around fragments of interpolated strings
+fn unquote(text: &str) -> &str {
+    let text = &text[1..];
+    text.strip_suffix(['"', '\'']).or_else(|| text.strip_suffix("${")).unwrap_or(text)
+}
+
+impl Macro for LiteralDecoder {
+    type Error = SpannedError<LiteralError>;
+    type Node = Lit;
+    type Ctx<'a> = Context<'a>;
+
+    fn apply(
+        &mut self,
+        guard: VisitGuard<Self::Node>,
+        ctx: &mut Self::Ctx<'_>,
+    ) -> Result<(), Skip<Self::Error>> {
+        let id = guard.allow_last().ok_or(Skipped)?;
+        let node = self.resolve(id, ctx);
+        let value = match node.as_enum() {
+            LitEnum::Num(x) => decode_number(&x.value),
+            LitEnum::Char(x) => unescape(unquote(&x.value)).map(|it| {
+                // The lexer accepts exactly one character or escape sequence
+                LiteralValue::Char(it.chars().next().unwrap_or_default())
+            }),
+            LitEnum::Str(x) => unescape(unquote(&x.value)).map(LiteralValue::String),
+            LitEnum::Interp(_) | LitEnum::Tuple(_) => return Err(Skipped),
+        };
+        match value {
+            Ok(value) => {
+                self.values.insert(id, value);
+                Ok(())
+            }
+            Err(error) => match ctx.rlt.get_unknown(id) {
+                Some(node) => Err(Failed(SpannedError::new(error, node.location()))),
+                None => {
+                    warn_about_broken_rlt::<Lit>();
+                    Err(Skipped)
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::literal::{decode_number, unescape, LiteralError, LiteralValue};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("0b1010", LiteralValue::Integral(10))]
+    #[case("0B_1_0", LiteralValue::Integral(2))]
+    #[case("0c17", LiteralValue::Integral(15))]
+    #[case("0C7_7", LiteralValue::Integral(63))]
+    #[case("0xFF", LiteralValue::Integral(255))]
+    #[case("0xdead_BEEF", LiteralValue::Integral(0xDEAD_BEEF))]
+    #[case("42", LiteralValue::Integral(42))]
+    #[case("- 42", LiteralValue::Integral(-42))]
+    #[case("1.5", LiteralValue::Floating(1.5))]
+    #[case("1e3", LiteralValue::Floating(1000.0))]
+    #[case("2.5E-1", LiteralValue::Floating(0.25))]
+    #[case(".5e+1", LiteralValue::Floating(5.0))]
+    fn test_decode_number(#[case] text: &str, #[case] expected: LiteralValue) {
+        assert_eq!(decode_number(text).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case("0x_")]
+    #[case("0b")]
+    #[case("0c_9")]
+    fn test_decode_number_without_digits(#[case] text: &str) {
+        assert!(matches!(decode_number(text), Err(LiteralError::InvalidDigits(it)) if it == text));
+    }
+
+    #[rstest]
+    #[case("0x1_0000_0000_0000_0000")]
+    #[case("99999999999999999999")]
+    fn test_decode_number_overflow(#[case] text: &str) {
+        assert!(matches!(decode_number(text), Err(LiteralError::IntegerOverflow(_))));
+    }
+
+    #[test]
+    fn test_decode_infinite_floating() {
+        assert!(matches!(decode_number("1e400"), Err(LiteralError::FloatingOverflow(_))));
+    }
+
+    #[rstest]
+    #[case("plain", "plain")]
+    #[case(r"a\tb\nc\r\0", "a\tb\nc\r\0")]
+    #[case(r#"\\ \' \" \$"#, r#"\ ' " $"#)]
+    #[case(r"\u{41}\u{1F600}", "A\u{1F600}")]
+    #[case(r"\u{1_F6_00}!", "\u{1F600}!")]
+    fn test_unescape(#[case] text: &str, #[case] expected: &str) {
+        assert_eq!(unescape(text).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case(r"\q", r"\q")]
+    #[case(r"\u41", r"\u")]
+    #[case(r"\u{XYZ}", r"\u{XYZ}")]
+    #[case("trailing \\", "\\")]
+    fn test_unescape_invalid_escape(#[case] text: &str, #[case] sequence: &str) {
+        assert!(matches!(unescape(text), Err(LiteralError::InvalidEscape(it)) if it == sequence));
+    }
+
+    #[rstest]
+    #[case(r"\u{D800}")]
+    #[case(r"\u{110000}")]
+    fn test_unescape_invalid_unicode(#[case] text: &str) {
+        assert!(matches!(unescape(text), Err(LiteralError::InvalidUnicode(it)) if it == text));
+    }
+}
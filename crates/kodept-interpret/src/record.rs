@@ -0,0 +1,272 @@
+use std::iter;
+
+use itertools::Itertools;
+use kodept_ast::graph::{AnyNode, AnyNodeId, Identifiable, NodeId, SyntaxTree};
+use kodept_ast::rlt_accessor::RLTAccessor;
+use kodept_ast::traits::AsEnum;
+use kodept_ast::utils::Skip;
+use kodept_ast::utils::Skip::{Failed, Skipped};
+use kodept_ast::{FieldInit, ModDecl, Operation, OperationEnum, StructDecl, TopLevelEnum};
+use kodept_core::structure::Located;
+use kodept_macros::context::Context;
+use kodept_macros::error::code::{CodedError, ErrorCode, KC050, KC051, KC052, KC053};
+use kodept_macros::error::suggestion::{Applicability, Suggestion};
+use kodept_macros::error::traits::SpannedError;
+use kodept_macros::visit_guard::VisitGuard;
+use kodept_macros::{warn_about_broken_rlt, Macro, MacroExt};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum RecordError {
+    #[error("Struct `{name}` has no field `{field}`")]
+    UnknownField { name: String, field: String },
+    #[error("No struct declares field `{0}`")]
+    UndeclaredField(String),
+    #[error("Missing fields in construction of `{name}`: {fields}")]
+    MissingFields { name: String, fields: String },
+    #[error("Field `{0}` is assigned more than once")]
+    DuplicateField(String),
+}
+
+impl CodedError for RecordError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            RecordError::UnknownField { .. } => KC050,
+            RecordError::UndeclaredField(_) => KC051,
+            RecordError::MissingFields { .. } => KC052,
+            RecordError::DuplicateField(_) => KC053,
+        }
+    }
+}
+
+/// Checks struct construction and update expressions against the fields structs declare.
+/// Only names of fields are checked, their types are left to the disabled type checker
+#[derive(Default)]
+pub struct RecordChecker;
+
+impl RecordChecker {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+fn enclosing_module(id: AnyNodeId, ast: &SyntaxTree) -> Option<&ModDecl> {
+    iter::successors(ast.parent_of(id), |it| ast.parent_of(it.get_id())).find_map(|it| match it {
+        AnyNode::ModDecl(x) => Some(x),
+        _ => None,
+    })
+}
+
+fn structs<'a>(module: &'a ModDecl, ast: &'a SyntaxTree) -> impl Iterator<Item = &'a StructDecl> {
+    module
+        .contents(ast)
+        .into_iter()
+        .filter_map(|it| match it.as_enum() {
+            TopLevelEnum::Struct(x) => Some(x),
+            _ => None,
+        })
+}
+
+fn field_names<'a>(decl: &'a StructDecl, ast: &'a SyntaxTree) -> Vec<&'a str> {
+    decl.parameters(ast)
+        .into_iter()
+        .map(|it| it.name.as_ref())
+        .collect()
+}
+
+/// Finds declared name that is likely meant instead of the given one
+fn closest_name<'a>(name: &str, declared: &[&'a str]) -> Option<&'a str> {
+    let threshold = (name.chars().count() / 3).max(1);
+    declared
+        .iter()
+        .map(|it| (strsim::levenshtein(name, it), *it))
+        .filter(|(distance, _)| *distance <= threshold)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, it)| it)
+}
+
+/// Second assignment of a field assigned more than once
+fn find_duplicate<'a>(fields: &[&'a FieldInit]) -> Option<&'a FieldInit> {
+    fields
+        .iter()
+        .enumerate()
+        .find(|(index, field)| fields[..*index].iter().any(|it| it.name == field.name))
+        .map(|(_, it)| *it)
+}
+
+fn spanned<T>(
+    error: RecordError,
+    id: NodeId<T>,
+    rlt: &RLTAccessor,
+) -> Skip<SpannedError<RecordError>>
+where
+    AnyNode: TryFrom<T>,
+{
+    match rlt.get_unknown(id) {
+        Some(node) => Failed(SpannedError::new(error, node.location())),
+        None => {
+            warn_about_broken_rlt::<T>();
+            Skipped
+        }
+    }
+}
+
+fn check_duplicates(
+    fields: &[&FieldInit],
+    rlt: &RLTAccessor,
+) -> Result<(), Skip<SpannedError<RecordError>>> {
+    match find_duplicate(fields) {
+        None => Ok(()),
+        Some(field) => Err(spanned(
+            RecordError::DuplicateField(field.name.to_string()),
+            field.get_id(),
+            rlt,
+        )),
+    }
+}
+
+impl Macro for RecordChecker {
+    type Error = SpannedError<RecordError>;
+    type Node = Operation;
+    type Ctx<'a> = Context<'a>;
+
+    fn apply(
+        &mut self,
+        guard: VisitGuard<Self::Node>,
+        ctx: &mut Self::Ctx<'_>,
+    ) -> Result<(), Skip<Self::Error>> {
+        let id = guard.allow_last().ok_or(Skipped)?;
+        let node = self.resolve(id, ctx);
+        let ast = &ctx.ast;
+        let module = enclosing_module(id.widen(), ast).ok_or(Skipped)?;
+
+        match node.as_enum() {
+            OperationEnum::Record(x) => {
+                let fields = x.fields(ast);
+                check_duplicates(&fields, &ctx.rlt)?;
+                // unknown types are reported by the scope analyzer
+                let decl = structs(module, ast)
+                    .find(|it| it.name == x.name)
+                    .ok_or(Skipped)?;
+                let declared = field_names(decl, ast);
+
+                if let Some(unknown) = fields
+                    .iter()
+                    .find(|it| !declared.contains(&it.name.as_ref()))
+                {
+                    let error = RecordError::UnknownField {
+                        name: x.name.to_string(),
+                        field: unknown.name.to_string(),
+                    };
+                    let Some(location) = ctx.rlt.get_unknown(unknown.get_id()) else {
+                        warn_about_broken_rlt::<FieldInit>();
+                        return Err(Skipped);
+                    };
+                    let location = location.location();
+                    let error = SpannedError::new(error, location);
+                    return match closest_name(&unknown.name, &declared) {
+                        Some(closest) => Err(Failed(error.with_suggestion(Suggestion::replace(
+                            "a field with a similar name exists",
+                            location,
+                            closest.to_string(),
+                            Applicability::MaybeIncorrect,
+                        )))),
+                        None => Err(Failed(error)),
+                    };
+                }
+                let missing: Vec<_> = declared
+                    .into_iter()
+                    .filter(|name| !fields.iter().any(|it| it.name.as_ref() == *name))
+                    .collect();
+                if missing.is_empty() {
+                    Ok(())
+                } else {
+                    let error = RecordError::MissingFields {
+                        name: x.name.to_string(),
+                        fields: missing.into_iter().map(|it| format!("`{it}`")).join(", "),
+                    };
+                    Err(spanned(error, id, &ctx.rlt))
+                }
+            }
+            OperationEnum::Update(x) => {
+                let fields = x.fields(ast);
+                check_duplicates(&fields, &ctx.rlt)?;
+                // The updated struct is known only after type inference,
+                // so here every field has to be declared at least somewhere
+                let declared: Vec<_> = structs(module, ast)
+                    .flat_map(|it| field_names(it, ast))
+                    .collect();
+                match fields
+                    .iter()
+                    .find(|it| !declared.contains(&it.name.as_ref()))
+                {
+                    None => Ok(()),
+                    Some(unknown) => Err(spanned(
+                        RecordError::UndeclaredField(unknown.name.to_string()),
+                        unknown.get_id(),
+                        &ctx.rlt,
+                    )),
+                }
+            }
+            _ => Err(Skipped),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::record::{closest_name, field_names, find_duplicate, structs};
+    use crate::testing::{build, module, parse};
+    use kodept_ast::graph::AnyNode;
+    use kodept_ast::visit_side::VisitSide;
+    use rstest::rstest;
+
+    const SOURCE: &str = "module Records {
+        struct Point(x: Int, y: Int)
+        fun main() {
+            val a = Point { x = 1, y = 2 }
+            val b = Point { x = 1, x = 2, y = 3 }
+            val c = a with { y = 2, x = 1, y = 3 }
+            b
+        }
+    }";
+
+    #[test]
+    fn test_duplicate_fields() {
+        let rlt = parse(SOURCE);
+        let ast = build(&rlt, SOURCE);
+
+        let duplicates: Vec<_> = ast
+            .dfs()
+            .filter(|(_, side)| *side != VisitSide::Exiting)
+            .filter_map(|(id, _)| match ast.get(id)? {
+                AnyNode::RecordLit(x) => Some(x.fields(&ast)),
+                AnyNode::RecordUpd(x) => Some(x.fields(&ast)),
+                _ => None,
+            })
+            .map(|fields| find_duplicate(&fields).map(|it| it.name.to_string()))
+            .collect();
+        assert_eq!(duplicates, [None, Some("x".into()), Some("y".into())]);
+    }
+
+    #[test]
+    fn test_declared_fields() {
+        let rlt = parse(SOURCE);
+        let ast = build(&rlt, SOURCE);
+        let module = module(&ast, "Records");
+
+        let fields: Vec<_> = structs(module, &ast)
+            .flat_map(|it| field_names(it, &ast))
+            .collect();
+        assert_eq!(fields, ["x", "y"]);
+    }
+
+    #[rstest]
+    #[case("z", None)]
+    #[case("heigth", Some("height"))]
+    #[case("widt", Some("width"))]
+    #[case("colour", None)]
+    fn test_closest_name(#[case] name: &str, #[case] expected: Option<&str>) {
+        assert_eq!(closest_name(name, &["width", "height"]), expected);
+    }
+}
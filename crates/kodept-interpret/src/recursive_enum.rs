@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use kodept_ast::graph::{AnyNode, SyntaxTree};
+use kodept_ast::traits::AsEnum;
+use kodept_ast::utils::Skip;
+use kodept_ast::utils::Skip::{Failed, Skipped};
+use kodept_ast::{EnumDecl, EnumKind, ModDecl, TopLevelEnum, Type, TypeEnum};
+use kodept_core::structure::{rlt, Located};
+use kodept_macros::context::Context;
+use kodept_macros::error::code::{CodedError, ErrorCode, KC071};
+use kodept_macros::error::traits::SpannedError;
+use kodept_macros::visit_guard::VisitGuard;
+use kodept_macros::{warn_about_broken_rlt, Macro, MacroExt};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum RecursiveEnumError {
+    #[error("Enum `{0}` contains itself and has infinite size, declare it as `enum class` to store it on the heap")]
+    InfiniteSize(String),
+}
+
+impl CodedError for RecursiveEnumError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            RecursiveEnumError::InfiniteSize(_) => KC071,
+        }
+    }
+}
+
+/// Only `enum class` values are boxed, so only they may refer to themselves,
+/// either directly or through other enums and type aliases
+#[derive(Default)]
+pub struct RecursiveEnumChecker {
+    // module name -> `enum struct` or type alias -> types it stores inline
+    contents: HashMap<String, HashMap<String, Vec<String>>>,
+}
+
+impl RecursiveEnumChecker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn contents_of(&mut self, module: &ModDecl, ast: &SyntaxTree) -> &HashMap<String, Vec<String>> {
+        self.contents
+            .entry(module.name.to_string())
+            .or_insert_with(|| {
+                let heap: Vec<_> = module
+                    .contents(ast)
+                    .into_iter()
+                    .filter_map(|it| match it.as_enum() {
+                        TopLevelEnum::Enum(x) if x.kind == EnumKind::Heap => Some(x.name.as_ref()),
+                        _ => None,
+                    })
+                    .collect();
+                module
+                    .contents(ast)
+                    .into_iter()
+                    .filter_map(|it| match it.as_enum() {
+                        TopLevelEnum::Enum(x) if x.kind == EnumKind::Stack => {
+                            let mut inline = vec![];
+                            for ty in payloads(x, ast) {
+                                stored_inline(ty, &heap, &mut inline, ast);
+                            }
+                            Some((x.name.to_string(), inline))
+                        }
+                        TopLevelEnum::TyAlias(x) => {
+                            let mut inline = vec![];
+                            stored_inline(x.aliased(ast), &heap, &mut inline, ast);
+                            Some((x.name.to_string(), inline))
+                        }
+                        _ => None,
+                    })
+                    .collect()
+            })
+    }
+}
+
+fn payloads<'a>(decl: &'a EnumDecl, ast: &'a SyntaxTree) -> Vec<&'a Type> {
+    decl.contents(ast)
+        .into_iter()
+        .flat_map(|variant| {
+            let fields = variant.fields(ast);
+            fields
+                .into_iter()
+                .map(|it| it.parameter_type(ast))
+                .chain(variant.payload(ast))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Collects names of types whose values are stored inside values of `ty`.
+/// Values of `enum class` are pointers, so their arguments are not stored inline
+fn stored_inline(ty: &Type, heap: &[&str], output: &mut Vec<String>, ast: &SyntaxTree) {
+    match ty.as_enum() {
+        TypeEnum::TyName(x) => output.push(x.name.to_string()),
+        TypeEnum::Tuple(x) => {
+            for item in x.types(ast) {
+                stored_inline(item, heap, output, ast);
+            }
+        }
+        TypeEnum::App(x) if heap.contains(&x.name.as_ref()) => {}
+        TypeEnum::App(x) => {
+            output.push(x.name.to_string());
+            for item in x.arguments(ast) {
+                stored_inline(item, heap, output, ast);
+            }
+        }
+    }
+}
+
+/// Returns the path from `from` back to `to`, which does not include `to` itself
+fn find_cycle<'a>(
+    from: &'a str,
+    to: &str,
+    contents: &'a HashMap<String, Vec<String>>,
+    visited: &mut Vec<&'a str>,
+) -> Option<Vec<&'a str>> {
+    let inline = contents.get(from).map(Vec::as_slice).unwrap_or_default();
+    if inline.iter().any(|it| it == to) {
+        return Some(vec![from]);
+    }
+    for next in inline {
+        if visited.contains(&next.as_str()) {
+            continue;
+        }
+        visited.push(next);
+        if let Some(mut path) = find_cycle(next, to, contents, visited) {
+            path.insert(0, from);
+            return Some(path);
+        }
+    }
+    None
+}
+
+impl Macro for RecursiveEnumChecker {
+    type Error = SpannedError<RecursiveEnumError>;
+    type Node = EnumDecl;
+    type Ctx<'a> = Context<'a>;
+
+    fn apply(
+        &mut self,
+        guard: VisitGuard<Self::Node>,
+        ctx: &mut Self::Ctx<'_>,
+    ) -> Result<(), Skip<Self::Error>> {
+        let id = guard.allow_last().ok_or(Skipped)?;
+        let node = self.resolve(id, ctx);
+        if node.kind == EnumKind::Heap {
+            return Err(Skipped);
+        }
+        let Some(AnyNode::ModDecl(module)) = ctx.ast.parent_of(id) else {
+            return Err(Skipped);
+        };
+
+        let contents = self.contents_of(module, &ctx.ast);
+        let Some(path) = find_cycle(&node.name, &node.name, contents, &mut vec![]) else {
+            return Ok(());
+        };
+        let Some(decl) = ctx.rlt.get::<_, rlt::Enum>(id) else {
+            warn_about_broken_rlt::<rlt::Enum>();
+            return Err(Skipped);
+        };
+        let error = RecursiveEnumError::InfiniteSize(node.name.to_string());
+        let error = SpannedError::new(error, decl.id().location());
+        match path[..] {
+            [_] => Err(Failed(error)),
+            _ => {
+                let cycle = path
+                    .iter()
+                    .chain([&path[0]])
+                    .map(|it| format!("`{it}`"))
+                    .join(" -> ");
+                Err(Failed(error.with_note(format!(
+                    "cycle of types stored inline: {cycle}"
+                ))))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::recursive_enum::{find_cycle, RecursiveEnumChecker};
+    use crate::testing::{build, module, parse};
+    use rstest::rstest;
+
+    const SOURCE: &str = "module Shapes {
+        enum struct List { Nil, Cons(Int, List) }
+        enum struct Tree { Leaf, Node(Forest) }
+        enum struct Forest { Empty, Trees(Tree, Forest) }
+        type Wrapped = (Int, Cell)
+        enum struct Cell { Value(Wrapped) }
+        enum class Expr { Lit(Int), Neg(Operand) }
+        enum struct Operand { Inner(Expr) }
+        enum class Boxed<T> { Value(T) }
+        enum struct Holder { Held(Boxed<Holder>) }
+        struct Pair<A, B>(first: A, second: B)
+        enum struct Link { Next(Pair<Int, Link>) }
+    }";
+
+    #[rstest]
+    #[case("List", Some(vec!["List"]))]
+    #[case("Tree", Some(vec!["Tree", "Forest"]))]
+    #[case("Forest", Some(vec!["Forest"]))]
+    #[case("Cell", Some(vec!["Cell", "Wrapped"]))]
+    #[case("Operand", None)]
+    #[case("Holder", None)]
+    #[case("Link", Some(vec!["Link"]))]
+    fn test_find_cycle(#[case] name: &str, #[case] expected: Option<Vec<&str>>) {
+        let rlt = parse(SOURCE);
+        let ast = build(&rlt, SOURCE);
+        let mut checker = RecursiveEnumChecker::new();
+        let contents = checker.contents_of(module(&ast, "Shapes"), &ast);
+
+        assert_eq!(find_cycle(name, name, contents, &mut vec![]), expected);
+    }
+}
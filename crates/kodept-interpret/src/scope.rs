@@ -5,6 +5,7 @@ use derive_more::Display;
 use kodept_ast::graph::{AnyNodeId, Identifiable, NodeId, SyntaxTree};
 use kodept_ast::interning::SharedStr;
 use kodept_inference::r#type::PolymorphicType;
+use kodept_macros::error::code::{CodedError, ErrorCode, KC020, KC021};
 use std::collections::BTreeSet;
 use std::fmt::Debug;
 use std::iter;
@@ -14,6 +15,12 @@ use thiserror::Error;
 #[display("Cannot get outer scope for root one")]
 pub struct ScopePeelError;
 
+impl CodedError for ScopePeelError {
+    fn code(&self) -> ErrorCode {
+        KC021
+    }
+}
+
 #[derive(Display, Debug, Error)]
 pub enum ScopeError {
     #[display("No scope available at this point")]
@@ -22,6 +29,15 @@ pub enum ScopeError {
     Duplicate(String),
 }
 
+impl CodedError for ScopeError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            ScopeError::NoScope => KC021,
+            ScopeError::Duplicate(_) => KC020,
+        }
+    }
+}
+
 type Index = usize;
 
 pub struct ScopeBuilder {
@@ -1,8 +1,12 @@
-use crate::scope::{ScopeBuilder, ScopePeelError, ScopeV2};
-use kodept_ast::graph::{AnyNode, Identifiable};
+use crate::constructor::{constructor_type, projection_type};
+use crate::scope::{ScopeBuilder, ScopeError, ScopePeelError, ScopeV2};
+use crate::symbol::{SymbolKind, SymbolV2};
+use kodept_ast::graph::{AnyNode, Identifiable, SyntaxTree};
 use kodept_ast::utils::Skip;
 use kodept_ast::visit_side::VisitSide;
-use kodept_ast::{AbstFnDecl, BodyFnDecl, EnumDecl, Exprs, ModDecl, StructDecl};
+use kodept_ast::{
+    AbstFnDecl, BodyFnDecl, EnumDecl, Exprs, ModDecl, ReferenceContext, StructDecl, TyAliasDecl,
+};
 use kodept_macros::context::Context;
 use kodept_macros::error::report::Severity;
 use kodept_macros::error::traits::SpannedError;
@@ -50,12 +54,19 @@ impl ScopeAnalyzer {
             AnyNode::NumLit(_) => None,
             AnyNode::CharLit(_) => None,
             AnyNode::StrLit(_) => None,
+            AnyNode::InterpStrLit(_) => None,
             AnyNode::TupleLit(_) => None,
             AnyNode::ElifExpr(_) => None,
             AnyNode::ElseExpr(_) => None,
             AnyNode::BinExpr(_) => None,
             AnyNode::UnExpr(_) => None,
             AnyNode::ProdTy(_) => None,
+            AnyNode::TyAliasDecl(_) => None,
+            AnyNode::TyApp(_) => None,
+            AnyNode::VariantDecl(_) => None,
+            AnyNode::RecordLit(_) => None,
+            AnyNode::RecordUpd(_) => None,
+            AnyNode::FieldInit(_) => None,
             // do not put `_` here, process each new case individually
         };
 
@@ -79,17 +90,72 @@ impl ScopeAnalyzer {
     }
 }
 
-fn extract_symbols(destination_scope: &mut ScopeV2, node: &AnyNode) {
+fn extract_symbols(
+    destination_scope: &mut ScopeV2,
+    node: &AnyNode,
+    ast: &SyntaxTree,
+) -> Result<(), ScopeError> {
     let id = node.get_id();
 
     match node {
         AnyNode::FileDecl(_) => {}
         AnyNode::ModDecl(_) => {}
         AnyNode::StructDecl(StructDecl { name, .. }) => {
-            // destination_scope.insert_symbol(SymbolV2::new(id, ))
+            let symbol = SymbolV2::new(
+                id,
+                ReferenceContext::local(Vec::<String>::new()),
+                name.clone(),
+                SymbolKind::Type,
+            );
+            if destination_scope.insert_symbol(symbol).is_some() {
+                return Err(ScopeError::Duplicate(name.to_string()));
+            }
+        }
+        AnyNode::EnumDecl(decl) => {
+            // The enum scope is already left, so constructors are visible in the enclosing module
+            let Some(AnyNode::ModDecl(module)) = ast.parent_of(id) else {
+                return Ok(());
+            };
+            for variant in decl.contents(ast) {
+                let symbol = SymbolV2::new(
+                    variant.get_id().widen(),
+                    ReferenceContext::local(Vec::<String>::new()),
+                    variant.name.clone(),
+                    SymbolKind::Function,
+                );
+                // Cyclic aliases are reported by `TypeAliasExpander`
+                let symbol = match constructor_type(module, decl, variant, ast) {
+                    Ok(ty) => symbol.with_type(ty),
+                    Err(_) => symbol,
+                };
+                if destination_scope.insert_symbol(symbol).is_some() {
+                    return Err(ScopeError::Duplicate(variant.name.to_string()));
+                }
+            }
+        }
+        AnyNode::TyParam(field) => {
+            // Only parameters of a struct are its fields, they stay inside the struct scope
+            let Some(AnyNode::StructDecl(decl)) = ast.parent_of(id) else {
+                return Ok(());
+            };
+            let Some(AnyNode::ModDecl(module)) = ast.parent_of(decl.get_id().widen()) else {
+                return Ok(());
+            };
+            let symbol = SymbolV2::new(
+                id,
+                ReferenceContext::local(Vec::<String>::new()),
+                field.name.clone(),
+                SymbolKind::Field,
+            );
+            // Cyclic aliases are reported by `TypeAliasExpander`
+            let symbol = match projection_type(module, decl, field, ast) {
+                Ok(ty) => symbol.with_type(ty),
+                Err(_) => symbol,
+            };
+            if destination_scope.insert_symbol(symbol).is_some() {
+                return Err(ScopeError::Duplicate(field.name.to_string()));
+            }
         }
-        AnyNode::EnumDecl(_) => {}
-        AnyNode::TyParam(_) => {}
         AnyNode::NonTyParam(_) => {}
         AnyNode::TyName(_) => {}
         AnyNode::VarDecl(_) => {}
@@ -103,15 +169,43 @@ fn extract_symbols(destination_scope: &mut ScopeV2, node: &AnyNode) {
         AnyNode::NumLit(_) => {}
         AnyNode::CharLit(_) => {}
         AnyNode::StrLit(_) => {}
+        AnyNode::InterpStrLit(_) => {}
         AnyNode::TupleLit(_) => {}
         AnyNode::IfExpr(_) => {}
         AnyNode::ElifExpr(_) => {}
         AnyNode::ElseExpr(_) => {}
         AnyNode::BinExpr(_) => {}
         AnyNode::UnExpr(_) => {}
-        AnyNode::AbstFnDecl(_) => {}
+        AnyNode::AbstFnDecl(AbstFnDecl { name, .. }) => {
+            let symbol = SymbolV2::new(
+                id,
+                ReferenceContext::local(Vec::<String>::new()),
+                name.clone(),
+                SymbolKind::Function,
+            );
+            if destination_scope.insert_symbol(symbol).is_some() {
+                return Err(ScopeError::Duplicate(name.to_string()));
+            }
+        }
         AnyNode::ProdTy(_) => {}
+        AnyNode::TyApp(_) => {}
+        AnyNode::VariantDecl(_) => {}
+        AnyNode::RecordLit(_) => {}
+        AnyNode::RecordUpd(_) => {}
+        AnyNode::FieldInit(_) => {}
+        AnyNode::TyAliasDecl(TyAliasDecl { name, .. }) => {
+            let symbol = SymbolV2::new(
+                id,
+                ReferenceContext::local(Vec::<String>::new()),
+                name.clone(),
+                SymbolKind::Type,
+            );
+            if destination_scope.insert_symbol(symbol).is_some() {
+                return Err(ScopeError::Duplicate(name.to_string()));
+            }
+        }
     }
+    Ok(())
 }
 
 impl Macro for ScopeAnalyzer {
@@ -138,7 +232,9 @@ impl Macro for ScopeAnalyzer {
         let scope = self.builder.current_scope_mut();
         dbg!(&scope);
 
-        extract_symbols(scope, node);
+        if let Err(e) = extract_symbols(scope, node, &ctx.ast) {
+            ctx.report(SpannedError::for_node(e, id, &ctx.rlt));
+        }
 
         Ok(())
     }
@@ -15,7 +15,8 @@ pub enum SymbolKind {
     Variable,
     Parameter,
     Constant,
-    Function
+    Function,
+    Field,
 }
 
 #[derive(Debug)]
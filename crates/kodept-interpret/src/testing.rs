@@ -0,0 +1,44 @@
+//! Syntax trees built from sources for tests
+
+use kodept_ast::graph::{NodeId, SyntaxTree};
+use kodept_ast::interning::InterningCodeHolder;
+use kodept_ast::{FileDecl, ModDecl};
+use kodept_core::code_point::CodePoint;
+use kodept_core::structure::rlt::RLT;
+use kodept_core::structure::span::CodeHolder;
+use kodept_parse::common::RLTProducer;
+use kodept_parse::lexer::PegLexer;
+use kodept_parse::parser::PegParser;
+use kodept_parse::token_stream::PackedTokenStream;
+use kodept_parse::tokenizer::{LazyTokenizer, Tok, TokCtor};
+use std::borrow::Cow;
+
+#[derive(Copy, Clone)]
+struct Source<'a>(&'a str);
+
+impl<'a> CodeHolder for Source<'a> {
+    type Str = Cow<'a, str>;
+
+    fn get_chunk(self, at: CodePoint) -> Self::Str {
+        Cow::Borrowed(&self.0[at.as_range()])
+    }
+}
+
+pub(crate) fn parse(source: &str) -> RLT {
+    let tokens = LazyTokenizer::new(source, PegLexer::<false>::new()).into_vec();
+    PegParser::<false>::new()
+        .parse_stream(&PackedTokenStream::new(&tokens))
+        .expect("Program should be well-formed")
+}
+
+pub(crate) fn build(rlt: &RLT, source: &str) -> SyntaxTree {
+    SyntaxTree::recursively_build(rlt, InterningCodeHolder::new(Source(source))).0
+}
+
+pub(crate) fn module<'a>(ast: &'a SyntaxTree, name: &str) -> &'a ModDecl {
+    let file: &FileDecl = ast.get(NodeId::Root).expect("Tree should have a root");
+    file.modules(ast)
+        .into_iter()
+        .find(|it| it.name.as_ref() == name)
+        .expect("Module should be declared")
+}
@@ -0,0 +1,205 @@
+use std::collections::HashMap;
+
+use kodept_ast::graph::{AnyNode, SyntaxTree};
+use kodept_ast::traits::AsEnum;
+use kodept_ast::utils::Skip;
+use kodept_ast::utils::Skip::{Failed, Skipped};
+use kodept_ast::visit_side::VisitSide;
+use kodept_ast::{ModDecl, TopLevelEnum, TyAliasDecl, Type, TypeEnum};
+use kodept_ast::interning::SharedStr;
+use kodept_core::structure::{rlt, Located};
+use kodept_inference::r#type::{app, var, MonomorphicType, Tuple};
+use kodept_macros::context::Context;
+use kodept_macros::error::code::{CodedError, ErrorCode, KC070};
+use kodept_macros::error::traits::SpannedError;
+use kodept_macros::visit_guard::VisitGuard;
+use kodept_macros::{warn_about_broken_rlt, Macro, MacroExt};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum TypeAliasError {
+    #[error("Type alias `{0}` refers to itself")]
+    SelfReferential(String),
+}
+
+impl CodedError for TypeAliasError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            TypeAliasError::SelfReferential(_) => KC070,
+        }
+    }
+}
+
+/// Expands type aliases declared in modules to the types they stand for.
+/// Expansions are not applied to inferred types yet, as type checking is disabled
+#[derive(Default)]
+pub struct TypeAliasExpander {
+    // module name -> alias name -> expanded type
+    expansions: HashMap<String, HashMap<String, MonomorphicType>>,
+}
+
+/// Converts type annotations to monomorphic types, following aliases declared in a module
+pub(crate) struct AliasResolver<'a> {
+    ast: &'a SyntaxTree,
+    aliases: HashMap<&'a str, &'a TyAliasDecl>,
+    generics: Vec<&'a str>,
+    visiting: Vec<&'a str>,
+}
+
+impl TypeAliasExpander {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the type alias `name` from `module` stands for
+    pub fn expand(&self, module: &str, name: &str) -> Option<&MonomorphicType> {
+        self.expansions.get(module)?.get(name)
+    }
+
+    /// Converts a type annotation written in `module` to a monomorphic type,
+    /// replacing aliases with their expansions.
+    /// Type parameters in scope become type variables numbered by their position.
+    /// Returns `None` if the type refers to a cyclic alias
+    pub fn resolve_type(
+        &self,
+        module: &ModDecl,
+        ty: &Type,
+        generics: &[SharedStr],
+        ast: &SyntaxTree,
+    ) -> Option<MonomorphicType> {
+        match ty.as_enum() {
+            TypeEnum::TyName(ty) => {
+                if let Some(index) = generics.iter().position(|it| *it == ty.name) {
+                    return Some(var(index));
+                }
+                if let Some(expansion) = self.expand(&module.name, &ty.name) {
+                    return Some(expansion.clone());
+                }
+                let is_alias = module.contents(ast).into_iter().any(|it| {
+                    matches!(it.as_enum(), TopLevelEnum::TyAlias(x) if x.name == ty.name)
+                });
+                // Aliases have no expansion only if they are cyclic
+                (!is_alias).then(|| MonomorphicType::Constant(ty.name.to_string()))
+            }
+            TypeEnum::Tuple(ty) => {
+                let mut tuple = Tuple::unit();
+                for item in ty.types(ast) {
+                    tuple.push(self.resolve_type(module, item, generics, ast)?);
+                }
+                Some(MonomorphicType::Tuple(tuple))
+            }
+            TypeEnum::App(ty) => {
+                let arguments: Vec<_> = ty
+                    .arguments(ast)
+                    .into_iter()
+                    .map(|it| self.resolve_type(module, it, generics, ast))
+                    .collect::<Option<_>>()?;
+                Some(app(ty.name.to_string(), arguments))
+            }
+        }
+    }
+}
+
+impl<'a> AliasResolver<'a> {
+    pub(crate) fn new(module: &'a ModDecl, ast: &'a SyntaxTree) -> Self {
+        let aliases = module
+            .contents(ast)
+            .into_iter()
+            .filter_map(|it| match it.as_enum() {
+                TopLevelEnum::TyAlias(alias) => Some((alias.name.as_ref(), alias)),
+                _ => None,
+            })
+            .collect();
+        Self {
+            ast,
+            aliases,
+            generics: vec![],
+            visiting: vec![],
+        }
+    }
+
+    /// Type parameters in scope become type variables numbered by their position
+    pub(crate) fn with_generics(mut self, generics: &'a [SharedStr]) -> Self {
+        self.generics = generics.iter().map(|it| it.as_ref()).collect();
+        self
+    }
+
+    /// Returns the name of the alias a cycle was found at
+    pub(crate) fn expand(&mut self, ty: &'a Type) -> Result<MonomorphicType, &'a str> {
+        match ty.as_enum() {
+            TypeEnum::TyName(ty) => {
+                if let Some(index) = self.generics.iter().position(|it| *it == ty.name.as_ref()) {
+                    return Ok(var(index));
+                }
+                let Some(alias) = self.aliases.get(ty.name.as_ref()).copied() else {
+                    return Ok(MonomorphicType::Constant(ty.name.to_string()));
+                };
+                if self.visiting.contains(&alias.name.as_ref()) {
+                    return Err(&alias.name);
+                }
+                self.visiting.push(&alias.name);
+                let result = self.expand(alias.aliased(self.ast))?;
+                self.visiting.pop();
+                Ok(result)
+            }
+            TypeEnum::Tuple(ty) => {
+                let mut tuple = Tuple::unit();
+                for item in ty.types(self.ast) {
+                    tuple.push(self.expand(item)?);
+                }
+                Ok(MonomorphicType::Tuple(tuple))
+            }
+            TypeEnum::App(ty) => {
+                let arguments: Vec<_> = ty
+                    .arguments(self.ast)
+                    .into_iter()
+                    .map(|it| self.expand(it))
+                    .collect::<Result<_, _>>()?;
+                Ok(app(ty.name.to_string(), arguments))
+            }
+        }
+    }
+}
+
+impl Macro for TypeAliasExpander {
+    type Error = SpannedError<TypeAliasError>;
+    type Node = TyAliasDecl;
+    type Ctx<'a> = Context<'a>;
+
+    fn apply(
+        &mut self,
+        guard: VisitGuard<Self::Node>,
+        ctx: &mut Self::Ctx<'_>,
+    ) -> Result<(), Skip<Self::Error>> {
+        let id = guard.allow_only(VisitSide::Entering).ok_or(Skipped)?;
+        let node = self.resolve(id, ctx);
+        let Some(AnyNode::ModDecl(module)) = ctx.ast.parent_of(id) else {
+            return Err(Skipped);
+        };
+
+        let mut resolver = AliasResolver::new(module, &ctx.ast);
+        resolver.visiting.push(&node.name);
+
+        match resolver.expand(node.aliased(&ctx.ast)) {
+            Ok(ty) => {
+                self.expansions
+                    .entry(module.name.to_string())
+                    .or_default()
+                    .insert(node.name.to_string(), ty);
+                Ok(())
+            }
+            // Cycles not passing through this alias are reported at their own declarations
+            Err(name) if name == node.name.as_ref() => {
+                let error = TypeAliasError::SelfReferential(node.name.to_string());
+                match ctx.rlt.get::<_, rlt::TypeAlias>(id) {
+                    Some(alias) => Err(Failed(SpannedError::new(error, alias.id.location()))),
+                    None => {
+                        warn_about_broken_rlt::<rlt::TypeAlias>();
+                        Err(Skipped)
+                    }
+                }
+            }
+            Err(_) => Err(Skipped),
+        }
+    }
+}
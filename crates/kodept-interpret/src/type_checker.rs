@@ -5,11 +5,16 @@ use kodept_ast::rlt_accessor::RLTAccessor;
 use kodept_ast::BodyFnDecl;
 use kodept_core::code_point::CodePoint;
 use kodept_core::structure::{rlt, Located};
+use kodept_inference::algorithm_u::AlgorithmUError;
 use kodept_inference::algorithm_w::{AlgorithmWError, CompoundInferError};
+use kodept_inference::constraint::ConstraintsSolverError;
 use kodept_inference::language::{Language, Var};
 use kodept_inference::r#type::PolymorphicType;
 use kodept_inference::traits::EnvironmentProvider;
 use kodept_macros::context::Context;
+use kodept_macros::error::code::{
+    CodedError, ErrorCode, KC030, KC031, KC032, KC033, KC034, KC035, KC036, KC665,
+};
 use kodept_macros::error::report::{IntoSpannedReportMessage, Label, Severity};
 use kodept_macros::error::traits::SpannedError;
 use kodept_macros::error::Diagnostic;
@@ -44,7 +49,7 @@ impl<'a> IntoSpannedReportMessage for TypeInfo<'a> {
     type Message = Diagnostic;
 
     fn into_message(self) -> Self::Message {
-        Diagnostic::new(Severity::Note)
+        Diagnostic::new(Severity::Note, KC036)
             .with_message(format!(
                 "Type of function `{}` inferred to: {}",
                 self.name, self.ty
@@ -57,7 +62,7 @@ impl IntoSpannedReportMessage for CannotInfer {
     type Message = Diagnostic;
 
     fn into_message(self) -> Self::Message {
-        Diagnostic::new(Severity::Error)
+        Diagnostic::new(Severity::Error, KC034)
             .with_message("Cannot infer type")
             .with_label(Label::primary("here", self.point))
     }
@@ -112,6 +117,44 @@ impl From<SpannedError<RecursiveTypeCheckingError>> for RecursiveTypeCheckingErr
     }
 }
 
+fn algorithm_u_code(error: &AlgorithmUError) -> ErrorCode {
+    match error {
+        AlgorithmUError::UnificationFail(..) | AlgorithmUError::UnificationMismatch(_) => KC031,
+        AlgorithmUError::InfiniteType(..) => KC032,
+    }
+}
+
+fn algorithm_w_code(error: &AlgorithmWError) -> ErrorCode {
+    match error {
+        AlgorithmWError::AlgorithmU(e) => algorithm_u_code(e),
+        AlgorithmWError::UnknownVar(_) => KC030,
+        AlgorithmWError::FailedConstraints(ConstraintsSolverError::AlgorithmU(e)) => {
+            algorithm_u_code(e)
+        }
+        AlgorithmWError::FailedConstraints(_) => KC033,
+    }
+}
+
+impl CodedError for InferError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            InferError::AlgorithmW(e) => algorithm_w_code(e),
+            InferError::Scope(e) => e.code(),
+        }
+    }
+}
+
+impl CodedError for RecursiveTypeCheckingError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            NodeNotFound(_) | InconvertibleToModel(_) => KC665,
+            MutuallyRecursive => KC035,
+            RecursiveTypeCheckingError::ScopeError(e) => e.code(),
+            RecursiveTypeCheckingError::AlgoWError(e) => algorithm_w_code(e),
+        }
+    }
+}
+
 impl From<InferError> for RecursiveTypeCheckingError {
     fn from(value: InferError) -> Self {
         match value {
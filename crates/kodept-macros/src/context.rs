@@ -1,11 +1,14 @@
-use crate::error::report::{IntoSpannedReportMessage, Report};
+use crate::error::report::{IntoSpannedReportMessage, Report, Severity};
 use crate::error::report_collector::{ReportCollector, Reporter};
+use crate::error::Diagnostic;
+use crate::lint::{LintInfo, LintLevel, Lints};
 use kodept_ast::graph::tags::ChildTag;
 use kodept_ast::graph::{
     AnyNode, AnyNodeD, AnyNodeId, HasChildrenMarker, Identifiable, NodeId, SyntaxTree,
 };
 use kodept_ast::rlt_accessor::RLTAccessor;
 use kodept_ast::Uninit;
+use kodept_core::code_point::CodePoint;
 use kodept_core::file_name::FileName;
 use kodept_core::Freeze;
 use std::mem::replace;
@@ -25,6 +28,7 @@ pub struct Context<'r> {
     pub rlt: RLTAccessor<'r>,
     pub collector: &'r ReportCollector,
     pub current_file: Freeze<FileDescriptor>,
+    pub lints: Lints,
 }
 
 impl<'rlt> Context<'rlt> {
@@ -35,6 +39,7 @@ impl<'rlt> Context<'rlt> {
             .describe()
     }
 
+    #[allow(clippy::result_large_err)]
     pub fn report_and_fail<T>(
         &mut self,
         message: impl IntoSpannedReportMessage,
@@ -46,6 +51,28 @@ impl<'rlt> Context<'rlt> {
         self.collector.report(self.current_file.id, message)
     }
 
+    /// Reports a lint triggered at the given place with its configured level.
+    /// Nothing is reported if the lint is allowed there
+    pub fn lint(
+        &self,
+        info: &LintInfo,
+        at: CodePoint,
+        build: impl FnOnce(Diagnostic) -> Diagnostic,
+    ) {
+        let level = self.lints.level_at(info, at);
+        let severity = match level {
+            LintLevel::Allow => return,
+            LintLevel::Warn => Severity::Warning,
+            LintLevel::Deny => Severity::Error,
+        };
+        let origin = if self.lints.levels.is_default(info) {
+            format!("`{}` is set to {level} by default", info.name)
+        } else {
+            format!("`{}` is set to {level} by the configuration", info.name)
+        };
+        self.report(build(Diagnostic::new(severity, info.code)).with_note(origin.into()))
+    }
+
     #[allow(unsafe_code)]
     pub fn replace<T>(&mut self, node_id: NodeId<T>, value: Uninit<'rlt, T>) -> Option<Uninit<T>>
     where
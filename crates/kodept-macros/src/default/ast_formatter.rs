@@ -5,6 +5,7 @@ use kodept_ast::utils::Skip;
 use kodept_ast::visit_side::VisitSide;
 
 use crate::context::Context;
+use crate::error::code::{CodedError, ErrorCode, KC011};
 use crate::visit_guard::VisitGuard;
 use crate::{Macro, MacroExt};
 
@@ -17,6 +18,12 @@ pub struct ASTFormatter<W: Write> {
 #[error(transparent)]
 pub struct IOError(Error);
 
+impl CodedError for IOError {
+    fn code(&self) -> ErrorCode {
+        KC011
+    }
+}
+
 impl<W: Write> ASTFormatter<W> {
     pub fn new(writer: W) -> Self {
         Self { writer, indent: 0 }
@@ -1,4 +1,5 @@
 use crate::context::Context;
+use crate::error::code::{CodedError, ErrorCode, KC011};
 use crate::visit_guard::VisitGuard;
 use crate::Macro;
 use kodept_ast::FileDecl;
@@ -16,6 +17,12 @@ pub struct ASTDotFormatter<W> {
 #[error(transparent)]
 pub struct Error(std::io::Error);
 
+impl CodedError for Error {
+    fn code(&self) -> ErrorCode {
+        KC011
+    }
+}
+
 impl<W: Write> Macro for ASTDotFormatter<W> {
     type Error = Error;
     type Node = FileDecl;
@@ -0,0 +1,183 @@
+use derive_more::Display;
+
+/// Stable identifier of a diagnostic, described in details by `kodept explain`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Display)]
+#[display("{code}")]
+pub struct ErrorCode {
+    code: &'static str,
+    explanation: &'static str,
+}
+
+/// Error that is reported with a stable code
+pub trait CodedError {
+    fn code(&self) -> ErrorCode;
+}
+
+macro_rules! error_codes {
+    ($($(#[$meta:meta])* $name:ident,)+) => {
+        $(
+            $(#[$meta])*
+            pub const $name: ErrorCode = ErrorCode {
+                code: stringify!($name),
+                explanation: include_str!(concat!("codes/", stringify!($name), ".md")),
+            };
+        )+
+
+        impl ErrorCode {
+            pub const ALL: &'static [ErrorCode] = &[$($name),+];
+        }
+    };
+}
+
+error_codes! {
+    /// Invalid usage of the command line interface
+    KC000,
+    /// Token that cannot appear at this place
+    KC001,
+    /// File ended in the middle of a declaration
+    KC002,
+    /// Sources cannot be found
+    KC010,
+    /// Sources cannot be read
+    KC011,
+    /// Project manifest cannot be parsed
+    KC012,
+    /// Lint with such a name does not exist
+    KC013,
+    /// Name is defined twice in the same scope
+    KC020,
+    /// Scope is missing during analysis
+    KC021,
+    /// Reference to an undefined name
+    KC030,
+    /// Types cannot be unified
+    KC031,
+    /// Type refers to itself
+    KC032,
+    /// Constraints on types cannot be satisfied
+    KC033,
+    /// Type of a function cannot be inferred
+    KC034,
+    /// Functions are type checked with mutual recursion
+    KC035,
+    /// Inferred type of a function
+    KC036,
+    /// Type is applied to a wrong number of arguments
+    KC040,
+    /// Type argument takes a different number of arguments than expected
+    KC041,
+    /// Struct does not have such a field
+    KC050,
+    /// No struct declares such a field
+    KC051,
+    /// Not all fields are initialized
+    KC052,
+    /// Field is initialized more than once
+    KC053,
+    /// Integer literal does not fit in 64 bits
+    KC060,
+    /// Floating literal is out of range
+    KC061,
+    /// Unknown escape sequence
+    KC062,
+    /// Invalid unicode scalar value
+    KC063,
+    /// Integer literal has no valid digits
+    KC064,
+    /// Type alias refers to itself
+    KC070,
+    /// Enum contains itself without indirection
+    KC071,
+    /// File is not formatted
+    KC080,
+    /// Variable is never used
+    KC090,
+    /// Function is never called
+    KC091,
+    /// Binding shadows another one
+    KC092,
+    /// `else` branch is never executed
+    KC093,
+    /// Type name is not written in `PascalCase`
+    KC094,
+    /// Compiler is in an inconsistent state
+    KC665,
+    /// Compiler crashed
+    KC666,
+}
+
+impl ErrorCode {
+    pub const fn code(&self) -> &'static str {
+        self.code
+    }
+
+    /// Long-form description of the error in markdown, along with an example
+    pub const fn explanation(&self) -> &'static str {
+        self.explanation
+    }
+
+    /// Short description taken from the header of the explanation
+    pub fn title(&self) -> &'static str {
+        let header = self.explanation.lines().next().unwrap_or_default();
+        header
+            .trim_start_matches('#')
+            .trim_start()
+            .trim_start_matches(self.code)
+            .trim_start_matches(':')
+            .trim()
+    }
+
+    /// Looks up a code ignoring case, so both `KC001` and `kc001` are found
+    pub fn find(code: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .find(|it| it.code.eq_ignore_ascii_case(code))
+            .copied()
+    }
+}
+
+impl CodedError for std::convert::Infallible {
+    fn code(&self) -> ErrorCode {
+        match *self {}
+    }
+}
+
+impl CodedError for std::io::Error {
+    fn code(&self) -> ErrorCode {
+        KC011
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use crate::error::code::{ErrorCode, KC001};
+
+    #[test]
+    fn test_codes_are_sorted_and_unique() {
+        let codes: Vec<_> = ErrorCode::ALL.iter().map(|it| it.code()).collect();
+
+        assert!(codes.windows(2).all(|it| it[0] < it[1]));
+    }
+
+    #[test]
+    fn test_every_code_is_explained() {
+        for code in ErrorCode::ALL {
+            assert!(
+                code.explanation().starts_with(&format!("# {code}")),
+                "Explanation of {code} should start with its header"
+            );
+        }
+    }
+
+    #[test]
+    fn test_find_ignores_case() {
+        assert_eq!(ErrorCode::find("kc001").unwrap(), KC001);
+        assert_eq!(ErrorCode::find("KC999"), None);
+    }
+
+    #[test]
+    fn test_title_is_taken_from_header() {
+        assert_eq!(KC001.title(), "Unexpected token");
+    }
+}
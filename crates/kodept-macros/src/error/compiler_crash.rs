@@ -1,7 +1,9 @@
 use std::any::Any;
+use std::fmt::{Display, Formatter};
 
 use derive_more::Constructor;
 
+use crate::error::code::KC666;
 use crate::error::report::{ReportMessage, Severity};
 
 #[derive(Constructor)]
@@ -9,15 +11,20 @@ pub struct CompilerCrash {
     message: Box<dyn Any + Send>,
 }
 
+impl Display for CompilerCrash {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(s) = self.message.downcast_ref::<String>() {
+            write!(f, "{s}")
+        } else if let Some(s) = self.message.downcast_ref::<&str>() {
+            write!(f, "{s}")
+        } else {
+            write!(f, "Unknown panic happened")
+        }
+    }
+}
+
 impl From<CompilerCrash> for ReportMessage {
     fn from(value: CompilerCrash) -> Self {
-        let message: String = if let Some(s) = value.message.downcast_ref::<String>() {
-            s.clone()
-        } else if let Some(s) = value.message.downcast_ref::<&str>() {
-            s.to_string()
-        } else {
-            "Unknown panic happened".to_string()
-        };
-        ReportMessage::new(Severity::Bug, "KC666", message)
+        ReportMessage::new(Severity::Bug, KC666, value.to_string())
     }
 }
@@ -1,13 +1,18 @@
-use std::borrow::Cow;
+use crate::error::code::ErrorCode;
+use crate::error::report::{IntoSpannedReportMessage, Label, Severity, SpannedReportMessage};
+use crate::error::suggestion::Suggestion;
 use derive_more::Display;
+use kodept_core::code_point::CodePoint;
+use std::borrow::Cow;
 use std::error::Error;
 use std::fmt::Formatter;
-use kodept_core::code_point::CodePoint;
-use crate::error::report::{IntoSpannedReportMessage, Label, Severity, SpannedReportMessage};
 
+pub mod code;
 pub mod compiler_crash;
 pub mod report;
 pub mod report_collector;
+pub mod structured;
+pub mod suggestion;
 pub mod traits;
 
 #[derive(Debug, Default)]
@@ -46,16 +51,20 @@ pub struct Diagnostic {
     message: Cow<'static, str>,
     labels: Vec<Label>,
     notes: Vec<Cow<'static, str>>,
-    severity: Severity
+    suggestions: Vec<Suggestion>,
+    severity: Severity,
+    code: ErrorCode,
 }
 
 impl Diagnostic {
-    pub fn new(severity: Severity) -> Self {
+    pub fn new(severity: Severity, code: ErrorCode) -> Self {
         Self {
             message: Default::default(),
             labels: Default::default(),
             notes: Default::default(),
+            suggestions: Default::default(),
             severity,
+            code,
         }
     }
 
@@ -75,6 +84,11 @@ impl Diagnostic {
         self.notes.push(note);
         self
     }
+
+    pub fn with_suggestion(mut self, suggestion: Suggestion) -> Self {
+        self.suggestions.push(suggestion);
+        self
+    }
 }
 
 impl IntoSpannedReportMessage for Diagnostic {
@@ -86,7 +100,7 @@ impl IntoSpannedReportMessage for Diagnostic {
 }
 
 impl SpannedReportMessage for Diagnostic {
-    fn labels(&self) -> impl IntoIterator<Item=Label> {
+    fn labels(&self) -> impl IntoIterator<Item = Label> {
         self.labels.clone()
     }
 
@@ -94,16 +108,25 @@ impl SpannedReportMessage for Diagnostic {
         self.severity
     }
 
+    fn code(&self) -> ErrorCode {
+        self.code
+    }
+
     fn message(&self) -> Cow<'static, str> {
         self.message.clone()
     }
 
-    fn notes(&self) -> impl IntoIterator<Item=Cow<'static, str>> {
+    fn notes(&self) -> impl IntoIterator<Item = Cow<'static, str>> {
         self.notes.clone()
     }
 
+    fn suggestions(&self) -> impl IntoIterator<Item = Suggestion> {
+        self.suggestions.clone()
+    }
+
     fn with_node_location(mut self, location: CodePoint) -> impl IntoSpannedReportMessage {
-        self.labels.push(Label::secondary("while checking here", location));
+        self.labels
+            .push(Label::secondary("while checking here", location));
         self
     }
 }
@@ -1,8 +1,9 @@
-use codespan_reporting::diagnostic::{Diagnostic, Label as ForeignLabel};
+use crate::error::code::{CodedError, ErrorCode};
+use crate::error::suggestion::Suggestion;
+use codespan_reporting::diagnostic::{Diagnostic, Label as ForeignLabel, LabelStyle};
 use kodept_core::code_point::CodePoint;
-use std::any::{type_name_of_val};
 use std::borrow::Cow;
-use std::hash::{DefaultHasher, Hash, Hasher};
+use std::ops::Range;
 
 #[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Copy, Clone, Hash)]
 pub enum Severity {
@@ -22,6 +23,7 @@ pub struct Label {
 #[derive(Debug)]
 pub struct ReportMessage {
     severity: Severity,
+    code: ErrorCode,
     notes: Vec<Cow<'static, str>>,
     message: String,
 }
@@ -29,8 +31,12 @@ pub struct ReportMessage {
 pub trait SpannedReportMessage {
     fn labels(&self) -> impl IntoIterator<Item = Label>;
     fn severity(&self) -> Severity;
+    fn code(&self) -> ErrorCode;
     fn message(&self) -> Cow<'static, str>;
     fn notes(&self) -> impl IntoIterator<Item = Cow<'static, str>>;
+    fn suggestions(&self) -> impl IntoIterator<Item = Suggestion> {
+        []
+    }
 
     fn with_node_location(self, location: CodePoint) -> impl IntoSpannedReportMessage;
 }
@@ -43,7 +49,19 @@ pub trait IntoSpannedReportMessage {
 
 #[derive(Debug)]
 pub struct Report<FileId = crate::context::FileId> {
+    file_id: FileId,
     diagnostic: Diagnostic<FileId>,
+    suggestions: Box<[(FileId, Suggestion)]>,
+}
+
+/// Reports with equal keys describe the same problem at the same place
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub(crate) struct ReportKey<FileId> {
+    // Foreign severity is not `Ord`
+    severity: u8,
+    file_id: FileId,
+    message: String,
+    primary_span: Option<(FileId, usize, usize)>,
 }
 
 impl Label {
@@ -76,9 +94,10 @@ impl From<Severity> for codespan_reporting::diagnostic::Severity {
 }
 
 impl ReportMessage {
-    pub fn new<S: Into<String>>(severity: Severity, _: S, message: String) -> Self {
+    pub fn new(severity: Severity, code: ErrorCode, message: String) -> Self {
         Self {
             severity,
+            code,
             message,
             notes: Default::default(),
         }
@@ -91,20 +110,10 @@ impl ReportMessage {
 }
 
 impl<FileId> Report<FileId> {
-    fn generate_code_from_type<T>(value: &T) -> String {
-        let type_name = type_name_of_val(value);
-        let mut hasher = DefaultHasher::new();
-        type_name.hash(&mut hasher);
-        let hash = hasher.finish();
-        let hash = hash.to_ne_bytes().into_iter().fold(0u16, |acc, next| {
-            acc ^ next as u16
-        });
-        format!("{:0>8X}", hash)
-    }
-
-    fn from_raw_message_with_code<T>(file_id: FileId, msg: T, code: String) -> Self
-    where T: SpannedReportMessage,
-        FileId: Clone
+    fn from_raw_message<T>(file_id: FileId, msg: T) -> Self
+    where
+        T: SpannedReportMessage,
+        FileId: Clone,
     {
         let labels = msg
             .labels()
@@ -119,13 +128,28 @@ impl<FileId> Report<FileId> {
             })
             .collect();
 
+        let suggestions: Box<[_]> = msg
+            .suggestions()
+            .into_iter()
+            .map(|it| (file_id.clone(), it))
+            .collect();
+        let notes = msg
+            .notes()
+            .into_iter()
+            .map(|it| it.to_string())
+            .chain(suggestions.iter().map(|(_, it)| it.as_note()))
+            .collect();
         let diagnostic = Diagnostic::new(msg.severity().into())
             .with_message(msg.message())
-            .with_code(code)
-            .with_notes(msg.notes().into_iter().map(|it| it.to_string()).collect())
+            .with_code(msg.code().code())
+            .with_notes(notes)
             .with_labels(labels);
 
-        Self { diagnostic }
+        Self {
+            file_id,
+            diagnostic,
+            suggestions,
+        }
     }
 
     #[must_use]
@@ -134,8 +158,13 @@ impl<FileId> Report<FileId> {
         T: IntoSpannedReportMessage,
         FileId: Clone,
     {
-        let code = Self::generate_code_from_type(&msg);
-        Self::from_raw_message_with_code(file_id, msg.into_message(), code)
+        Self::from_raw_message(file_id, msg.into_message())
+    }
+
+    /// File the report was made for
+    #[must_use]
+    pub const fn file_id(&self) -> &FileId {
+        &self.file_id
     }
 
     #[must_use]
@@ -149,15 +178,54 @@ impl<FileId> Report<FileId> {
     }
 
     #[must_use]
-    pub(crate) fn into_diagnostic(self) -> Diagnostic<FileId> {
-        self.diagnostic
+    pub const fn is_warning(&self) -> bool {
+        matches!(
+            self.diagnostic.severity,
+            codespan_reporting::diagnostic::Severity::Warning
+        )
+    }
+
+    #[must_use]
+    pub const fn is_bug(&self) -> bool {
+        matches!(
+            self.diagnostic.severity,
+            codespan_reporting::diagnostic::Severity::Bug
+        )
+    }
+
+    #[must_use]
+    pub(crate) fn key(&self) -> ReportKey<FileId>
+    where
+        FileId: Clone,
+    {
+        let primary_span = self
+            .diagnostic
+            .labels
+            .iter()
+            .find(|it| it.style == LabelStyle::Primary)
+            .map(|it| {
+                let Range { start, end } = it.range;
+                (it.file_id.clone(), start, end)
+            });
+        ReportKey {
+            severity: self.diagnostic.severity as u8,
+            file_id: self.file_id.clone(),
+            message: self.diagnostic.message.clone(),
+            primary_span,
+        }
+    }
+
+    #[must_use]
+    pub(crate) fn into_parts(self) -> (Diagnostic<FileId>, Box<[(FileId, Suggestion)]>) {
+        (self.diagnostic, self.suggestions)
     }
 }
 
-impl<E: std::error::Error> From<E> for ReportMessage {
+impl<E: std::error::Error + CodedError> From<E> for ReportMessage {
     fn from(value: E) -> Self {
         ReportMessage {
             severity: Severity::Error,
+            code: value.code(),
             message: value.to_string(),
             notes: Default::default(),
         }
@@ -181,6 +249,10 @@ impl SpannedReportMessage for ReportMessage {
         self.severity
     }
 
+    fn code(&self) -> ErrorCode {
+        self.code
+    }
+
     fn message(&self) -> Cow<'static, str> {
         Cow::Owned(self.message.clone())
     }
@@ -190,7 +262,7 @@ impl SpannedReportMessage for ReportMessage {
     }
 
     fn with_node_location(self, location: CodePoint) -> impl IntoSpannedReportMessage {
-        crate::error::Diagnostic::new(self.severity)
+        crate::error::Diagnostic::new(self.severity, self.code)
             .with_message(self.message)
             .with_node_location(location)
     }
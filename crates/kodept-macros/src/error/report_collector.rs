@@ -1,36 +1,110 @@
 use crate::context::FileId;
-use crate::error::report::{IntoSpannedReportMessage, Report};
+use crate::error::report::{IntoSpannedReportMessage, Report, ReportKey};
 use append_only_vec::AppendOnlyVec;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::collections::{BTreeMap, BTreeSet};
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 
 #[derive(Default, Debug)]
 pub struct ReportCollector<F = FileId> {
     reports: AppendOnlyVec<Report<F>>,
-    has_errors: AtomicBool,
+    errors: AtomicUsize,
+    /// Collector may be shared by files processed concurrently
+    errors_by_file: Mutex<BTreeMap<F, usize>>,
+    seen: Mutex<BTreeSet<ReportKey<F>>>,
+    limit: Option<ErrorLimit>,
+}
+
+/// Maximum number of errors, shared between all collectors it is given to
+#[derive(Debug, Clone)]
+pub struct ErrorLimit {
+    max: NonZeroUsize,
+    collected: Arc<AtomicUsize>,
 }
 
 pub trait Reporter<F> {
     fn report(self, file_id: F, message: impl IntoSpannedReportMessage);
 }
 
+impl ErrorLimit {
+    #[must_use]
+    pub fn new(max: NonZeroUsize) -> Self {
+        Self {
+            max,
+            collected: Default::default(),
+        }
+    }
+
+    #[must_use]
+    pub fn max(&self) -> NonZeroUsize {
+        self.max
+    }
+
+    #[must_use]
+    pub fn is_reached(&self) -> bool {
+        self.collected.load(Ordering::Acquire) >= self.max.get()
+    }
+
+    /// Counts one more error, returns `false` if it does not fit into the limit
+    fn try_acquire(&self) -> bool {
+        self.collected
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |it| {
+                (it < self.max.get()).then_some(it + 1)
+            })
+            .is_ok()
+    }
+}
+
 impl<F> ReportCollector<F> {
     #[must_use]
     pub const fn new() -> Self {
         ReportCollector {
             reports: AppendOnlyVec::new(),
-            has_errors: AtomicBool::new(false),
+            errors: AtomicUsize::new(0),
+            errors_by_file: Mutex::new(BTreeMap::new()),
+            seen: Mutex::new(BTreeSet::new()),
+            limit: None,
         }
     }
 
-    pub fn push_report(&self, report: Report<F>) {
-        self.has_errors
-            .fetch_or(report.is_error(), Ordering::AcqRel);
-        self.reports.push(report);
+    /// Errors that do not fit into the limit are dropped
+    #[must_use]
+    pub const fn with_limit(limit: Option<ErrorLimit>) -> Self {
+        ReportCollector {
+            reports: AppendOnlyVec::new(),
+            errors: AtomicUsize::new(0),
+            errors_by_file: Mutex::new(BTreeMap::new()),
+            seen: Mutex::new(BTreeSet::new()),
+            limit,
+        }
     }
 
     #[must_use]
     pub fn has_errors(&self) -> bool {
-        self.has_errors.load(Ordering::Acquire)
+        self.error_count() != 0
+    }
+
+    /// Number of reported errors, including the ones that did not fit into the limit
+    #[must_use]
+    pub fn error_count(&self) -> usize {
+        self.errors.load(Ordering::Acquire)
+    }
+
+    /// Number of errors reported in the file, including the ones that did not fit into the limit
+    #[must_use]
+    pub fn file_error_count(&self, file_id: &F) -> usize
+    where
+        F: Ord,
+    {
+        let errors = self.errors_by_file.lock().unwrap_or_else(|e| e.into_inner());
+        errors.get(file_id).copied().unwrap_or_default()
+    }
+
+    /// Whether no more errors can be collected, so there is no point to continue
+    #[must_use]
+    pub fn is_limit_reached(&self) -> bool {
+        self.limit.as_ref().is_some_and(ErrorLimit::is_reached)
     }
 
     pub fn has_reports(&self) -> bool {
@@ -41,28 +115,156 @@ impl<F> ReportCollector<F> {
     pub fn into_collected_reports(self) -> Vec<Report<F>> {
         self.reports.into_vec()
     }
+
+    /// Collected reports ordered by files, reports of a file keep the order they were made in.
+    /// Files may be processed concurrently, so their reports are interleaved when collected
+    #[must_use]
+    pub fn into_sorted_reports(self) -> Vec<Report<F>>
+    where
+        F: Ord,
+    {
+        let mut reports = self.into_collected_reports();
+        reports.sort_by(|a, b| a.file_id().cmp(b.file_id()));
+        reports
+    }
+}
+
+impl<F: Clone + Ord> ReportCollector<F> {
+    /// Reports equal to already collected ones are ignored.
+    /// Compiler bugs are always collected regardless of the limit
+    pub fn push_report(&self, report: Report<F>) {
+        let is_new = self
+            .seen
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(report.key());
+        if !is_new {
+            return;
+        }
+        if report.is_error() {
+            self.errors.fetch_add(1, Ordering::AcqRel);
+            *self
+                .errors_by_file
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .entry(report.file_id().clone())
+                .or_default() += 1;
+            let fits = match &self.limit {
+                Some(limit) if !report.is_bug() => limit.try_acquire(),
+                _ => true,
+            };
+            if !fits {
+                return;
+            }
+        }
+        self.reports.push(report);
+    }
 }
 
 impl<F> Reporter<F> for &ReportCollector<F>
 where
-    F: Clone,
+    F: Clone + Ord,
 {
     fn report(self, file_id: F, message: impl IntoSpannedReportMessage) {
-        let report = Report::from_message(file_id, message);
-        self.has_errors
-            .fetch_or(report.is_error(), Ordering::AcqRel);
-        self.reports.push(report);
+        self.push_report(Report::from_message(file_id, message));
     }
 }
 
 impl<F> Reporter<F> for &mut ReportCollector<F>
 where
-    F: Clone,
+    F: Clone + Ord,
 {
     fn report(self, file_id: F, message: impl IntoSpannedReportMessage) {
-        let report = Report::from_message(file_id, message);
-        self.has_errors
-            .fetch_or(report.is_error(), Ordering::AcqRel);
-        self.reports.push_mut(report);
+        // `AppendOnlyVec::push_mut` does not count the pushed element, so it would be lost
+        self.push_report(Report::from_message(file_id, message));
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use crate::error::code::KC000;
+    use crate::error::report::{Label, Severity};
+    use crate::error::report_collector::{ErrorLimit, ReportCollector, Reporter};
+    use crate::error::Diagnostic;
+    use kodept_core::code_point::CodePoint;
+    use rstest::rstest;
+    use std::num::NonZeroUsize;
+
+    fn diagnostic(severity: Severity, message: &str, offset: u32) -> Diagnostic {
+        Diagnostic::new(severity, KC000)
+            .with_message(message.to_string())
+            .with_label(Label::primary("here", CodePoint::single_point(offset)))
+    }
+
+    #[rstest]
+    #[case::same(diagnostic(Severity::Error, "a", 0), 1)]
+    #[case::other_span(diagnostic(Severity::Error, "a", 1), 2)]
+    #[case::other_message(diagnostic(Severity::Error, "b", 0), 2)]
+    #[case::other_severity(diagnostic(Severity::Warning, "a", 0), 2)]
+    fn test_deduplication(#[case] second: Diagnostic, #[case] expected: usize) {
+        let collector = ReportCollector::new();
+        collector.report(0, diagnostic(Severity::Error, "a", 0));
+        collector.report(0, second);
+
+        assert_eq!(collector.into_collected_reports().len(), expected);
+    }
+
+    #[test]
+    fn test_unlabelled_reports_from_other_files() {
+        let collector = ReportCollector::new();
+        let unlabelled = || Diagnostic::new(Severity::Error, KC000).with_message("a".to_string());
+        collector.report(0, unlabelled());
+        collector.report(1, unlabelled());
+        collector.report(1, unlabelled());
+
+        assert_eq!(collector.into_collected_reports().len(), 2);
+    }
+
+    #[test]
+    fn test_errors_are_counted_by_files() {
+        let collector = ReportCollector::new();
+        collector.report(0, diagnostic(Severity::Error, "a", 0));
+        collector.report(1, diagnostic(Severity::Error, "b", 0));
+        collector.report(1, diagnostic(Severity::Bug, "c", 0));
+        collector.report(1, diagnostic(Severity::Warning, "d", 0));
+
+        assert_eq!(collector.file_error_count(&0), 1);
+        assert_eq!(collector.file_error_count(&1), 2);
+        assert_eq!(collector.file_error_count(&2), 0);
+        assert_eq!(collector.error_count(), 3);
+    }
+
+    #[test]
+    fn test_error_limit() {
+        let limit = ErrorLimit::new(NonZeroUsize::new(2).unwrap());
+        let first = ReportCollector::with_limit(Some(limit.clone()));
+        let second = ReportCollector::with_limit(Some(limit.clone()));
+        first.report(0, diagnostic(Severity::Error, "a", 0));
+        first.report(0, diagnostic(Severity::Warning, "b", 0));
+        assert!(!second.is_limit_reached());
+        second.report(0, diagnostic(Severity::Error, "c", 0));
+        second.report(0, diagnostic(Severity::Error, "d", 0));
+        second.report(0, diagnostic(Severity::Bug, "e", 0));
+
+        assert!(limit.is_reached());
+        assert!(first.is_limit_reached());
+        assert_eq!(second.error_count(), 3);
+        assert_eq!(first.into_collected_reports().len(), 2);
+        assert_eq!(second.into_collected_reports().len(), 2);
+    }
+
+    #[test]
+    fn test_sorted_by_files() {
+        let collector = ReportCollector::new();
+        collector.report(1, diagnostic(Severity::Error, "a", 0));
+        collector.report(0, diagnostic(Severity::Error, "b", 0));
+        collector.report(1, diagnostic(Severity::Warning, "c", 0));
+        collector.report(0, diagnostic(Severity::Warning, "d", 0));
+
+        let reports = collector.into_sorted_reports();
+        let order: Vec<_> = reports.iter().map(|it| *it.file_id()).collect();
+        assert_eq!(order, [0, 0, 1, 1]);
+        assert!(reports[0].is_error() && reports[2].is_error());
     }
 }
@@ -0,0 +1,338 @@
+use crate::error::code::ErrorCode;
+use crate::error::suggestion::Suggestion;
+use codespan_reporting::diagnostic::{Diagnostic, LabelStyle, Severity};
+use codespan_reporting::files::{Files, SimpleFile};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::BTreeSet;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+/// Way the diagnostics are rendered to the output stream
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum ReportFormat {
+    /// Human-readable output provided by `codespan`
+    #[default]
+    Terminal,
+    /// One JSON object per line
+    Json,
+}
+
+/// Position-resolved diagnostic that does not depend on the source files anymore
+#[derive(Debug, Clone, Serialize)]
+pub struct StructuredReport {
+    pub severity: &'static str,
+    pub code: Option<String>,
+    pub message: String,
+    pub labels: Vec<StructuredLabel>,
+    pub notes: Vec<String>,
+    pub suggestions: Vec<StructuredSuggestion>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StructuredLabel {
+    pub file: String,
+    pub primary: bool,
+    pub message: String,
+    /// Byte offset of the first character
+    pub start: usize,
+    /// Byte offset after the last character
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+    /// Same as `column`, but counted in UTF-16 code units
+    pub column_utf16: usize,
+    pub end_column_utf16: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StructuredSuggestion {
+    pub file: String,
+    pub message: String,
+    pub applicability: String,
+    pub replacement: String,
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+    /// Same as `column`, but counted in UTF-16 code units
+    pub column_utf16: usize,
+    pub end_column_utf16: usize,
+}
+
+/// Source files that can tell columns in UTF-16 code units, which editors expect
+pub trait Utf16Columns<'a>: Files<'a> {
+    /// Zero-based column of the byte. By default, it is computed from the contents of the line
+    fn utf16_column(&'a self, id: Self::FileId, byte_index: usize) -> Option<usize> {
+        let line = self.line_index(id, byte_index).ok()?;
+        let line_start = self.line_range(id, line).ok()?.start;
+        let source = self.source(id).ok()?;
+        let prefix = source.as_ref().get(line_start..byte_index)?;
+        Some(prefix.encode_utf16().count())
+    }
+}
+
+/// Accumulates diagnostics to be written as a single SARIF 2.1 log
+#[derive(Debug, Clone, Default)]
+pub struct SarifLog {
+    results: Arc<Mutex<Vec<StructuredReport>>>,
+}
+
+fn severity_name(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Bug => "bug",
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Note => "note",
+        Severity::Help => "help",
+    }
+}
+
+impl<'a, N, S> Utf16Columns<'a> for SimpleFile<N, S>
+where
+    N: 'a + std::fmt::Display + Clone,
+    S: 'a + AsRef<str>,
+{
+}
+
+impl StructuredReport {
+    pub fn new<'f, F: Utf16Columns<'f>>(diagnostic: &Diagnostic<F::FileId>, files: &'f F) -> Self {
+        let labels = diagnostic
+            .labels
+            .iter()
+            .filter_map(|label| {
+                let start = files.location(label.file_id, label.range.start).ok()?;
+                let end = files.location(label.file_id, label.range.end).ok()?;
+                let column_utf16 = files.utf16_column(label.file_id, label.range.start)?;
+                let end_column_utf16 = files.utf16_column(label.file_id, label.range.end)?;
+                Some(StructuredLabel {
+                    file: files.name(label.file_id).ok()?.to_string(),
+                    primary: label.style == LabelStyle::Primary,
+                    message: label.message.clone(),
+                    start: label.range.start,
+                    end: label.range.end,
+                    line: start.line_number,
+                    column: start.column_number,
+                    end_line: end.line_number,
+                    end_column: end.column_number,
+                    column_utf16: column_utf16 + 1,
+                    end_column_utf16: end_column_utf16 + 1,
+                })
+            })
+            .collect();
+
+        Self {
+            severity: severity_name(diagnostic.severity),
+            code: diagnostic.code.clone(),
+            message: diagnostic.message.clone(),
+            labels,
+            notes: diagnostic.notes.clone(),
+            suggestions: vec![],
+        }
+    }
+
+    pub fn with_suggestions<'f, F: Utf16Columns<'f>>(
+        mut self,
+        suggestions: &[(F::FileId, Suggestion)],
+        files: &'f F,
+    ) -> Self {
+        self.suggestions = suggestions
+            .iter()
+            .filter_map(|&(file_id, ref suggestion)| {
+                let range = suggestion.point.as_range();
+                let start = files.location(file_id, range.start).ok()?;
+                let end = files.location(file_id, range.end).ok()?;
+                let column_utf16 = files.utf16_column(file_id, range.start)?;
+                let end_column_utf16 = files.utf16_column(file_id, range.end)?;
+                Some(StructuredSuggestion {
+                    file: files.name(file_id).ok()?.to_string(),
+                    message: suggestion.message.to_string(),
+                    applicability: suggestion.applicability.to_string(),
+                    replacement: suggestion.replacement.to_string(),
+                    start: range.start,
+                    end: range.end,
+                    line: start.line_number,
+                    column: start.column_number,
+                    end_line: end.line_number,
+                    end_column: end.column_number,
+                    column_utf16: column_utf16 + 1,
+                    end_column_utf16: end_column_utf16 + 1,
+                })
+            })
+            .collect();
+        self
+    }
+
+    pub fn write_json_line<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+        serde_json::to_writer(&mut writer, self)?;
+        writeln!(writer)
+    }
+
+    fn to_sarif_result(&self) -> Value {
+        let level = match self.severity {
+            "bug" | "error" => "error",
+            "warning" => "warning",
+            _ => "note",
+        };
+        let location = |label: &StructuredLabel| {
+            json!({
+                "physicalLocation": {
+                    "artifactLocation": { "uri": label.file },
+                    "region": {
+                        "byteOffset": label.start,
+                        "byteLength": label.end - label.start,
+                        "startLine": label.line,
+                        "startColumn": label.column_utf16,
+                        "endLine": label.end_line,
+                        "endColumn": label.end_column_utf16,
+                    }
+                },
+                "message": { "text": label.message },
+            })
+        };
+        let (primary, related): (Vec<_>, Vec<_>) = self.labels.iter().partition(|it| it.primary);
+        let mut text = self.message.clone();
+        for note in &self.notes {
+            text.push('\n');
+            text.push_str(note);
+        }
+
+        let mut result = json!({
+            "level": level,
+            "message": { "text": text },
+            "locations": primary.into_iter().map(location).collect::<Vec<_>>(),
+            "relatedLocations": related.into_iter().map(location).collect::<Vec<_>>(),
+        });
+        if let Some(code) = &self.code {
+            result["ruleId"] = json!(code);
+        }
+        if !self.suggestions.is_empty() {
+            result["fixes"] = self
+                .suggestions
+                .iter()
+                .map(|it| {
+                    json!({
+                        "description": { "text": it.message },
+                        "artifactChanges": [{
+                            "artifactLocation": { "uri": it.file },
+                            "replacements": [{
+                                "deletedRegion": {
+                                    "byteOffset": it.start,
+                                    "byteLength": it.end - it.start,
+                                },
+                                "insertedContent": { "text": it.replacement },
+                            }]
+                        }]
+                    })
+                })
+                .collect();
+        }
+        result
+    }
+}
+
+impl SarifLog {
+    pub fn push(&self, report: StructuredReport) {
+        self.results
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(report);
+    }
+
+    /// Reports recorded so far
+    pub fn reports(&self) -> Vec<StructuredReport> {
+        self.results.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    pub fn to_json(&self) -> Value {
+        let results = self.results.lock().unwrap_or_else(|e| e.into_inner());
+        let used_codes: BTreeSet<_> = results.iter().filter_map(|it| it.code.as_deref()).collect();
+        let rules: Vec<_> = used_codes
+            .into_iter()
+            .filter_map(ErrorCode::find)
+            .map(|code| {
+                json!({
+                    "id": code.code(),
+                    "shortDescription": { "text": code.title() },
+                    "fullDescription": { "text": code.title() },
+                    "help": { "text": code.explanation(), "markdown": code.explanation() },
+                })
+            })
+            .collect();
+
+        json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "kodept",
+                        "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                        "rules": rules,
+                    }
+                },
+                "results": results.iter().map(StructuredReport::to_sarif_result).collect::<Vec<_>>(),
+            }]
+        })
+    }
+
+    pub fn write<W: Write>(&self, writer: W) -> std::io::Result<()> {
+        serde_json::to_writer_pretty(writer, &self.to_json())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use crate::error::structured::{SarifLog, StructuredReport};
+    use codespan_reporting::diagnostic::{Diagnostic, Label};
+    use codespan_reporting::files::SimpleFile;
+
+    fn report() -> StructuredReport {
+        let file = SimpleFile::new("main.kd", "module A =>\nfun f( = 1\n");
+        let diagnostic = Diagnostic::error()
+            .with_code("KC001")
+            .with_message("Unexpected =")
+            .with_labels(vec![Label::primary((), 19..20).with_message("here")])
+            .with_notes(vec!["Expected <ident>".to_string()]);
+        StructuredReport::new(&diagnostic, &file)
+    }
+
+    #[test]
+    fn test_json_line_resolves_positions() {
+        let mut output = vec![];
+        report().write_json_line(&mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.ends_with('\n'));
+        let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(value["severity"], "error");
+        assert_eq!(value["code"], "KC001");
+        assert_eq!(value["labels"][0]["file"], "main.kd");
+        assert_eq!(value["labels"][0]["line"], 2);
+        assert_eq!(value["labels"][0]["column"], 8);
+        assert_eq!(value["labels"][0]["column_utf16"], 8);
+        assert_eq!(value["labels"][0]["start"], 19);
+    }
+
+    #[test]
+    fn test_sarif_contains_rules_of_reported_codes() {
+        let log = SarifLog::default();
+        log.push(report());
+        let value = log.to_json();
+        let run = &value["runs"][0];
+
+        assert_eq!(value["version"], "2.1.0");
+        assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "KC001");
+        assert_eq!(run["results"][0]["ruleId"], "KC001");
+        assert_eq!(run["results"][0]["level"], "error");
+        let region = &run["results"][0]["locations"][0]["physicalLocation"]["region"];
+        assert_eq!(region["startLine"], 2);
+        assert_eq!(region["byteLength"], 1);
+    }
+}
@@ -0,0 +1,176 @@
+use derive_more::Display;
+use kodept_core::code_point::CodePoint;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// How sure the compiler is that applying a suggestion produces the intended code
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Display)]
+pub enum Applicability {
+    /// Safe to apply without a review, used by `--fix`
+    #[display("machine-applicable")]
+    MachineApplicable,
+    /// Likely to be correct, but should be checked by a human
+    #[display("maybe-incorrect")]
+    MaybeIncorrect,
+    /// Replacement contains placeholders that have to be filled in
+    #[display("has-placeholders")]
+    HasPlaceholders,
+}
+
+/// Replacement of a span of source code with the given text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    pub message: Cow<'static, str>,
+    pub point: CodePoint,
+    pub replacement: Cow<'static, str>,
+    pub applicability: Applicability,
+}
+
+impl Suggestion {
+    pub fn replace(
+        message: impl Into<Cow<'static, str>>,
+        point: CodePoint,
+        replacement: impl Into<Cow<'static, str>>,
+        applicability: Applicability,
+    ) -> Self {
+        Self {
+            message: message.into(),
+            point,
+            replacement: replacement.into(),
+            applicability,
+        }
+    }
+
+    pub fn insert(
+        message: impl Into<Cow<'static, str>>,
+        offset: u32,
+        text: impl Into<Cow<'static, str>>,
+        applicability: Applicability,
+    ) -> Self {
+        Self::replace(message, CodePoint::new(0, offset), text, applicability)
+    }
+
+    /// Human-readable form of the suggestion shown along with other notes
+    pub fn as_note(&self) -> String {
+        if self.point.length == 0 {
+            format!("help: {}: insert `{}`", self.message, self.replacement)
+        } else {
+            format!("help: {}: `{}`", self.message, self.replacement)
+        }
+    }
+}
+
+/// Applies machine-applicable suggestions to the text.
+/// Suggestions overlapping already applied ones are skipped.
+///
+/// Returns patched text and the number of applied suggestions
+pub fn apply_suggestions<'s>(
+    source: &str,
+    suggestions: impl IntoIterator<Item = &'s Suggestion>,
+) -> (String, usize) {
+    let mut suggestions: Vec<_> = suggestions
+        .into_iter()
+        .filter(|it| it.applicability == Applicability::MachineApplicable)
+        .filter(|it| it.point.as_range().end <= source.len())
+        .collect();
+    suggestions.sort_by_key(|it| it.point.as_range().start);
+
+    let mut output = String::with_capacity(source.len());
+    let mut position = 0;
+    let mut applied = 0;
+    for suggestion in suggestions {
+        let range = suggestion.point.as_range();
+        if range.start < position
+            || !source.is_char_boundary(range.start)
+            || !source.is_char_boundary(range.end)
+        {
+            continue;
+        }
+        output.push_str(&source[position..range.start]);
+        output.push_str(&suggestion.replacement);
+        position = range.end;
+        applied += 1;
+    }
+    output.push_str(&source[position..]);
+    (output, applied)
+}
+
+#[derive(Debug)]
+struct PendingFixes {
+    snapshot: String,
+    suggestions: Vec<Suggestion>,
+}
+
+/// Collects suggestions of emitted reports to apply them to the files later
+#[derive(Debug, Clone, Default)]
+pub struct FixLog {
+    files: Arc<Mutex<BTreeMap<PathBuf, PendingFixes>>>,
+}
+
+impl FixLog {
+    /// Remembers suggestion along with the source it was made for
+    pub fn push(&self, path: PathBuf, source: &str, suggestion: Suggestion) {
+        let mut files = self.files.lock().unwrap_or_else(|e| e.into_inner());
+        files
+            .entry(path)
+            .or_insert_with(|| PendingFixes {
+                snapshot: source.to_string(),
+                suggestions: vec![],
+            })
+            .suggestions
+            .push(suggestion);
+    }
+
+    /// Rewrites files in place. Files that changed since they were read are left untouched,
+    /// as well as sources that are not files at all.
+    ///
+    /// Returns paths of changed files along with the number of applied suggestions
+    pub fn apply(&self) -> std::io::Result<Vec<(PathBuf, usize)>> {
+        let files = self.files.lock().unwrap_or_else(|e| e.into_inner());
+        let mut changed = vec![];
+        for (path, fixes) in files.iter() {
+            if !Self::is_unchanged(path, &fixes.snapshot) {
+                continue;
+            }
+            let (patched, applied) = apply_suggestions(&fixes.snapshot, &fixes.suggestions);
+            if applied > 0 {
+                std::fs::write(path, patched)?;
+                changed.push((path.clone(), applied));
+            }
+        }
+        Ok(changed)
+    }
+
+    fn is_unchanged(path: &Path, snapshot: &str) -> bool {
+        path.is_file() && std::fs::read_to_string(path).is_ok_and(|it| it == snapshot)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use crate::error::suggestion::{apply_suggestions, Applicability, Suggestion};
+    use kodept_core::code_point::CodePoint;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::insertion(vec![Suggestion::insert("close", 8, "}", Applicability::MachineApplicable)], "fun f {\n}", 1)]
+    #[case::replacement(vec![Suggestion::replace("rename", CodePoint::new(1, 4), "g", Applicability::MachineApplicable)], "fun g {\n", 1)]
+    #[case::unsafe_skipped(vec![Suggestion::replace("rename", CodePoint::new(1, 4), "g", Applicability::MaybeIncorrect)], "fun f {\n", 0)]
+    #[case::overlapping(vec![
+        Suggestion::replace("a", CodePoint::new(3, 0), "val", Applicability::MachineApplicable),
+        Suggestion::replace("b", CodePoint::new(1, 2), "x", Applicability::MachineApplicable),
+    ], "val f {\n", 1)]
+    fn test_apply_suggestions(
+        #[case] suggestions: Vec<Suggestion>,
+        #[case] expected: &str,
+        #[case] count: usize,
+    ) {
+        let (output, applied) = apply_suggestions("fun f {\n", &suggestions);
+
+        assert_eq!(output, expected);
+        assert_eq!(applied, count);
+    }
+}
@@ -1,9 +1,11 @@
 use crate::context::FileId;
+use crate::error::code::{CodedError, ErrorCode};
 use crate::error::report::{
     IntoSpannedReportMessage, Label, Report, ReportMessage, Severity, SpannedReportMessage,
 };
+use crate::error::structured::{ReportFormat, SarifLog, StructuredReport, Utf16Columns};
+use crate::error::suggestion::{FixLog, Suggestion};
 use crate::error::{Diagnostic, ErrorReported};
-use codespan_reporting::files::Files;
 use codespan_reporting::term::termcolor::WriteColor;
 use codespan_reporting::term::Config;
 use extend::ext;
@@ -13,12 +15,31 @@ use kodept_core::code_point::CodePoint;
 use kodept_core::structure::Located;
 use std::borrow::Cow;
 use std::fmt::{Debug, Display, Formatter};
-use crate::error::report_collector::{ReportCollector, Reporter};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use crate::error::report_collector::{ErrorLimit, ReportCollector, Reporter};
 
 #[derive(Clone, Debug)]
 pub struct CodespanSettings<S> {
     pub config: Config,
     pub stream: S,
+    pub format: ReportFormat,
+    /// Every emitted report is also recorded here, if set
+    pub sarif: Option<SarifLog>,
+    /// Suggestions of emitted reports are recorded here, if set
+    pub fixes: Option<FixLog>,
+    /// Number of emitted reports of every severity
+    pub summary: ReportSummary,
+    /// Collection of errors stops after this many ones
+    pub error_limit: Option<ErrorLimit>,
+}
+
+/// Counts emitted reports, shared between all clones
+#[derive(Clone, Debug, Default)]
+pub struct ReportSummary {
+    errors: Arc<AtomicUsize>,
+    warnings: Arc<AtomicUsize>,
 }
 
 #[derive(Debug)]
@@ -26,42 +47,73 @@ pub struct SpannedError<E: std::error::Error> {
     point: CodePoint,
     severity: Severity,
     notes: Vec<Cow<'static, str>>,
+    suggestions: Vec<Suggestion>,
     inner: E,
 }
 
 pub trait DrainReports {
     type Output;
 
-    fn drain(self, file_id: FileId, collector: &mut ReportCollector) -> Self::Output;
+    fn drain(self, file_id: FileId, collector: &ReportCollector) -> Self::Output;
 }
 
 pub trait Reportable {
     type FileId;
 
-    fn emit<'f, W: WriteColor, F: Files<'f, FileId = Self::FileId>>(
+    fn emit<'f, W: WriteColor, F: Utf16Columns<'f, FileId = Self::FileId>>(
         self,
         settings: &mut CodespanSettings<W>,
         source: &'f F,
     );
 }
 
-impl<FileId> Reportable for Report<FileId> {
+impl<FileId: Copy> Reportable for Report<FileId> {
     type FileId = FileId;
 
-    fn emit<'f, W: WriteColor, F: Files<'f, FileId = Self::FileId>>(
+    fn emit<'f, W: WriteColor, F: Utf16Columns<'f, FileId = Self::FileId>>(
         self,
         settings: &mut CodespanSettings<W>,
         source: &'f F,
     ) {
-        codespan_reporting::term::emit(&mut settings.stream, &settings.config, source, &self.into_diagnostic())
-            .expect("Cannot emit diagnostics")
+        settings.summary.record(&self);
+        let (diagnostic, suggestions) = self.into_parts();
+        if let Some(fixes) = &settings.fixes {
+            for (file_id, suggestion) in suggestions.iter() {
+                if let (Ok(name), Ok(contents)) = (source.name(*file_id), source.source(*file_id)) {
+                    fixes.push(
+                        PathBuf::from(name.to_string()),
+                        contents.as_ref(),
+                        suggestion.clone(),
+                    );
+                }
+            }
+        }
+        let structured = match (&settings.format, &settings.sarif) {
+            (ReportFormat::Terminal, None) => None,
+            _ => Some(StructuredReport::new(&diagnostic, source).with_suggestions(&suggestions, source)),
+        };
+        if let (Some(log), Some(report)) = (&settings.sarif, &structured) {
+            log.push(report.clone());
+        }
+        match (settings.format, structured) {
+            (ReportFormat::Json, Some(report)) => report
+                .write_json_line(&mut settings.stream)
+                .expect("Cannot emit diagnostics"),
+            _ => codespan_reporting::term::emit(
+                &mut settings.stream,
+                &settings.config,
+                source,
+                &diagnostic,
+            )
+            .expect("Cannot emit diagnostics"),
+        }
     }
 }
 
 impl<R: Reportable> Reportable for Vec<R> {
     type FileId = R::FileId;
 
-    fn emit<'f, W: WriteColor, F: Files<'f, FileId = Self::FileId>>(
+    fn emit<'f, W: WriteColor, F: Utf16Columns<'f, FileId = Self::FileId>>(
         self,
         settings: &mut CodespanSettings<W>,
         source: &'f F,
@@ -71,8 +123,8 @@ impl<R: Reportable> Reportable for Vec<R> {
 }
 
 #[ext]
-pub impl<T, E: std::error::Error + Send + Sync + 'static> Result<T, E> {
-    fn or_emit<'f, W: WriteColor, F: Files<'f, FileId = FileId>>(
+pub impl<T, E: std::error::Error + CodedError + Send + Sync + 'static> Result<T, E> {
+    fn or_emit<'f, W: WriteColor, F: Utf16Columns<'f, FileId = FileId>>(
         self,
         settings: &mut CodespanSettings<W>,
         source: &'f F,
@@ -84,10 +136,10 @@ pub impl<T, E: std::error::Error + Send + Sync + 'static> Result<T, E> {
                 pub struct Helper<'e, E: std::error::Error>(&'e E);
                 impl<'e, E> From<Helper<'e, E>> for ReportMessage
                 where
-                    E: std::error::Error,
+                    E: std::error::Error + CodedError,
                 {
                     fn from(value: Helper<E>) -> Self {
-                        Self::new(Severity::Error, "external", value.0.to_string())
+                        Self::new(Severity::Error, value.0.code(), value.0.to_string())
                     }
                 }
 
@@ -98,12 +150,56 @@ pub impl<T, E: std::error::Error + Send + Sync + 'static> Result<T, E> {
     }
 }
 
+impl ReportSummary {
+    fn record<F>(&self, report: &Report<F>) {
+        if report.is_error() {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+        } else if report.is_warning() {
+            self.warnings.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[must_use]
+    pub fn errors(&self) -> usize {
+        self.errors.load(Ordering::Relaxed)
+    }
+
+    #[must_use]
+    pub fn warnings(&self) -> usize {
+        self.warnings.load(Ordering::Relaxed)
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.errors() == 0 && self.warnings() == 0
+    }
+}
+
+impl Display for ReportSummary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        fn plural(count: usize, word: &str) -> String {
+            match count {
+                1 => format!("1 {word}"),
+                _ => format!("{count} {word}s"),
+            }
+        }
+
+        write!(
+            f,
+            "{}, {} emitted",
+            plural(self.errors(), "error"),
+            plural(self.warnings(), "warning")
+        )
+    }
+}
+
 impl<E: std::error::Error> SpannedError<E> {
     pub fn new(inner: E, at: CodePoint) -> Self {
         Self {
             point: at,
             severity: Severity::Error,
             notes: Default::default(),
+            suggestions: Default::default(),
             inner,
         }
     }
@@ -128,17 +224,23 @@ impl<E: std::error::Error> SpannedError<E> {
         self
     }
     
+    pub fn with_suggestion(mut self, suggestion: Suggestion) -> Self {
+        self.suggestions.push(suggestion);
+        self
+    }
+
     pub fn map<F: std::error::Error>(self, f: impl FnOnce(E) -> F) -> SpannedError<F> {
         SpannedError {
             point: self.point,
             severity: self.severity,
             notes: self.notes,
+            suggestions: self.suggestions,
             inner: f(self.inner),
         }
     }
 }
 
-impl<E: std::error::Error> SpannedReportMessage for SpannedError<E> {
+impl<E: std::error::Error + CodedError> SpannedReportMessage for SpannedError<E> {
     fn labels(&self) -> impl IntoIterator<Item = Label> {
         [Label::primary("here", self.point)]
     }
@@ -147,6 +249,10 @@ impl<E: std::error::Error> SpannedReportMessage for SpannedError<E> {
         self.severity
     }
 
+    fn code(&self) -> ErrorCode {
+        self.inner.code()
+    }
+
     fn message(&self) -> Cow<'static, str> {
         Cow::Owned(self.inner.to_string())
     }
@@ -155,10 +261,21 @@ impl<E: std::error::Error> SpannedReportMessage for SpannedError<E> {
         self.notes.clone()
     }
 
+    fn suggestions(&self) -> impl IntoIterator<Item = Suggestion> {
+        self.suggestions.clone()
+    }
+
     fn with_node_location(self, location: CodePoint) -> impl IntoSpannedReportMessage {
-        Diagnostic::new(self.severity)
+        let diagnostic = Diagnostic::new(self.severity, self.inner.code())
             .with_message(self.inner.to_string())
-            .with_label(Label::primary("here", self.point))
+            .with_label(Label::primary("here", self.point));
+        let diagnostic = self
+            .notes
+            .into_iter()
+            .fold(diagnostic, |acc, note| acc.with_note(note));
+        self.suggestions
+            .into_iter()
+            .fold(diagnostic, |acc, suggestion| acc.with_suggestion(suggestion))
             .with_node_location(location)
     }
 }
@@ -169,7 +286,7 @@ impl<E: std::error::Error> Display for SpannedError<E> {
     }
 }
 
-impl<E: std::error::Error + 'static> IntoSpannedReportMessage for SpannedError<E> {
+impl<E: std::error::Error + CodedError + 'static> IntoSpannedReportMessage for SpannedError<E> {
     type Message = Self;
 
     fn into_message(self) -> Self::Message {
@@ -180,7 +297,7 @@ impl<E: std::error::Error + 'static> IntoSpannedReportMessage for SpannedError<E
 impl<T, S: IntoSpannedReportMessage, I: IntoIterator<Item = S>> DrainReports for Result<T, I> {
     type Output = Option<T>;
 
-    fn drain(self, file_id: FileId, collector: &mut ReportCollector) -> Self::Output {
+    fn drain(self, file_id: FileId, collector: &ReportCollector) -> Self::Output {
         match self {
             Ok(x) => Some(x),
             Err(e) => {
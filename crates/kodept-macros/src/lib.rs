@@ -11,6 +11,7 @@ use kodept_ast::utils::Skip;
 pub mod context;
 pub mod default;
 pub mod error;
+pub mod lint;
 pub mod visit_guard;
 
 pub mod execution {
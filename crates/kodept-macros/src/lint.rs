@@ -0,0 +1,262 @@
+use crate::error::code::{CodedError, ErrorCode, KC013};
+use crate::Macro;
+use derive_more::Display;
+use kodept_core::code_point::CodePoint;
+use std::collections::HashMap;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// How a triggered lint is reported
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Display)]
+pub enum LintLevel {
+    /// Lint is not reported at all
+    #[display("allow")]
+    Allow,
+    /// Lint is reported as a warning
+    #[display("warn")]
+    Warn,
+    /// Lint is reported as an error and fails the compilation
+    #[display("deny")]
+    Deny,
+}
+
+#[derive(Debug, Error)]
+#[error("Unknown lint level `{0}`, expected one of `allow`, `warn` or `deny`")]
+pub struct UnknownLintLevel(String);
+
+#[derive(Debug, Error)]
+#[error("Unknown lint `{0}`")]
+pub struct UnknownLint(pub String);
+
+impl CodedError for UnknownLint {
+    fn code(&self) -> ErrorCode {
+        KC013
+    }
+}
+
+impl FromStr for LintLevel {
+    type Err = UnknownLintLevel;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "allow" => Ok(Self::Allow),
+            "warn" => Ok(Self::Warn),
+            "deny" => Ok(Self::Deny),
+            _ => Err(UnknownLintLevel(s.to_string())),
+        }
+    }
+}
+
+/// Static description of a lint
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct LintInfo {
+    /// Name used to configure the lint, i.e. `unused_variables`
+    pub name: &'static str,
+    pub default_level: LintLevel,
+    /// One sentence shown in the list of lints
+    pub description: &'static str,
+    /// Code every report of the lint is emitted with
+    pub code: ErrorCode,
+}
+
+/// Check that is reported with the configured level instead of always being an error
+pub trait Lint: Macro {
+    const INFO: LintInfo;
+}
+
+/// Set of all lints known to the compiler
+#[derive(Debug, Clone, Default)]
+pub struct LintRegistry {
+    lints: Vec<LintInfo>,
+}
+
+/// Levels of lints that differ from their defaults
+#[derive(Debug, Clone, Default)]
+pub struct LintLevels {
+    overrides: HashMap<&'static str, LintLevel>,
+}
+
+/// Lints allowed by `// kodept: allow(name, ...)` comments.
+/// A comment silences the lints on its own line and on the next one
+#[derive(Debug, Clone, Default)]
+pub struct Suppressions {
+    line_starts: Vec<usize>,
+    allowed: HashMap<usize, Vec<String>>,
+}
+
+/// Everything needed to decide whether and how a lint is reported in the current file
+#[derive(Debug, Clone, Default)]
+pub struct Lints {
+    pub levels: LintLevels,
+    pub suppressions: Suppressions,
+}
+
+fn normalize(name: &str) -> String {
+    name.trim().replace('-', "_")
+}
+
+impl LintRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register<L: Lint>(mut self) -> Self {
+        self.lints.push(L::INFO);
+        self
+    }
+
+    /// Looks up a lint by name, both `unused-variables` and `unused_variables` are accepted
+    pub fn find(&self, name: &str) -> Option<&LintInfo> {
+        let name = normalize(name);
+        self.lints.iter().find(|it| it.name == name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &LintInfo> {
+        self.lints.iter()
+    }
+
+    /// Applies overrides in order, so the last level given for a lint wins
+    pub fn levels<'a>(
+        &self,
+        overrides: impl IntoIterator<Item = (&'a str, LintLevel)>,
+    ) -> Result<LintLevels, UnknownLint> {
+        let mut levels = LintLevels::default();
+        for (name, level) in overrides {
+            let info = self
+                .find(name)
+                .ok_or_else(|| UnknownLint(name.to_string()))?;
+            levels.overrides.insert(info.name, level);
+        }
+        Ok(levels)
+    }
+}
+
+impl LintLevels {
+    pub fn level(&self, info: &LintInfo) -> LintLevel {
+        self.overrides
+            .get(info.name)
+            .copied()
+            .unwrap_or(info.default_level)
+    }
+
+    pub fn is_default(&self, info: &LintInfo) -> bool {
+        !self.overrides.contains_key(info.name)
+    }
+}
+
+impl Suppressions {
+    pub fn parse(source: &str) -> Self {
+        let line_starts: Vec<_> = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        let mut allowed: HashMap<usize, Vec<String>> = HashMap::new();
+
+        for (line, text) in source.lines().enumerate() {
+            let Some(names) = Self::parse_directive(text) else {
+                continue;
+            };
+            for name in names {
+                allowed.entry(line).or_default().push(name.clone());
+                allowed.entry(line + 1).or_default().push(name);
+            }
+        }
+
+        Self {
+            line_starts,
+            allowed,
+        }
+    }
+
+    fn parse_directive(line: &str) -> Option<Vec<String>> {
+        let (_, comment) = line.split_once("//")?;
+        let list = comment
+            .trim_start()
+            .strip_prefix("kodept:")?
+            .trim_start()
+            .strip_prefix("allow(")?;
+        let (list, _) = list.split_once(')')?;
+        Some(
+            list.split(',')
+                .map(normalize)
+                .filter(|it| !it.is_empty())
+                .collect(),
+        )
+    }
+
+    pub fn is_suppressed(&self, name: &str, at: CodePoint) -> bool {
+        let line = self
+            .line_starts
+            .partition_point(|&start| start <= at.offset as usize)
+            .saturating_sub(1);
+        self.allowed
+            .get(&line)
+            .is_some_and(|names| names.iter().any(|it| it == name))
+    }
+}
+
+impl Lints {
+    /// Level the lint is reported with at the given place
+    pub fn level_at(&self, info: &LintInfo, at: CodePoint) -> LintLevel {
+        if self.suppressions.is_suppressed(info.name, at) {
+            LintLevel::Allow
+        } else {
+            self.levels.level(info)
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use crate::error::code::KC000;
+    use crate::lint::{LintInfo, LintLevel, LintRegistry, Lints, Suppressions};
+    use kodept_core::code_point::CodePoint;
+    use rstest::rstest;
+
+    const UNUSED: LintInfo = LintInfo {
+        name: "unused_things",
+        default_level: LintLevel::Warn,
+        description: "Detects things that are never used",
+        code: KC000,
+    };
+
+    fn registry() -> LintRegistry {
+        LintRegistry {
+            lints: vec![UNUSED],
+        }
+    }
+
+    #[rstest]
+    #[case::default(vec![], LintLevel::Warn)]
+    #[case::overridden(vec![("unused_things", LintLevel::Deny)], LintLevel::Deny)]
+    #[case::dashes(vec![("unused-things", LintLevel::Allow)], LintLevel::Allow)]
+    #[case::last_wins(vec![("unused_things", LintLevel::Allow), ("unused_things", LintLevel::Deny)], LintLevel::Deny)]
+    fn test_levels(#[case] overrides: Vec<(&str, LintLevel)>, #[case] expected: LintLevel) {
+        let levels = registry().levels(overrides).unwrap();
+
+        assert_eq!(levels.level(&UNUSED), expected);
+    }
+
+    #[test]
+    fn test_unknown_lint() {
+        let error = registry()
+            .levels([("unused_stuff", LintLevel::Deny)])
+            .unwrap_err();
+
+        assert_eq!(error.0, "unused_stuff");
+    }
+
+    #[rstest]
+    #[case::same_line(22, LintLevel::Allow)]
+    #[case::next_line(50, LintLevel::Allow)]
+    #[case::unrelated_line(58, LintLevel::Warn)]
+    fn test_suppressions(#[case] offset: u32, #[case] expected: LintLevel) {
+        let source = "fun f => 1 // kodept: allow(unused-things)\nval x = 1\nval y = 2\n";
+        let lints = Lints {
+            levels: Default::default(),
+            suppressions: Suppressions::parse(source),
+        };
+
+        assert_eq!(lints.level_at(&UNUSED, CodePoint::new(1, offset)), expected);
+    }
+}
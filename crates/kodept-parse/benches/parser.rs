@@ -2,7 +2,7 @@ use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Through
 
 use kodept_parse::common::RLTProducer;
 use kodept_parse::lexer::PegLexer;
-use kodept_parse::parser::{NomParser, PegParser};
+use kodept_parse::parser::{NomParser, PegParser, PestParser};
 use kodept_parse::token_match::PackedTokenMatch;
 use kodept_parse::token_stream::PackedTokenStream;
 use kodept_parse::tokenizer::{LazyTokenizer, Tok, TokCtor};
@@ -33,6 +33,9 @@ fn bench_impls(c: &mut Criterion) {
         group.bench_with_input(BenchmarkId::new("peg", factor), &tokens, |b, i| {
             b.iter(|| PegParser::<false>::new().parse_stream(i).expect("Success"))
         });
+        group.bench_with_input(BenchmarkId::new("pest", factor), &tokens, |b, i| {
+            b.iter(|| PestParser::new().parse_stream(i).expect("Success"))
+        });
     }
     group.finish();
 }
@@ -33,6 +33,21 @@ pub trait RLTProducer<Output = RLT> {
     fn parse_stream<'t>(&self, input: &PackedTokenStream<'t>) -> Result<Output, Self::Error<'t>>;
 }
 
+/// Parser that skips malformed declarations instead of stopping at the first one.
+///
+/// Tokens are skipped up to a sync point: the next declaration is on the new line,
+/// or the enclosing module ends. Line comments include the line break.
+/// Braced blocks are skipped as a whole.
+pub trait RecoveringRLTProducer<Output = RLT>: RLTProducer<Output> {
+    /// Replaces every malformed top level declaration with an error node and continues.
+    /// Returns the partial output along with all errors found,
+    /// or fails if the input cannot be recovered at all.
+    fn parse_recovering<'t>(
+        &self,
+        input: &PackedTokenStream<'t>,
+    ) -> Result<(Output, Vec<Self::Error<'t>>), Self::Error<'t>>;
+}
+
 pub trait ErrorAdapter<A, O: Original<A>> {
     fn adapt(self, original_input: O, position: usize) -> ParseErrors<A>;
 }
@@ -49,7 +49,7 @@ impl<A> ParseError<A> {
             hints: vec![],
         }
     }
-    
+
     pub fn unexpected_eof(expected: Vec<StaticStr>, location: ErrorLocation) -> Self {
         Self::ExpectedNotEOF {
             expected,
@@ -57,7 +57,7 @@ impl<A> ParseError<A> {
             hints: vec![],
         }
     }
-    
+
     pub fn with_hints(mut self, hint: Cow<'static, str>) -> Self {
         let hints = match self {
             ParseError::ExpectedInstead { ref mut hints, .. } => hints,
@@ -68,6 +68,18 @@ impl<A> ParseError<A> {
     }
 }
 
+impl<A> ParseErrors<A> {
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+impl<A> FromIterator<ParseError<A>> for ParseErrors<A> {
+    fn from_iter<T: IntoIterator<Item = ParseError<A>>>(iter: T) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
 impl<A> IntoIterator for ParseErrors<A> {
     type Item = ParseError<A>;
     type IntoIter = std::vec::IntoIter<ParseError<A>>;
@@ -0,0 +1,650 @@
+//! Pretty printer producing source code in the canonical style.
+//!
+//! Layout follows the RLT, while comments are taken from the lossless token stream,
+//! so formatting never loses them. Line breaks that matter for readability,
+//! like a function body on its own line, are kept as they were written.
+
+use kodept_core::structure::rlt::new_types::{BinaryOperationSymbol, Enclosed, Symbol, TypeName};
+use kodept_core::structure::rlt::{AbstractFunction, InitializedVariable};
+use kodept_core::structure::rlt::{
+    Application, BlockLevelNode, BodiedFunction, Body, Context, ContextualReference, Enum,
+    EnumVariant, Expression, ExpressionBlock, FieldInit, File, IfExpr, Literal, Module, Operation,
+    Parameter, Reference, Term, TopLevelNode, Type, TypedParameter, Variable, VariantPayload, RLT,
+};
+use kodept_core::structure::Located;
+
+use crate::formatter::printer::Printer;
+use crate::trivia::LosslessTokens;
+
+mod printer;
+
+const INDENT: usize = 4;
+
+/// Returns formatted source code of `rlt`, that was parsed from `input` split into `tokens`
+pub fn format(input: &str, tokens: &LosslessTokens, rlt: &RLT) -> String {
+    let tokens: Vec<_> = tokens.iter().copied().collect();
+    let mut printer = Printer::new(input, &tokens);
+    printer.file(&rlt.0);
+    printer.finish()
+}
+
+/// Binding power of operations, the same as in the grammar
+mod precedence {
+    pub(super) const LOWEST: u8 = 0;
+    pub(super) const ASSIGN: u8 = 1;
+    pub(super) const LOGIC: u8 = 2;
+    pub(super) const BIT: u8 = 3;
+    pub(super) const COMPARISON: u8 = 4;
+    pub(super) const COMPOUND_COMPARISON: u8 = 5;
+    pub(super) const COMPLEX_COMPARISON: u8 = 6;
+    pub(super) const ADD: u8 = 7;
+    pub(super) const MUL: u8 = 8;
+    pub(super) const POW: u8 = 9;
+    pub(super) const UNARY: u8 = 10;
+    pub(super) const UPDATE: u8 = 11;
+    pub(super) const ACCESS: u8 = 12;
+    pub(super) const APPLICATION: u8 = 13;
+    pub(super) const ATOM: u8 = 14;
+}
+
+fn binary_precedence(symbol: &BinaryOperationSymbol) -> (u8, Symbol) {
+    use precedence::*;
+
+    match symbol.clone() {
+        BinaryOperationSymbol::Pow(x) => (POW, x),
+        BinaryOperationSymbol::Mul(x) => (MUL, x),
+        BinaryOperationSymbol::Add(x) => (ADD, x),
+        BinaryOperationSymbol::ComplexComparison(x) => (COMPLEX_COMPARISON, x),
+        BinaryOperationSymbol::CompoundComparison(x) => (COMPOUND_COMPARISON, x),
+        BinaryOperationSymbol::Comparison(x) => (COMPARISON, x),
+        BinaryOperationSymbol::Bit(x) => (BIT, x),
+        BinaryOperationSymbol::Logic(x) => (LOGIC, x),
+        BinaryOperationSymbol::Assign(x) => (ASSIGN, x),
+    }
+}
+
+fn operation_precedence(operation: &Operation) -> u8 {
+    use precedence::*;
+
+    match operation {
+        Operation::Access { .. } => ACCESS,
+        Operation::TopUnary { .. } => UNARY,
+        Operation::Binary { operation, .. } => binary_precedence(operation).0,
+        Operation::Application(_) => APPLICATION,
+        Operation::Update { .. } => UPDATE,
+        // Lambdas and `if`s take everything to the right of them
+        Operation::Expression(Expression::Lambda { .. } | Expression::If(_)) => LOWEST,
+        Operation::Block(_) | Operation::Expression(_) | Operation::Record(_) => ATOM,
+    }
+}
+
+impl Printer<'_> {
+    fn file(&mut self, file: &File) {
+        for module in file.0.iter() {
+            self.blank_line();
+            self.module(module);
+        }
+    }
+
+    fn module(&mut self, module: &Module) {
+        match module {
+            Module::Global {
+                keyword,
+                id,
+                flow,
+                rest,
+            } => {
+                self.token(keyword.location());
+                self.space();
+                self.token(id.location());
+                self.space();
+                self.token(flow.location());
+                for item in rest.iter() {
+                    self.blank_line();
+                    self.top_level(item);
+                }
+            }
+            Module::Ordinary {
+                keyword,
+                id,
+                lbrace,
+                rest,
+                rbrace,
+            } => {
+                self.token(keyword.location());
+                self.space();
+                self.token(id.location());
+                self.space();
+                self.token(lbrace.location());
+                self.with_indent(self.line_indent() + INDENT, |this| {
+                    for (index, item) in rest.iter().enumerate() {
+                        if index == 0 {
+                            this.line_break();
+                        } else {
+                            this.blank_line();
+                        }
+                        this.top_level(item);
+                    }
+                    this.comments_before(rbrace.location());
+                });
+                if !rest.is_empty() {
+                    self.line_break();
+                }
+                self.token(rbrace.location());
+            }
+        }
+    }
+
+    fn top_level(&mut self, node: &TopLevelNode) {
+        match node {
+            TopLevelNode::Enum(x) => self.enum_statement(x),
+            TopLevelNode::Struct(x) => {
+                self.token(x.keyword.location());
+                self.space();
+                self.token(x.id.location());
+                self.generics(&x.generics);
+                if let Some(parameters) = &x.parameters {
+                    self.list(parameters, Self::typed_parameter);
+                }
+                if let Some(body) = &x.body {
+                    self.space();
+                    self.lines(body, Self::bodied_function);
+                }
+            }
+            TopLevelNode::TypeAlias(x) => {
+                self.token(x.keyword.location());
+                self.space();
+                self.token(x.id.location());
+                self.space();
+                self.token(x.equals.location());
+                self.space();
+                self.type_grammar(&x.aliased);
+            }
+            TopLevelNode::ForeignFunction(x) => {
+                self.token(x.keyword.location());
+                self.space();
+                self.abstract_function(&x.function);
+            }
+            TopLevelNode::BodiedFunction(x) => self.bodied_function(x),
+            TopLevelNode::Error(x) => self.token(x.location()),
+        }
+    }
+
+    fn enum_statement(&mut self, node: &Enum) {
+        let (Enum::Stack {
+            keyword,
+            id,
+            generics,
+            contents,
+        }
+        | Enum::Heap {
+            keyword,
+            id,
+            generics,
+            contents,
+        }) = node;
+
+        self.token(keyword.location());
+        self.space();
+        self.token(id.location());
+        self.generics(generics);
+        let Some(contents) = contents else {
+            self.punctuation(";");
+            return;
+        };
+
+        self.space();
+        self.token(contents.left.location());
+        if contents.inner.is_empty() {
+            self.token(contents.right.location());
+        } else if self.breaks_before_next() {
+            // Each variant on its own line, followed by a comma
+            self.with_indent(self.line_indent() + INDENT, |this| {
+                for variant in contents.inner.iter() {
+                    this.line_break();
+                    this.enum_variant(variant);
+                    this.punctuation(",");
+                }
+                this.comments_before(contents.right.location());
+            });
+            self.line_break();
+            self.token(contents.right.location());
+        } else {
+            self.space();
+            self.separated(&contents.inner, Self::enum_variant);
+            self.space();
+            self.token(contents.right.location());
+        }
+    }
+
+    fn enum_variant(&mut self, variant: &EnumVariant) {
+        self.token(variant.id.location());
+        match &variant.payload {
+            None => {}
+            Some(VariantPayload::Positional(x)) => self.list(x, Self::type_grammar),
+            Some(VariantPayload::Named(x)) => self.list(x, Self::typed_parameter),
+        }
+    }
+
+    fn abstract_function(&mut self, function: &AbstractFunction) {
+        self.token(function.keyword.location());
+        self.space();
+        self.token(function.id.location());
+        self.generics(&function.generics);
+        if let Some(params) = &function.params {
+            self.list(params, Self::typed_parameter);
+        }
+        self.return_type(&function.return_type);
+    }
+
+    fn bodied_function(&mut self, function: &BodiedFunction) {
+        self.token(function.keyword.location());
+        self.space();
+        self.token(function.id.location());
+        self.generics(&function.generics);
+        if let Some(params) = &function.params {
+            self.list(params, Self::parameter);
+        }
+        self.return_type(&function.return_type);
+        self.body(&function.body);
+    }
+
+    fn generics(&mut self, generics: &Option<Enclosed<Box<[TypeName]>>>) {
+        if let Some(generics) = generics {
+            self.list(generics, |this, it| this.token(it.location()));
+        }
+    }
+
+    fn return_type(&mut self, return_type: &Option<(Symbol, Type)>) {
+        if let Some((colon, ty)) = return_type {
+            self.token(colon.location());
+            self.space();
+            self.type_grammar(ty);
+        }
+    }
+
+    fn parameter(&mut self, parameter: &Parameter) {
+        match parameter {
+            Parameter::Typed(x) => self.typed_parameter(x),
+            Parameter::Untyped(x) => self.token(x.id.location()),
+        }
+    }
+
+    fn typed_parameter(&mut self, parameter: &TypedParameter) {
+        self.token(parameter.id.location());
+        self.text(":");
+        self.space();
+        self.type_grammar(&parameter.parameter_type);
+    }
+
+    fn type_grammar(&mut self, ty: &Type) {
+        match ty {
+            Type::Reference(x) => self.token(x.location()),
+            Type::Tuple(x) => self.list(x, Self::type_grammar),
+            Type::Application { id, arguments } => {
+                self.token(id.location());
+                self.list(arguments, Self::type_grammar);
+            }
+        }
+    }
+
+    fn body(&mut self, body: &Body) {
+        match body {
+            Body::Block(x) => {
+                self.space();
+                self.block(x);
+            }
+            Body::Simplified { flow, expression } => {
+                self.space();
+                self.token(flow.location());
+                if self.breaks_before_next() {
+                    self.with_indent(self.line_indent() + INDENT, |this| {
+                        this.line_break();
+                        this.block_level(expression);
+                    });
+                } else {
+                    self.space();
+                    self.block_level(expression);
+                }
+            }
+        }
+    }
+
+    fn block(&mut self, block: &ExpressionBlock) {
+        self.token(block.lbrace.location());
+        match &block.expression[..] {
+            [] => {}
+            [single] if !self.breaks_before_next() => {
+                self.space();
+                self.block_level(single);
+                self.space();
+            }
+            items => {
+                self.with_indent(self.line_indent() + INDENT, |this| {
+                    for item in items {
+                        this.line_break();
+                        this.block_level(item);
+                    }
+                    this.comments_before(block.rbrace.location());
+                });
+                self.line_break();
+            }
+        }
+        self.token(block.rbrace.location());
+    }
+
+    fn block_level(&mut self, node: &BlockLevelNode) {
+        match node {
+            BlockLevelNode::InitVar(x) => self.init_var(x),
+            BlockLevelNode::Block(x) => self.block(x),
+            BlockLevelNode::Function(x) => self.bodied_function(x),
+            BlockLevelNode::Operation(x) => self.operation(x, precedence::LOWEST),
+        }
+    }
+
+    fn init_var(&mut self, node: &InitializedVariable) {
+        let (Variable::Immutable {
+            keyword,
+            id,
+            assigned_type,
+        }
+        | Variable::Mutable {
+            keyword,
+            id,
+            assigned_type,
+        }) = &node.variable;
+
+        self.token(keyword.location());
+        self.space();
+        self.token(id.location());
+        self.return_type(assigned_type);
+        self.space();
+        self.token(node.equals.location());
+        self.space();
+        self.operation(&node.expression, precedence::LOWEST);
+    }
+
+    /// Prints `operation` in parentheses if it binds weaker than `min_precedence`
+    fn operation(&mut self, operation: &Operation, min_precedence: u8) {
+        if operation_precedence(operation) < min_precedence {
+            self.text("(");
+            self.operation(operation, precedence::LOWEST);
+            self.text(")");
+            return;
+        }
+
+        match operation {
+            Operation::Block(x) => self.block(x),
+            Operation::Access { left, dot, right } => {
+                self.operation(left, precedence::ACCESS);
+                self.token(dot.location());
+                self.operation(right, precedence::APPLICATION);
+            }
+            Operation::TopUnary { operator, expr } => {
+                self.token(operator.location());
+                self.operation(expr, precedence::UPDATE);
+            }
+            Operation::Binary {
+                left,
+                operation,
+                right,
+            } => {
+                let (power, symbol) = binary_precedence(operation);
+                let (left_power, right_power) = match operation {
+                    BinaryOperationSymbol::Pow(_) | BinaryOperationSymbol::Assign(_) => {
+                        (power + 1, power)
+                    }
+                    _ => (power, power + 1),
+                };
+                self.operation(left, left_power);
+                self.space();
+                self.token(symbol.location());
+                self.space();
+                self.operation(right, right_power);
+            }
+            Operation::Application(x) => self.application(x),
+            Operation::Expression(x) => self.expression(x),
+            Operation::Record(x) => {
+                self.token(x.id.location());
+                self.space();
+                self.fields(&x.fields);
+            }
+            Operation::Update {
+                record,
+                keyword,
+                fields,
+            } => {
+                self.operation(record, precedence::UPDATE);
+                self.space();
+                self.token(keyword.location());
+                self.space();
+                self.fields(fields);
+            }
+        }
+    }
+
+    fn application(&mut self, application: &Application) {
+        self.operation(&application.expr, precedence::ATOM);
+        if let Some(params) = &application.params {
+            self.list(params, |this, it| this.operation(it, precedence::LOWEST));
+        }
+    }
+
+    fn fields(&mut self, fields: &Enclosed<Box<[FieldInit]>>) {
+        self.token(fields.left.location());
+        self.space();
+        self.separated(&fields.inner, |this, it| {
+            this.token(it.id.location());
+            this.space();
+            this.token(it.equals.location());
+            this.space();
+            this.operation(&it.value, precedence::LOWEST);
+        });
+        self.space();
+        self.token(fields.right.location());
+    }
+
+    fn expression(&mut self, expression: &Expression) {
+        match expression {
+            Expression::Lambda { binds, flow, expr } => {
+                self.list(binds, Self::parameter);
+                self.space();
+                self.token(flow.location());
+                self.space();
+                self.operation(expr, precedence::LOWEST);
+            }
+            Expression::Term(Term::Reference(x)) => self.token(x.location()),
+            Expression::Term(Term::Contextual(x)) => self.contextual(x),
+            Expression::Literal(x) => self.literal(x),
+            Expression::If(x) => self.if_expression(x),
+        }
+    }
+
+    fn contextual(&mut self, reference: &ContextualReference) {
+        let mut context = &reference.context;
+        let mut path: Vec<&Reference> = vec![];
+        let global = loop {
+            match context {
+                Context::Global { colon } => break Some(colon),
+                Context::Local => break None,
+                Context::Inner { parent, needle } => {
+                    path.push(needle);
+                    context = parent;
+                }
+            }
+        };
+
+        if let Some(colon) = global {
+            self.token(colon.location());
+        }
+        for item in path.into_iter().rev() {
+            self.token(item.location());
+            self.text("::");
+        }
+        self.token(reference.inner.location());
+    }
+
+    fn literal(&mut self, literal: &Literal) {
+        match literal {
+            // Single item needs a comma to not be taken for parentheses
+            Literal::Tuple(x) if x.inner.len() == 1 => {
+                self.token(x.left.location());
+                self.operation(&x.inner[0], precedence::LOWEST);
+                self.punctuation(",");
+                self.token(x.right.location());
+            }
+            Literal::Tuple(x) => self.list(x, |this, it| this.operation(it, precedence::LOWEST)),
+            Literal::Interpolated(x) => {
+                for (fragment, expression) in x.fragments.iter().zip(x.expressions.iter()) {
+                    self.token(fragment.location());
+                    self.operation(expression, precedence::LOWEST);
+                }
+                if let Some(last) = x.fragments.last() {
+                    self.token(last.location());
+                }
+            }
+            _ => self.token(literal.location()),
+        }
+    }
+
+    /// Prints `if` chain. When it spans several lines, each branch starts on its own line
+    /// aligned with `if`, and in chains with `elif`s conditions and bodies are aligned too.
+    fn if_expression(&mut self, expression: &IfExpr) {
+        let column = self.column();
+        let last_keyword = expression
+            .el
+            .as_ref()
+            .map(|it| it.keyword.location())
+            .or_else(|| expression.elif.last().map(|it| it.keyword.location()));
+        let multiline =
+            last_keyword.is_some_and(|it| self.breaks_between(expression.keyword.location(), it));
+
+        let branches: Vec<_> = std::iter::once((
+            &expression.keyword,
+            Some(&expression.condition),
+            &expression.body,
+        ))
+        .chain(
+            expression
+                .elif
+                .iter()
+                .map(|it| (&it.keyword, Some(&it.condition), &it.body)),
+        )
+        .chain(expression.el.iter().map(|it| (&it.keyword, None, &it.body)))
+        .collect();
+        let widths: Option<Vec<_>> = (multiline && !expression.elif.is_empty())
+            .then(|| {
+                branches
+                    .iter()
+                    .map(|(keyword, condition, _)| match condition {
+                        None => Some(0),
+                        Some(condition) => {
+                            let mut probe = self.probe(keyword.location());
+                            probe.operation(condition, precedence::LOWEST);
+                            probe.single_line().map(|it| it.chars().count() + 1)
+                        }
+                    })
+                    .collect()
+            })
+            .flatten();
+        let head_width = widths.as_ref().and_then(|it| it.iter().max().copied());
+
+        self.with_indent(column, |this| {
+            for (index, (keyword, condition, body)) in branches.iter().enumerate() {
+                if index > 0 && multiline {
+                    this.line_break();
+                } else if index > 0 {
+                    this.space();
+                }
+                this.token(keyword.location());
+                let keyword_width = this.source(keyword.location()).len();
+                if head_width.is_some() {
+                    this.pad("elif".len() - keyword_width);
+                }
+                if let Some(condition) = condition {
+                    this.space();
+                    this.operation(condition, precedence::LOWEST);
+                }
+                if let (Some(widths), Some(max)) = (&widths, head_width) {
+                    this.pad(max - widths[index]);
+                }
+                this.body(body);
+            }
+        });
+    }
+
+    fn list<T>(&mut self, items: &Enclosed<Box<[T]>>, item: impl FnMut(&mut Self, &T)) {
+        self.token(items.left.location());
+        self.separated(&items.inner, item);
+        self.token(items.right.location());
+    }
+
+    fn separated<T>(&mut self, items: &[T], mut item: impl FnMut(&mut Self, &T)) {
+        for (index, it) in items.iter().enumerate() {
+            if index > 0 {
+                self.punctuation(",");
+                self.space();
+            }
+            item(self, it);
+        }
+    }
+
+    /// Prints items enclosed in braces on separate lines
+    fn lines<T>(&mut self, items: &Enclosed<Box<[T]>>, mut item: impl FnMut(&mut Self, &T)) {
+        self.token(items.left.location());
+        if !items.inner.is_empty() {
+            self.with_indent(self.line_indent() + INDENT, |this| {
+                for it in items.inner.iter() {
+                    this.line_break();
+                    item(this, it);
+                }
+                this.comments_before(items.right.location());
+            });
+            self.line_break();
+        }
+        self.token(items.right.location());
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use rstest::rstest;
+
+    use super::format;
+    use crate::common::RLTProducer;
+    use crate::lexer::PegLexer;
+    use crate::parser::PegParser;
+    use crate::token_stream::PackedTokenStream;
+    use crate::tokenizer::{LazyTokenizer, Tok, TokCtor};
+    use crate::trivia::LosslessTokens;
+
+    #[rstest]
+    #[case::spacing(
+        "module A =>\nfun  f(a:Int,b)=>a+b*2",
+        "module A =>\n\nfun f(a: Int, b) => a + b * 2\n"
+    )]
+    #[case::parentheses(
+        "module A =>\nfun f => ((a + b)) * c - (d - -e)",
+        "module A =>\n\nfun f => (a + b) * c - (d - -e)\n"
+    )]
+    #[case::enum_lines(
+        "module A =>\nenum struct E {\n A, B\n}",
+        "module A =>\n\nenum struct E {\n    A,\n    B,\n}\n"
+    )]
+    #[case::if_alignment(
+        "module A =>\nfun f(x) =>\n  if x == 1 => 1\n  elif x == 22 => 2\n  else => 3",
+        "module A =>\n\nfun f(x) =>\n    if   x == 1  => 1\n    elif x == 22 => 2\n    else         => 3\n"
+    )]
+    #[case::comments(
+        "// a\nmodule A =>\n\n\n// doc\nfun f => 1 // tail\n",
+        "// a\nmodule A =>\n\n// doc\nfun f => 1 // tail\n"
+    )]
+    fn test_format(#[case] input: &str, #[case] expected: &str) {
+        let tokens = LazyTokenizer::new(input, PegLexer::<false>::new()).into_vec();
+        let stream = PackedTokenStream::new(&tokens);
+        let rlt = PegParser::<false>::new().parse_stream(&stream).unwrap();
+
+        assert_eq!(format(input, &LosslessTokens::new(&tokens), &rlt), expected);
+    }
+}
@@ -0,0 +1,279 @@
+use kodept_core::code_point::CodePoint;
+
+use crate::lexer::PackedToken::{Comment, MultilineComment, Newline};
+use crate::token_match::PackedTokenMatch;
+
+/// Writes formatted output, taking care of indentation and comments.
+///
+/// Significant tokens are written through [`Printer::token`] in source order,
+/// so every comment found in between is printed near the place it was written at.
+#[derive(Clone)]
+pub(super) struct Printer<'a> {
+    input: &'a str,
+    /// All tokens of the source including trivia
+    tokens: &'a [PackedTokenMatch],
+    /// Index of the first token not yet printed
+    cursor: usize,
+    output: String,
+    indent: usize,
+    at_line_start: bool,
+    pending_space: bool,
+    /// Set after a line comment, so the next output goes to a new line
+    pending_newline: bool,
+    /// Where the line comment after the last token starts in the output
+    trailing_comment: Option<usize>,
+}
+
+impl<'a> Printer<'a> {
+    pub(super) fn new(input: &'a str, tokens: &'a [PackedTokenMatch]) -> Self {
+        Self {
+            input,
+            tokens,
+            cursor: 0,
+            output: String::with_capacity(input.len()),
+            indent: 0,
+            at_line_start: true,
+            pending_space: false,
+            pending_newline: false,
+            trailing_comment: None,
+        }
+    }
+
+    /// Copy of this printer writing to an empty line, used to measure output before printing it.
+    /// Everything before `point` is treated as already printed.
+    pub(super) fn probe(&self, point: CodePoint) -> Self {
+        Self {
+            cursor: self.find(point.offset as usize),
+            output: String::new(),
+            indent: 0,
+            at_line_start: false,
+            pending_space: false,
+            pending_newline: false,
+            ..self.clone()
+        }
+    }
+
+    /// Returns output of a probe if it fits on a single line
+    pub(super) fn single_line(self) -> Option<String> {
+        (!self.pending_newline && !self.output.contains('\n')).then_some(self.output)
+    }
+
+    pub(super) fn finish(mut self) -> String {
+        self.flush_comments(self.tokens.len());
+        let length = self.output.trim_end().len();
+        self.output.truncate(length);
+        if !self.output.is_empty() {
+            self.output.push('\n');
+        }
+        self.output
+    }
+
+    pub(super) fn source(&self, point: CodePoint) -> &'a str {
+        &self.input[point.as_range()]
+    }
+
+    /// Whether there is a line break in the source between `from` and `to`
+    pub(super) fn breaks_between(&self, from: CodePoint, to: CodePoint) -> bool {
+        self.input[from.offset as usize..to.offset as usize].contains('\n')
+    }
+
+    pub(super) fn column(&self) -> usize {
+        if self.at_line_start || self.pending_newline {
+            return self.indent;
+        }
+        let line_start = self.output.rfind('\n').map_or(0, |it| it + 1);
+        self.output[line_start..].chars().count() + usize::from(self.pending_space)
+    }
+
+    pub(super) fn with_indent<R>(&mut self, indent: usize, f: impl FnOnce(&mut Self) -> R) -> R {
+        let old = std::mem::replace(&mut self.indent, indent);
+        let result = f(self);
+        self.indent = old;
+        result
+    }
+
+    /// Indentation of the line being printed
+    pub(super) fn line_indent(&self) -> usize {
+        if self.at_line_start || self.pending_newline {
+            return self.indent;
+        }
+        let line_start = self.output.rfind('\n').map_or(0, |it| it + 1);
+        let line = &self.output[line_start..];
+        line.len() - line.trim_start().len()
+    }
+
+    pub(super) fn text(&mut self, text: &str) {
+        if self.pending_newline {
+            self.newline();
+        }
+        if self.at_line_start {
+            self.output.extend(std::iter::repeat(' ').take(self.indent));
+            self.at_line_start = false;
+        } else if self.pending_space {
+            self.output.push(' ');
+        }
+        self.pending_space = false;
+        self.output.push_str(text);
+    }
+
+    /// Prints punctuation right after the last token, even if it has a comment after it
+    pub(super) fn punctuation(&mut self, text: &str) {
+        let next = self.tokens[self.cursor..]
+            .iter()
+            .find(|it| !it.token.is_ignored())
+            .filter(|it| self.source(it.point) == text);
+        if let Some(next) = next {
+            return self.token(next.point);
+        }
+
+        match self.trailing_comment {
+            Some(position) if self.pending_newline => {
+                self.output.insert_str(position, text);
+                self.trailing_comment = Some(position + text.len());
+            }
+            _ => self.text(text),
+        }
+    }
+
+    /// Adds `width` spaces right after the last output
+    pub(super) fn pad(&mut self, width: usize) {
+        if width > 0 {
+            self.text(&" ".repeat(width));
+        }
+    }
+
+    pub(super) fn space(&mut self) {
+        self.pending_space = true;
+    }
+
+    pub(super) fn newline(&mut self) {
+        self.output.push('\n');
+        self.at_line_start = true;
+        self.pending_space = false;
+        self.pending_newline = false;
+    }
+
+    /// Starts a new line, unless it is already started
+    pub(super) fn line_break(&mut self) {
+        if !self.at_line_start {
+            self.newline();
+        }
+    }
+
+    /// Separates next output with an empty line, unless it is the start of a block
+    pub(super) fn blank_line(&mut self) {
+        self.line_break();
+        let previous = self.output.trim_end();
+        if !previous.is_empty() && !previous.ends_with('{') && !self.output.ends_with("\n\n") {
+            self.newline();
+        }
+    }
+
+    /// Prints comments left before the closing token at `point`, e.g. at the end of a block
+    pub(super) fn comments_before(&mut self, point: CodePoint) {
+        let end = self.find(point.offset as usize);
+        self.flush_comments(end);
+        while self.output.ends_with("\n\n") {
+            self.output.pop();
+        }
+    }
+
+    /// Whether there is a line break in the source before the next significant token
+    pub(super) fn breaks_before_next(&self) -> bool {
+        self.pending_newline
+            || self.tokens[self.cursor..]
+                .iter()
+                .take_while(|it| it.token.is_ignored())
+                .any(|it| matches!(it.token, Newline | Comment))
+    }
+
+    /// Prints significant tokens at `point` with comments before and after them
+    pub(super) fn token(&mut self, point: CodePoint) {
+        let start = self.find(point.offset as usize);
+        self.flush_comments(start);
+
+        let end = self.find((point.offset + point.length) as usize);
+        let mut words = self.tokens[start..end]
+            .iter()
+            .filter(|it| !it.token.is_ignored());
+        if let Some(first) = words.next() {
+            self.text(self.source(first.point));
+        }
+        for word in words {
+            self.space();
+            self.text(self.source(word.point));
+        }
+        self.cursor = end;
+        self.flush_trailing_comments();
+    }
+
+    /// Returns index of the first token starting at `offset` or later
+    fn find(&self, offset: usize) -> usize {
+        self.cursor
+            + self.tokens[self.cursor..].partition_point(|it| (it.point.offset as usize) < offset)
+    }
+
+    fn comment(&self, token: &PackedTokenMatch) -> &'a str {
+        self.source(token.point).trim_end()
+    }
+
+    /// Prints comments on the same line as the last printed token
+    fn flush_trailing_comments(&mut self) {
+        while let Some(next) = self.tokens.get(self.cursor) {
+            match next.token {
+                MultilineComment => {
+                    self.space();
+                    self.text(self.comment(next));
+                    self.space();
+                }
+                Comment => {
+                    self.space();
+                    self.trailing_comment = Some(self.output.len());
+                    self.text(self.comment(next));
+                    self.pending_newline = true;
+                    self.cursor += 1;
+                    return;
+                }
+                Newline => return,
+                _ if next.token.is_ignored() => {}
+                _ => return,
+            }
+            self.cursor += 1;
+        }
+    }
+
+    /// Prints comments on their own lines before the token at `end`
+    fn flush_comments(&mut self, end: usize) {
+        let mut newlines = 0;
+        let mut printed = false;
+        while self.cursor < end {
+            let next = self.tokens[self.cursor];
+            self.cursor += 1;
+            match next.token {
+                Newline => newlines += 1,
+                Comment | MultilineComment => {
+                    if newlines > 1 {
+                        self.blank_line();
+                    } else if newlines == 1 {
+                        self.line_break();
+                    }
+                    self.space();
+                    self.text(self.comment(&next));
+                    printed = true;
+                    newlines = usize::from(self.source(next.point).ends_with('\n'));
+                    if next.token == Comment {
+                        self.pending_newline = true;
+                    } else {
+                        self.space();
+                    }
+                }
+                _ => {}
+            }
+        }
+        if newlines > 1 && (printed || self.at_line_start) {
+            self.blank_line();
+        } else if newlines == 1 && printed {
+            self.line_break();
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use std::ops::Range;
+
+use kodept_core::code_point::CodePoint;
+
+use crate::lexer::PackedToken;
+use crate::token_match::PackedTokenMatch;
+
+/// Returns position right after the string literal starting at `start`
+fn skip_string(text: &[u8], start: usize) -> Option<usize> {
+    let mut pos = start + 1;
+    while pos < text.len() {
+        match text[pos] {
+            b'\\' => pos += 2,
+            b'"' => return Some(pos + 1),
+            b'$' if text.get(pos + 1) == Some(&b'{') => match skip_braced(text, pos + 2) {
+                Some(end) => pos = end + 1,
+                None => pos += 1,
+            },
+            _ => pos += 1,
+        }
+    }
+    None
+}
+
+/// Returns position of `}` closing the brace opened right before `start`
+fn skip_braced(text: &[u8], start: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut pos = start;
+    while pos < text.len() {
+        match text[pos] {
+            b'"' => {
+                pos = skip_string(text, pos)?;
+                continue;
+            }
+            b'{' => depth += 1,
+            b'}' if depth == 0 => return Some(pos),
+            b'}' => depth -= 1,
+            _ => {}
+        }
+        pos += 1;
+    }
+    None
+}
+
+/// Returns ranges of expressions embedded in the string literal `text`, excluding `${` and `}`
+fn embedded_expressions(text: &str) -> Vec<Range<usize>> {
+    let text = text.as_bytes();
+    let mut result = vec![];
+    let mut pos = 1;
+    while pos < text.len() {
+        match text[pos] {
+            b'\\' => pos += 2,
+            b'$' if text.get(pos + 1) == Some(&b'{') => match skip_braced(text, pos + 2) {
+                Some(end) => {
+                    result.push(pos + 2..end);
+                    pos = end + 1;
+                }
+                None => pos += 1,
+            },
+            _ => pos += 1,
+        }
+    }
+    result
+}
+
+fn fragment(token: PackedToken, range: Range<usize>) -> PackedTokenMatch {
+    PackedTokenMatch::new(
+        token,
+        CodePoint::new((range.end - range.start) as u32, range.start as u32),
+    )
+}
+
+/// Replaces string literals with embedded expressions, e.g. `"Hello, ${name}!"`,
+/// by `StringStart`, tokens of embedded expressions, `StringMiddle`s and `StringEnd`.
+/// `lex` produces tokens of the given range of `input`.
+pub(crate) fn split_interpolations<E>(
+    input: &str,
+    tokens: Vec<PackedTokenMatch>,
+    lex: &mut impl FnMut(Range<usize>) -> Result<Vec<PackedTokenMatch>, E>,
+) -> Result<Vec<PackedTokenMatch>, E> {
+    let mut result = Vec::with_capacity(tokens.len());
+    for token in tokens {
+        if token.token != PackedToken::String {
+            result.push(token);
+            continue;
+        }
+        let start = token.point.offset as usize;
+        let end = start + token.point.length as usize;
+        let expressions = embedded_expressions(&input[start..end]);
+        if expressions.is_empty() {
+            result.push(token);
+            continue;
+        }
+
+        let mut fragment_start = start;
+        for (index, expression) in expressions.into_iter().enumerate() {
+            let expression = start + expression.start..start + expression.end;
+            let kind = if index == 0 {
+                PackedToken::StringStart
+            } else {
+                PackedToken::StringMiddle
+            };
+            result.push(fragment(kind, fragment_start..expression.start));
+            let inner = lex(expression.clone())?;
+            result.extend(split_interpolations(input, inner, lex)?);
+            fragment_start = expression.end;
+        }
+        result.push(fragment(PackedToken::StringEnd, fragment_start..end));
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use rstest::rstest;
+
+    use super::embedded_expressions;
+
+    #[rstest]
+    #[case::plain(r#""hello""#, vec![])]
+    #[case::single(r#""Hello, ${name}!""#, vec![10..14])]
+    #[case::many(r#""${a} and ${b}""#, vec![3..4, 12..13])]
+    #[case::escaped(r#""\${a}""#, vec![])]
+    #[case::unclosed(r#""${""#, vec![])]
+    #[case::nested_braces(r#""${{a}}""#, vec![3..6])]
+    #[case::nested_string(r#""${f("}")}""#, vec![3..9])]
+    fn test_embedded_expressions(
+        #[case] input: &str,
+        #[case] expected: Vec<std::ops::Range<usize>>,
+    ) {
+        assert_eq!(embedded_expressions(input), expected);
+    }
+}
@@ -180,6 +180,12 @@ pub enum PackedToken {
     Floating,
     Char,
     String,
+    /// Part of a string literal up to the first embedded expression, e.g. `"Hello, ${`
+    StringStart,
+    /// Part of a string literal between embedded expressions, e.g. `} and ${`
+    StringMiddle,
+    /// Part of a string literal after the last embedded expression, e.g. `}!"`
+    StringEnd,
     Dot,
     Flow,
     Plus,
@@ -221,6 +227,19 @@ impl PackedToken {
         }
     }
 
+    /// Whether a top level declaration or a module can start with this token
+    pub fn starts_declaration(&self) -> bool {
+        matches!(
+            self,
+            PackedToken::Fun
+                | PackedToken::Foreign
+                | PackedToken::Struct
+                | PackedToken::Enum
+                | PackedToken::TypeAlias
+                | PackedToken::Module
+        )
+    }
+
     pub fn is_symbol(&self) -> bool {
         match self {
             PackedToken::Comma => true,
@@ -29,6 +29,11 @@ mod tests {
     )]
     #[case::ignore_newline("\n\n\n", Newline, Some("\n\n"))]
     #[case::ignore_whitespace("   \t", Whitespace, None)]
+    #[case::char_escape(r"'\n'", Char, None)]
+    #[case::char_unicode_escape(r"'\u{1F600}' rest", Char, Some(" rest"))]
+    #[case::string_escaped_quote(r#""a\"b" rest"#, String, Some(" rest"))]
+    #[case::string_interpolation(r#""a ${f("}")} b" rest"#, String, Some(" rest"))]
+    #[case::floating_exponent("1.5e10", Floating, None)]
     fn test_parser(
         #[case] input: &'static str,
         #[case] expected: PackedToken,
@@ -6,11 +6,16 @@ pub mod token_stream;
 pub mod tokenizer;
 
 pub mod error;
+pub mod formatter;
+pub mod trace;
+pub mod trivia;
 
+mod interpolation;
+
+mod nom;
 mod peg;
 mod pest;
-mod nom;
 
 pub mod common;
 
-pub const TRACING_OPTION: bool = cfg!(feature = "trace");
\ No newline at end of file
+pub const TRACING_OPTION: bool = cfg!(feature = "trace");
@@ -14,10 +14,12 @@ pub(crate) const UPPER_ALPHABET: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
 mod grammar {
     use nom::branch::alt;
     use nom::bytes::complete::{is_a, is_not, take_while};
-    use nom::character::complete::{anychar, char, digit0, digit1, not_line_ending, one_of};
+    use nom::character::complete::{
+        anychar, char, digit0, digit1, none_of, not_line_ending, one_of,
+    };
     use nom::combinator::{map, opt, recognize, value};
     use nom::error::context;
-    use nom::multi::{many1, many_till};
+    use nom::multi::{many0, many1, many_till};
     use nom::sequence::{delimited, tuple};
     use nom::Parser;
     use nom_supreme::tag::complete::{tag, tag_no_case};
@@ -63,7 +65,7 @@ mod grammar {
             .recognize()
             .map(Ignore::Comment);
         let multiline_comment = tag("/*")
-            .precedes(many_till(anychar, tag("*/")).cut())
+            .precedes(many_till(anychar, tag("*/")))
             .recognize()
             .map(Ignore::MultilineComment);
 
@@ -82,17 +84,17 @@ mod grammar {
         context(
             "keyword",
             alt(include_literals! {
-                "fun" => Keyword::Fun,
-                "val" => Keyword::Val,
-                "var" => Keyword::Var,
+                soft "fun" => Keyword::Fun,
+                soft "val" => Keyword::Val,
+                soft "var" => Keyword::Var,
                 soft "if" => Keyword::If,
                 soft "elif" => Keyword::Elif,
                 soft "else" => Keyword::Else,
-                "match" => Keyword::Match,
-                "while" => Keyword::While,
-                "module" => Keyword::Module,
-                "extend" => Keyword::Extend,
-                "return" => Keyword::Return,
+                soft "match" => Keyword::Match,
+                soft "while" => Keyword::While,
+                soft "module" => Keyword::Module,
+                soft "extend" => Keyword::Extend,
+                soft "return" => Keyword::Return,
                 "\\" => Keyword::Lambda,
                 soft "abstract" => Keyword::Abstract,
                 soft "trait" => Keyword::Trait,
@@ -143,6 +145,34 @@ mod grammar {
         )(input)
     }
 
+    fn string_contents(input: &str) -> TResult<'_, &str> {
+        many0(alt((
+            char('\\').precedes(anychar).recognize(),
+            interpolation,
+            is_not("\\\"$"),
+            tag("$"),
+        )))
+        .recognize()
+        .parse(input)
+    }
+
+    /// Embedded expression is split into separate tokens by the tokenizer
+    fn interpolation(input: &str) -> TResult<'_, &str> {
+        tuple((tag("${"), braced, char('}')))
+            .recognize()
+            .parse(input)
+    }
+
+    fn braced(input: &str) -> TResult<'_, &str> {
+        many0(alt((
+            delimited(char('"'), string_contents, char('"')).recognize(),
+            delimited(char('{'), braced, char('}')).recognize(),
+            is_not("{}\""),
+        )))
+        .recognize()
+        .parse(input)
+    }
+
     fn literal(input: &str) -> TResult<Literal> {
         fn number_parser<'a>(
             prefix: &'static str,
@@ -170,8 +200,18 @@ mod grammar {
             )),
             opt(tuple((tag_no_case("e"), opt(one_of("-+")), digit1))),
         )));
-        let char_p = delimited(char('\''), anychar.recognize(), char('\''));
-        let string = delimited(char('"'), is_not(r#"""#).opt(), char('"'));
+        let escape = || {
+            char('\\').precedes(alt((
+                tuple((tag("u{"), is_not("}'").opt(), char('}'))).recognize(),
+                anychar.recognize(),
+            )))
+        };
+        let char_p = delimited(
+            char('\''),
+            alt((escape().recognize(), none_of("'").recognize())),
+            char('\''),
+        );
+        let string = delimited(char('"'), string_contents, char('"'));
 
         context(
             "literal",
@@ -181,7 +221,7 @@ mod grammar {
                 map(hex, Literal::Hex),
                 map(floating, Literal::Floating),
                 map(char_p, Literal::Char),
-                map(string, |it| Literal::String(it.unwrap_or_default())),
+                map(string, Literal::String),
             )),
         )(input)
     }
@@ -245,10 +285,10 @@ mod grammar {
             alt((
                 map(ignore, Token::Ignore),
                 map(keyword, Token::Keyword),
-                map(symbol, Token::Symbol),
                 map(identifier, Token::Identifier),
-                map(literal, Token::Literal),
+                map(symbol, Token::Symbol),
                 map(operator, Token::Operator),
+                map(literal, Token::Literal),
             )),
         )(input)
     }
@@ -3,8 +3,8 @@ use nom::IResult;
 use nom_supreme::error::ErrorTree;
 use thiserror::Error;
 
-mod lexer;
 mod error;
+mod lexer;
 mod parser;
 
 pub(crate) use lexer::Lexer;
@@ -17,4 +17,4 @@ type TResult<'t, O> = IResult<&'t str, O, TokenizationError<'t>>;
 #[error("Expected `{expected}`")]
 pub struct TokenVerificationError {
     pub expected: &'static str,
-}
\ No newline at end of file
+}
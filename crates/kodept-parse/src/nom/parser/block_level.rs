@@ -1,6 +1,6 @@
 use crate::lexer::PackedToken::*;
-use crate::nom::parser::macros::{function};
-use crate::nom::parser::utils::{match_token, newline_separated};
+use crate::nom::parser::macros::function;
+use crate::nom::parser::utils::{match_token, newline_separated, RuleExt};
 use crate::nom::parser::{function, operator, r#type, ParseResult};
 use crate::token_stream::PackedTokenStream;
 use kodept_core::structure::rlt;
@@ -11,13 +11,13 @@ use nom::sequence::tuple;
 use nom::Parser;
 use nom_supreme::ParserExt;
 
-fn block(input: PackedTokenStream) -> ParseResult<rlt::ExpressionBlock> {
+pub(super) fn block(input: PackedTokenStream) -> ParseResult<rlt::ExpressionBlock> {
     tuple((
         match_token(LBrace),
         newline_separated(grammar),
         match_token(RBrace),
     ))
-    .context(function!())
+    .rule(function!())
     .map(|it| rlt::ExpressionBlock {
         lbrace: Symbol::from_located(it.0),
         expression: it.1.into_boxed_slice(),
@@ -28,7 +28,7 @@ fn block(input: PackedTokenStream) -> ParseResult<rlt::ExpressionBlock> {
 
 fn simple(input: PackedTokenStream) -> ParseResult<rlt::Body> {
     tuple((match_token(Flow), grammar.cut()))
-        .context(function!())
+        .rule(function!())
         .map(|it| rlt::Body::Simplified {
             flow: Symbol::from_located(it.0),
             expression: it.1,
@@ -38,7 +38,7 @@ fn simple(input: PackedTokenStream) -> ParseResult<rlt::Body> {
 
 pub(super) fn body(input: PackedTokenStream) -> ParseResult<rlt::Body> {
     alt((block.map(rlt::Body::Block), simple))
-        .context(function!())
+        .rule(function!())
         .parse(input)
 }
 
@@ -50,7 +50,7 @@ fn var_declaration(input: PackedTokenStream) -> ParseResult<rlt::Variable> {
         tuple((match_token(Colon), r#type::grammar)).opt(),
     ))
     .cut()
-    .context(function!())
+    .rule(function!())
     .parse(input)?;
 
     if kind.token == Val {
@@ -80,7 +80,7 @@ fn initialized_variable(input: PackedTokenStream) -> ParseResult<rlt::Initialize
         match_token(Equals).cut(),
         operator::grammar,
     ))
-    .context(function!())
+    .rule(function!())
     .map(|it| rlt::InitializedVariable {
         variable: it.0,
         equals: Symbol::from_located(it.1),
@@ -96,6 +96,6 @@ pub(super) fn grammar(input: PackedTokenStream) -> ParseResult<rlt::BlockLevelNo
         function::bodied.map(rlt::BlockLevelNode::Function),
         operator::grammar.map(rlt::BlockLevelNode::Operation),
     ))
-    .context(function!())
+    .rule(function!())
     .parse(input)
 }
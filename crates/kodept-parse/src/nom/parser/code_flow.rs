@@ -3,17 +3,17 @@ use nom::sequence::tuple;
 use nom::Parser;
 use nom_supreme::ParserExt;
 
-use kodept_core::structure::rlt;
-use kodept_core::structure::rlt::new_types::Keyword;
 use crate::lexer::PackedToken::*;
 use crate::nom::parser::macros::function;
-use crate::nom::parser::utils::match_token;
+use crate::nom::parser::utils::{match_token, RuleExt};
 use crate::nom::parser::{block_level, operator, ParseResult};
 use crate::token_stream::PackedTokenStream;
+use kodept_core::structure::rlt;
+use kodept_core::structure::rlt::new_types::Keyword;
 
 fn else_expr(input: PackedTokenStream) -> ParseResult<rlt::ElseExpr> {
     tuple((match_token(Else), block_level::body.cut()))
-        .context(function!())
+        .rule(function!())
         .map(|it| rlt::ElseExpr {
             keyword: Keyword::from_located(it.0),
             body: it.1,
@@ -27,7 +27,7 @@ fn elif_expr(input: PackedTokenStream) -> ParseResult<rlt::ElifExpr> {
         operator::grammar.cut(),
         block_level::body.cut(),
     ))
-    .context(function!())
+    .rule(function!())
     .map(|it| rlt::ElifExpr {
         keyword: Keyword::from_located(it.0),
         condition: it.1,
@@ -44,7 +44,7 @@ pub(super) fn if_expr(input: PackedTokenStream) -> ParseResult<rlt::IfExpr> {
         many0(elif_expr),
         else_expr.opt(),
     ))
-    .context(function!())
+    .rule(function!())
     .map(|it| rlt::IfExpr {
         keyword: Keyword::from_located(it.0),
         condition: it.1,
@@ -1,13 +1,12 @@
 use nom::branch::alt;
 use nom::sequence::tuple;
 use nom::Parser;
-use nom_supreme::ParserExt;
 
 use crate::common::VerboseEnclosed;
 use crate::lexer::PackedToken::*;
 use crate::nom::parser::macros::function;
 use crate::nom::parser::parameter::parameter;
-use crate::nom::parser::utils::{comma_separated0, match_token};
+use crate::nom::parser::utils::{comma_separated0, match_token, RuleExt};
 use crate::nom::parser::{code_flow, literal, operator, term, ParseResult};
 use crate::token_stream::PackedTokenStream;
 use kodept_core::structure::rlt;
@@ -15,13 +14,13 @@ use kodept_core::structure::rlt::new_types::Symbol;
 
 fn lambda(input: PackedTokenStream) -> ParseResult<rlt::Expression> {
     tuple((
-        match_token(LBrace),
+        match_token(LBracket),
         comma_separated0(parameter),
-        match_token(RBrace),
+        match_token(RBracket),
         match_token(Flow),
         operator::grammar,
     ))
-    .context(function!())
+    .rule(function!())
     .map(|it| rlt::Expression::Lambda {
         binds: VerboseEnclosed::from((it.0, it.1.into_boxed_slice(), it.2)).into(),
         flow: Symbol::from_located(it.3),
@@ -37,6 +36,6 @@ pub(super) fn grammar(input: PackedTokenStream) -> ParseResult<rlt::Expression>
         literal::grammar.map(rlt::Expression::Literal),
         code_flow::if_expr.map(|it| rlt::Expression::If(Box::new(it))),
     ))
-    .context(function!())
+    .rule(function!())
     .parse(input)
 }
@@ -3,9 +3,9 @@ use nom::Parser;
 use nom_supreme::ParserExt;
 
 use crate::lexer::PackedToken::*;
-use crate::nom::parser::macros::{function};
+use crate::nom::parser::macros::function;
 use crate::nom::parser::parameter::{parameter, typed_parameter};
-use crate::nom::parser::utils::{comma_separated0, match_token, paren_enclosed};
+use crate::nom::parser::utils::{comma_separated0, match_token, paren_enclosed, RuleExt};
 use crate::nom::parser::{block_level, r#type, ParseResult};
 use crate::token_stream::PackedTokenStream;
 use kodept_core::structure::rlt;
@@ -19,15 +19,40 @@ fn abstract_function(input: PackedTokenStream) -> ParseResult<rlt::AbstractFunct
         match_token(Abstract),
         match_token(Fun),
         match_token(Identifier),
+        r#type::generics.opt(),
         paren_enclosed(comma_separated0(typed_parameter)).opt(),
         tuple((match_token(Colon), r#type::grammar)).opt(),
     ))
-    .context(function!())
+    .rule(function!())
     .map(|it| rlt::AbstractFunction {
         keyword: Keyword::from_located(it.1),
         id: new_types::Identifier::from_located(it.2),
-        params: it.3.map(|it| it.into()),
-        return_type: it.4.map(|it| (Symbol::from_located(it.0), it.1)),
+        generics: it.3.map(|it| it.into()),
+        params: it.4.map(|it| it.into()),
+        return_type: it.5.map(|it| (Symbol::from_located(it.0), it.1)),
+    })
+    .parse(input)
+}
+
+pub(super) fn foreign(input: PackedTokenStream) -> ParseResult<rlt::ForeignFunction> {
+    tuple((
+        match_token(Foreign),
+        match_token(Fun).cut(),
+        match_token(Identifier).cut(),
+        r#type::generics.opt(),
+        paren_enclosed(comma_separated0(typed_parameter)).opt(),
+        tuple((match_token(Colon), r#type::grammar.cut())).opt(),
+    ))
+    .rule(function!())
+    .map(|it| rlt::ForeignFunction {
+        keyword: Keyword::from_located(it.0),
+        function: rlt::AbstractFunction {
+            keyword: Keyword::from_located(it.1),
+            id: new_types::Identifier::from_located(it.2),
+            generics: it.3.map(|it| it.into()),
+            params: it.4.map(|it| it.into()),
+            return_type: it.5.map(|it| (Symbol::from_located(it.0), it.1)),
+        },
     })
     .parse(input)
 }
@@ -36,17 +61,19 @@ pub(super) fn bodied(input: PackedTokenStream) -> ParseResult<rlt::BodiedFunctio
     tuple((
         match_token(Fun),
         match_token(Identifier),
+        r#type::generics.opt(),
         paren_enclosed(comma_separated0(parameter)).opt(),
         tuple((match_token(Colon), r#type::grammar.cut())).opt(),
         block_level::body.cut(),
     ))
-    .context(function!())
+    .rule(function!())
     .map(|it| rlt::BodiedFunction {
         keyword: Keyword::from_located(it.0),
         id: new_types::Identifier::from_located(it.1),
-        params: it.2.map(|it| it.into()),
-        return_type: it.3.map(|it| (Symbol::from_located(it.0), it.1)),
-        body: Box::new(it.4),
+        generics: it.2.map(|it| it.into()),
+        params: it.3.map(|it| it.into()),
+        return_type: it.4.map(|it| (Symbol::from_located(it.0), it.1)),
+        body: Box::new(it.5),
     })
     .parse(input)
 }
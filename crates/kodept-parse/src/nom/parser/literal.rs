@@ -1,18 +1,50 @@
+use std::iter;
+
 use nom::branch::alt;
+use nom::multi::many0;
+use nom::sequence::tuple;
 use nom::Parser;
-use nom_supreme::ParserExt;
 
+use crate::lexer::PackedToken;
 use crate::lexer::PackedToken::*;
-use crate::nom::parser::macros::{function};
-use crate::nom::parser::utils::{comma_separated0, match_token, paren_enclosed};
+use crate::nom::parser::macros::function;
+use crate::nom::parser::utils::{comma_separated0, match_token, paren_enclosed, RuleExt};
 use crate::nom::parser::{operator, ParseResult};
 use crate::token_stream::PackedTokenStream;
 use kodept_core::structure::rlt;
 use kodept_core::structure::span::Span;
 
+fn string_fragment(
+    kind: PackedToken,
+) -> impl FnMut(PackedTokenStream) -> ParseResult<rlt::Literal> {
+    move |input| {
+        match_token(kind)
+            .map(|it| rlt::Literal::String(Span::new(it.point)))
+            .parse(input)
+    }
+}
+
+fn interpolated_string(input: PackedTokenStream) -> ParseResult<rlt::Literal> {
+    tuple((
+        string_fragment(StringStart),
+        operator::grammar,
+        many0(tuple((string_fragment(StringMiddle), operator::grammar))),
+        string_fragment(StringEnd),
+    ))
+    .rule(function!())
+    .map(|(start, first, rest, end)| {
+        let (middles, others): (Vec<_>, Vec<_>) = rest.into_iter().unzip();
+        rlt::Literal::Interpolated(rlt::InterpolatedString {
+            fragments: iter::once(start).chain(middles).chain([end]).collect(),
+            expressions: iter::once(first).chain(others).collect(),
+        })
+    })
+    .parse(input)
+}
+
 fn tuple_literal(input: PackedTokenStream) -> ParseResult<rlt::Literal> {
     paren_enclosed(comma_separated0(operator::grammar))
-        .context(function!())
+        .rule(function!())
         .map(|it| rlt::Literal::Tuple(it.into()))
         .parse(input)
 }
@@ -25,8 +57,9 @@ pub(super) fn grammar(input: PackedTokenStream) -> ParseResult<rlt::Literal> {
         match_token(Floating).map(|it| rlt::Literal::Floating(Span::new(it.point))),
         match_token(Char).map(|it| rlt::Literal::Char(Span::new(it.point))),
         match_token(String).map(|it| rlt::Literal::String(Span::new(it.point))),
+        interpolated_string,
         tuple_literal,
     ))
-    .context(function!())
+    .rule(function!())
     .parse(input)
 }
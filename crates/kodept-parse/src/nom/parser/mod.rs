@@ -1,10 +1,12 @@
+use std::cell::RefCell;
+
+use ::nom::IResult;
 use derive_more::Constructor;
 use kodept_core::structure::rlt::RLT;
-use ::nom::IResult;
 use nom_supreme::error::GenericErrorTree;
 use nom_supreme::final_parser::final_parser;
 
-use crate::common::RLTProducer;
+use crate::common::{RLTProducer, RecoveringRLTProducer};
 use crate::nom::TokenVerificationError;
 use crate::token_stream::PackedTokenStream;
 
@@ -20,6 +22,7 @@ mod function;
 mod literal;
 mod operator;
 mod parameter;
+mod recovery;
 mod term;
 mod top_level;
 mod r#type;
@@ -32,8 +35,22 @@ impl RLTProducer for Parser {
     type Error<'t> = ParseError<'t>;
 
     fn parse_stream<'t>(&self, input: &PackedTokenStream<'t>) -> Result<RLT, Self::Error<'t>> {
-        let file = final_parser(file::grammar)(*input)?;
-        Ok(RLT(file))
+        let (rlt, errors) = self.parse_recovering(input)?;
+        match errors.into_iter().next() {
+            None => Ok(rlt),
+            Some(e) => Err(e),
+        }
+    }
+}
+
+impl RecoveringRLTProducer for Parser {
+    fn parse_recovering<'t>(
+        &self,
+        input: &PackedTokenStream<'t>,
+    ) -> Result<(RLT, Vec<Self::Error<'t>>), Self::Error<'t>> {
+        let errors = RefCell::default();
+        let file = final_parser(file::grammar(&errors))(*input)?;
+        Ok((RLT(file), errors.into_inner()))
     }
 }
 
@@ -47,5 +64,5 @@ mod macros {
         }};
     }
 
-    pub(crate) use {function};
+    pub(crate) use function;
 }
@@ -5,15 +5,19 @@ use nom::Parser;
 use nom_supreme::ParserExt;
 use nonempty_collections::NEVec;
 
+use crate::common::VerboseEnclosed;
 use crate::lexer::PackedToken::*;
 use crate::nom::parser::macros::function;
-use crate::nom::parser::utils::{comma_separated0, match_token, paren_enclosed};
-use crate::nom::parser::{expression, ParseResult};
+use crate::nom::parser::utils::{
+    comma_separated0, comma_separated1, match_any_token, match_token, RuleExt,
+};
+use crate::nom::parser::{block_level, expression, r#type, ParseResult};
 use crate::token_match::PackedTokenMatch;
 use crate::token_stream::PackedTokenStream;
 use kodept_core::structure::rlt;
+use kodept_core::structure::rlt::new_types;
 use kodept_core::structure::rlt::new_types::{
-    BinaryOperationSymbol, Enclosed, Symbol, UnaryOperationSymbol,
+    BinaryOperationSymbol, Enclosed, Keyword, Symbol, UnaryOperationSymbol,
 };
 
 fn left_fold<I, T, P, E, F, R>(parser: P, produce: F) -> impl Parser<I, R, E>
@@ -46,46 +50,120 @@ where
     })
 }
 
+fn field_init(input: PackedTokenStream) -> ParseResult<rlt::FieldInit> {
+    tuple((match_token(Identifier), match_token(Equals), grammar.cut()))
+        .rule(function!())
+        .map(|(id, equals, value)| rlt::FieldInit {
+            id: new_types::Identifier::from_located(id),
+            equals: Symbol::from_located(equals),
+            value,
+        })
+        .parse(input)
+}
+
+/// At least one field is required, so `if True {}` is not taken for a construction
+fn record_fields(input: PackedTokenStream) -> ParseResult<Enclosed<Box<[rlt::FieldInit]>>> {
+    tuple((
+        match_token(LBrace),
+        comma_separated1(field_init),
+        match_token(RBrace).cut(),
+    ))
+    .rule(function!())
+    .map(|it| VerboseEnclosed::from(it).into())
+    .parse(input)
+}
+
+fn record(input: PackedTokenStream) -> ParseResult<rlt::Record> {
+    tuple((r#type::reference, record_fields))
+        .rule(function!())
+        .map(|(id, fields)| rlt::Record { id, fields })
+        .parse(input)
+}
+
 fn atom(input: PackedTokenStream) -> ParseResult<rlt::Operation> {
     alt((
+        record.map(|it| rlt::Operation::Record(Box::new(it))),
         delimited(match_token(LParen), grammar, match_token(RParen)),
         expression::grammar.map(rlt::Operation::Expression),
+        block_level::block.map(rlt::Operation::Block),
+    ))
+    .rule(function!())
+    .parse(input)
+}
+
+/// Opening `(` should immediately follow the applied expression, so it starts a new tuple otherwise
+fn parameters(input: PackedTokenStream) -> ParseResult<Enclosed<Box<[rlt::Operation]>>> {
+    tuple((
+        match_any_token(LParen),
+        comma_separated0(grammar).cut(),
+        match_token(RParen).cut(),
     ))
-    .context(function!())
+    .rule(function!())
+    .map(|it| VerboseEnclosed::from(it).into())
     .parse(input)
 }
 
+fn application(input: PackedTokenStream) -> ParseResult<rlt::Operation> {
+    tuple((atom, parameters.opt()))
+        .rule(function!())
+        .map(|(expr, params)| match params {
+            None => expr,
+            Some(_) => rlt::Operation::Application(Box::new(rlt::Application { expr, params })),
+        })
+        .parse(input)
+}
+
+/// Right side of an access may be prefixed with an unary operator, as in `a.-b`
+fn accessed(input: PackedTokenStream) -> ParseResult<rlt::Operation> {
+    unary.or(application).parse(input)
+}
+
 fn access(input: PackedTokenStream) -> ParseResult<rlt::Operation> {
     left_fold(
-        tuple((atom, many0(tuple((match_token(Dot), atom))))),
+        tuple((application, many0(tuple((match_token(Dot), accessed))))),
         |a, op, b| rlt::Operation::Access {
             left: Box::new(a),
             dot: op,
             right: Box::new(b),
         },
     )
-    .context(function!())
+    .rule(function!())
     .parse(input)
 }
 
-fn parameters(input: PackedTokenStream) -> ParseResult<Enclosed<Box<[rlt::Operation]>>> {
-    paren_enclosed(comma_separated0(grammar))
-        .context(function!())
-        .map(|it| it.into())
-        .parse(input)
-}
-
-fn application(input: PackedTokenStream) -> ParseResult<rlt::Operation> {
-    tuple((access, parameters.opt()))
-        .context(function!())
-        .map(|(expr, params)| match params {
-            None => expr,
-            Some(_) => rlt::Operation::Application(Box::new(rlt::Application { expr, params })),
-        })
-        .parse(input)
+/// Accesses may follow an update, as in `(a with { b = c }).d`
+fn update(input: PackedTokenStream) -> ParseResult<rlt::Operation> {
+    tuple((
+        access,
+        many0(tuple((
+            match_token(With),
+            record_fields.cut(),
+            many0(tuple((match_token(Dot), accessed))),
+        ))),
+    ))
+    .rule(function!())
+    .map(|(record, updates)| {
+        updates
+            .into_iter()
+            .fold(record, |acc, (keyword, fields, accesses)| {
+                let update = rlt::Operation::Update {
+                    record: Box::new(acc),
+                    keyword: Keyword::from_located(keyword),
+                    fields,
+                };
+                accesses
+                    .into_iter()
+                    .fold(update, |left, (dot, right)| rlt::Operation::Access {
+                        left: Box::new(left),
+                        dot: Symbol::from_located(dot),
+                        right: Box::new(right),
+                    })
+            })
+    })
+    .parse(input)
 }
 
-fn top_expr(input: PackedTokenStream) -> ParseResult<rlt::Operation> {
+fn unary(input: PackedTokenStream) -> ParseResult<rlt::Operation> {
     alt((
         match_token(Sub).map(|it| UnaryOperationSymbol::Neg(Symbol::from_located(it))),
         match_token(NotLogic).map(|it| UnaryOperationSymbol::Not(Symbol::from_located(it))),
@@ -97,11 +175,13 @@ fn top_expr(input: PackedTokenStream) -> ParseResult<rlt::Operation> {
         operator: it.0,
         expr: Box::new(it.1),
     })
-    .or(application)
-    .context(function!())
     .parse(input)
 }
 
+fn top_expr(input: PackedTokenStream) -> ParseResult<rlt::Operation> {
+    unary.or(update).rule(function!()).parse(input)
+}
+
 fn pow_expr(input: PackedTokenStream) -> ParseResult<rlt::Operation> {
     right_fold(
         top_expr.and(match_token(Pow).and(pow_expr).opt()),
@@ -111,7 +191,7 @@ fn pow_expr(input: PackedTokenStream) -> ParseResult<rlt::Operation> {
             right: Box::new(b),
         },
     )
-    .context(function!())
+    .rule(function!())
     .parse(input)
 }
 
@@ -126,7 +206,7 @@ fn mul_expr(input: PackedTokenStream) -> ParseResult<rlt::Operation> {
             right: Box::new(b),
         },
     )
-    .context(function!())
+    .rule(function!())
     .parse(input)
 }
 
@@ -141,7 +221,7 @@ fn add_expr(input: PackedTokenStream) -> ParseResult<rlt::Operation> {
             right: Box::new(b),
         },
     )
-    .context(function!())
+    .rule(function!())
     .parse(input)
 }
 
@@ -154,7 +234,7 @@ fn complex_cmp(input: PackedTokenStream) -> ParseResult<rlt::Operation> {
             right: Box::new(b),
         },
     )
-    .context(function!())
+    .rule(function!())
     .parse(input)
 }
 
@@ -175,7 +255,7 @@ fn compound_cmp(input: PackedTokenStream) -> ParseResult<rlt::Operation> {
             right: Box::new(b),
         },
     )
-    .context(function!())
+    .rule(function!())
     .parse(input)
 }
 
@@ -190,7 +270,7 @@ fn simple_cmp(input: PackedTokenStream) -> ParseResult<rlt::Operation> {
             right: Box::new(b),
         },
     )
-    .context(function!())
+    .rule(function!())
     .parse(input)
 }
 
@@ -205,7 +285,7 @@ fn bit_expr(input: PackedTokenStream) -> ParseResult<rlt::Operation> {
             right: Box::new(b),
         },
     )
-    .context(function!())
+    .rule(function!())
     .parse(input)
 }
 
@@ -220,7 +300,7 @@ fn logic_expr(input: PackedTokenStream) -> ParseResult<rlt::Operation> {
             right: Box::new(b),
         },
     )
-    .context(function!())
+    .rule(function!())
     .parse(input)
 }
 
@@ -233,7 +313,7 @@ fn assign_expr(input: PackedTokenStream) -> ParseResult<rlt::Operation> {
             right: Box::new(b),
         },
     )
-    .context(function!())
+    .rule(function!())
     .parse(input)
 }
 
@@ -0,0 +1,134 @@
+use std::cell::RefCell;
+
+use kodept_core::structure::rlt::new_types::Erroneous;
+use kodept_core::structure::rlt::TopLevelNode;
+use nom::Err::{Error, Failure};
+use nom::{InputTake, Parser};
+use nom_supreme::error::BaseErrorKind;
+
+use crate::lexer::traits::ToRepresentation;
+use crate::lexer::PackedToken::{
+    Comment, LBrace, MultilineComment, Newline, RBrace, Semicolon, Whitespace,
+};
+use crate::nom::parser::{ParseError, ParseResult};
+use crate::nom::TokenVerificationError;
+use crate::token_match::PackedTokenMatch;
+use crate::token_stream::PackedTokenStream;
+
+/// Returns position of `}` matching `{` at the start of `tokens`
+fn matching_brace(tokens: &[PackedTokenMatch]) -> Option<usize> {
+    let mut depth = 0usize;
+    for (index, it) in tokens.iter().enumerate() {
+        match it.token {
+            LBrace => depth += 1,
+            RBrace if depth == 1 => return Some(index),
+            RBrace => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Sync point described in [`crate::common::RecoveringRLTProducer`]
+fn is_sync_point(tokens: &[PackedTokenMatch]) -> bool {
+    match tokens {
+        [] => true,
+        [first, ..] if first.token == RBrace => true,
+        [first, rest @ ..] if matches!(first.token, Newline | Comment) => rest
+            .iter()
+            .find(|it| !it.token.is_ignored())
+            .is_some_and(|it| it.token.starts_declaration() || it.token == RBrace),
+        _ => false,
+    }
+}
+
+fn is_declaration_end(tokens: &[PackedTokenMatch]) -> bool {
+    tokens
+        .iter()
+        .find(|it| !matches!(it.token, Whitespace | MultilineComment))
+        .map_or(true, |it| {
+            matches!(it.token, Newline | Comment | Semicolon | RBrace)
+        })
+}
+
+/// Returns the number of tokens to skip to get to the next declaration
+fn recovery_length(tokens: &[PackedTokenMatch]) -> usize {
+    let mut pos = 0;
+    while !is_sync_point(&tokens[pos..]) {
+        pos += match tokens[pos].token {
+            LBrace => matching_brace(&tokens[pos..]).map_or(1, |it| it + 1),
+            _ => 1,
+        };
+    }
+    pos
+}
+
+/// Parses a declaration, or skips tokens up to the next one saving the error to `errors`
+pub(super) fn recovering<'t, 'e, P>(
+    mut parser: P,
+    errors: &'e RefCell<Vec<ParseError<'t>>>,
+) -> impl FnMut(PackedTokenStream<'t>) -> ParseResult<'t, TopLevelNode> + 'e
+where
+    P: Parser<PackedTokenStream<'t>, TopLevelNode, ParseError<'t>> + 'e,
+{
+    move |input| {
+        let error = match parser.parse(input) {
+            Ok((rest, output)) if is_declaration_end(&rest) => return Ok((rest, output)),
+            Ok((rest, _)) => {
+                let start = rest.iter().position(|it| !it.token.is_ignored());
+                ParseError::Base {
+                    location: rest.take_split(start.unwrap_or(rest.len())).0,
+                    kind: BaseErrorKind::External(TokenVerificationError::new(
+                        Newline.representation(),
+                    )),
+                }
+            }
+            Err(Error(e) | Failure(e)) => e,
+            Err(e) => return Err(e),
+        };
+
+        let start = input
+            .iter()
+            .position(|it| !it.token.is_ignored())
+            .unwrap_or(input.len());
+        let length = recovery_length(&input[start..]);
+        if length == 0 {
+            return Err(Error(error));
+        }
+        errors.borrow_mut().push(error);
+        let skipped = input.sub_stream(start..start + length);
+        let (rest, _) = input.take_split(start + length);
+        Ok((rest, TopLevelNode::Error(Erroneous::from_located(skipped))))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use kodept_core::code_point::CodePoint;
+    use rstest::rstest;
+
+    use super::recovery_length;
+    use crate::lexer::PackedToken;
+    use crate::lexer::PackedToken::*;
+    use crate::token_match::PackedTokenMatch;
+
+    fn tokens(kinds: &[PackedToken]) -> Vec<PackedTokenMatch> {
+        kinds
+            .iter()
+            .enumerate()
+            .map(|(index, it)| PackedTokenMatch::new(*it, CodePoint::new(1, index as u32)))
+            .collect()
+    }
+
+    #[rstest]
+    #[case::next_declaration(&[Fun, Identifier, Newline, Fun, Identifier], 2)]
+    #[case::module_end(&[Fun, Identifier, Newline, RBrace], 2)]
+    #[case::closing_brace(&[Fun, Identifier, RBrace], 2)]
+    #[case::eof(&[Fun, Identifier, Newline, Identifier], 4)]
+    #[case::balanced_braces(&[Struct, LBrace, Newline, Fun, RBrace, Newline, Fun], 5)]
+    #[case::unbalanced_brace(&[Struct, LBrace, Newline, Fun, Identifier], 2)]
+    fn test_recovery_length(#[case] input: &[PackedToken], #[case] expected: usize) {
+        assert_eq!(recovery_length(&tokens(input)), expected);
+    }
+}
@@ -12,9 +12,10 @@ use nom::Parser;
 use nom_supreme::ParserExt;
 
 use crate::lexer::PackedToken::*;
-use crate::nom::parser::macros::{function};
-use crate::nom::parser::utils::match_token;
+use crate::nom::parser::macros::function;
+use crate::nom::parser::utils::{adjacent, match_any_token, match_token, RuleExt};
 use crate::nom::parser::ParseResult;
+use crate::token_match::PackedTokenMatch;
 use crate::token_stream::PackedTokenStream;
 use kodept_core::structure::rlt;
 use kodept_core::structure::rlt::new_types::Symbol;
@@ -23,8 +24,8 @@ use kodept_core::structure::rlt::{new_types, Context, ContextualReference};
 fn global_type_ref(input: PackedTokenStream) -> ParseResult<(Context, rlt::Reference)> {
     tuple((
         match_token(DoubleColon),
-        many0(type_ref.terminated(match_token(DoubleColon))),
-        type_ref,
+        many0(adjacent(type_ref).terminated(double_colon)),
+        adjacent(type_ref),
     ))
     .map(|(global, context, ty)| {
         let start = Context::Global {
@@ -36,15 +37,15 @@ fn global_type_ref(input: PackedTokenStream) -> ParseResult<(Context, rlt::Refer
         });
         (context, ty)
     })
-    .context(function!())
+    .rule(function!())
     .parse(input)
 }
 
 fn global_ref(input: PackedTokenStream) -> ParseResult<(Context, rlt::Reference)> {
     tuple((
         match_token(DoubleColon),
-        many0(type_ref.terminated(match_token(DoubleColon))),
-        variable_ref,
+        many0(adjacent(type_ref).terminated(double_colon)),
+        adjacent(variable_ref),
     ))
     .map(|(global, context, r)| {
         let start = Context::Global {
@@ -56,12 +57,12 @@ fn global_ref(input: PackedTokenStream) -> ParseResult<(Context, rlt::Reference)
         });
         (context, r)
     })
-    .context(function!())
+    .rule(function!())
     .parse(input)
 }
 
 fn local_type_ref(input: PackedTokenStream) -> ParseResult<(Context, rlt::Reference)> {
-    tuple((type_ref, many1(match_token(DoubleColon).precedes(type_ref))))
+    tuple((type_ref, many1(double_colon.precedes(adjacent(type_ref)))))
         .map(|it| (it.0, VecDeque::from(it.1)))
         .map(|(first, mut rest)| {
             let start = Context::Local;
@@ -75,38 +76,46 @@ fn local_type_ref(input: PackedTokenStream) -> ParseResult<(Context, rlt::Refere
             });
             (context, last)
         })
-        .context(function!())
+        .rule(function!())
         .parse(input)
 }
 
 fn local_ref(input: PackedTokenStream) -> ParseResult<(Context, rlt::Reference)> {
     tuple((
-        many1(type_ref.terminated(match_token(DoubleColon))),
-        variable_ref,
+        type_ref.terminated(double_colon),
+        many0(adjacent(type_ref).terminated(double_colon)),
+        adjacent(variable_ref),
     ))
-    .map(|(rest, last)| {
+    .map(|(first, rest, last)| {
         let start = Context::Local;
-        let context = rest.into_iter().fold(start, |acc, next| Context::Inner {
-            parent: Box::new(acc),
-            needle: next,
-        });
+        let context = std::iter::once(first)
+            .chain(rest)
+            .fold(start, |acc, next| Context::Inner {
+                parent: Box::new(acc),
+                needle: next,
+            });
         (context, last)
     })
-    .context(function!())
+    .rule(function!())
     .parse(input)
 }
 
+/// Parts of a contextual reference are written without spaces, so `A\n::b` is not taken for `A::b`
+fn double_colon(input: PackedTokenStream) -> ParseResult<PackedTokenMatch> {
+    match_any_token(DoubleColon)(input)
+}
+
 fn variable_ref(input: PackedTokenStream) -> ParseResult<rlt::Reference> {
     match_token(Identifier)
         .map(|it| rlt::Reference::Identifier(new_types::Identifier::from_located(it)))
-        .context(function!())
+        .rule(function!())
         .parse(input)
 }
 
 fn type_ref(input: PackedTokenStream) -> ParseResult<rlt::Reference> {
     match_token(Type)
-        .map(|it| rlt::Reference::Identifier(new_types::Identifier::from_located(it)))
-        .context(function!())
+        .map(|it| rlt::Reference::Type(new_types::TypeName::from_located(it)))
+        .rule(function!())
         .parse(input)
 }
 
@@ -116,12 +125,12 @@ fn contextual(input: PackedTokenStream) -> ParseResult<ContextualReference> {
             context: it.0,
             inner: it.1,
         })
-        .context(function!())
+        .rule(function!())
         .parse(input)
 }
 
 fn reference(input: PackedTokenStream) -> ParseResult<rlt::Reference> {
-    variable_ref.or(type_ref).context(function!()).parse(input)
+    variable_ref.or(type_ref).rule(function!()).parse(input)
 }
 
 pub(super) fn grammar(input: PackedTokenStream) -> ParseResult<rlt::Term> {
@@ -129,6 +138,6 @@ pub(super) fn grammar(input: PackedTokenStream) -> ParseResult<rlt::Term> {
         contextual.map(rlt::Term::Contextual),
         reference.map(rlt::Term::Reference),
     ))
-    .context(function!())
+    .rule(function!())
     .parse(input)
 }
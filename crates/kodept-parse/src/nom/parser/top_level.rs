@@ -4,37 +4,79 @@ use nom::sequence::tuple;
 use nom::Parser;
 use nom_supreme::ParserExt;
 
+use kodept_core::code_point::CodePoint;
 use kodept_core::structure::rlt;
-use kodept_core::structure::rlt::new_types::Keyword;
+use kodept_core::structure::rlt::new_types::{Keyword, Symbol};
 use kodept_core::structure::rlt::TopLevelNode;
 
+use crate::common::VerboseEnclosed;
 use crate::lexer::PackedToken::*;
 use crate::nom::parser::macros::function;
 use crate::nom::parser::parameter::typed_parameter;
 use crate::nom::parser::utils::{
     brace_enclosed, comma_separated0, comma_separated1, match_token, newline_separated,
-    paren_enclosed,
+    paren_enclosed, RuleExt,
 };
 use crate::nom::parser::{function, r#type, ParseResult};
 use crate::token_stream::PackedTokenStream;
 
+fn variant_payload(input: PackedTokenStream) -> ParseResult<rlt::VariantPayload> {
+    alt((
+        // Positional payloads start the same way, so the list itself is not cut
+        tuple((
+            match_token(LParen),
+            comma_separated1(typed_parameter),
+            match_token(RParen).cut(),
+        ))
+        .map(|it| rlt::VariantPayload::Named(VerboseEnclosed::from(it).into())),
+        paren_enclosed(comma_separated0(r#type::grammar))
+            .map(|it| rlt::VariantPayload::Positional(it.into())),
+    ))
+    .rule(function!())
+    .parse(input)
+}
+
+fn enum_variant(input: PackedTokenStream) -> ParseResult<rlt::EnumVariant> {
+    tuple((r#type::reference, variant_payload.opt()))
+        .rule(function!())
+        .map(|(id, payload)| rlt::EnumVariant { id, payload })
+        .parse(input)
+}
+
 fn enum_statement(input: PackedTokenStream) -> ParseResult<rlt::Enum> {
     tuple((
-        tuple((
-            match_token(Enum),
-            match_token(Struct).or(match_token(Class)).cut(),
-        )).recognize(),
+        match_token(Enum),
+        alt((match_token(Struct), match_token(Class))).cut(),
         r#type::reference,
+        r#type::generics.opt(),
         cut(alt((
             match_token(Semicolon).value(None),
-            brace_enclosed(comma_separated1(r#type::reference)).map(Some),
+            brace_enclosed(comma_separated0(enum_variant)).map(Some),
         ))),
     ))
-    .context(function!())
-    .map(|it| rlt::Enum::Stack {
-        keyword: Keyword::from_located(it.0),
-        id: it.1,
-        contents: it.2.map(|it| it.into()),
+    .rule(function!())
+    .map(|(first, second, id, generics, contents)| {
+        // Keyword spans both words, e.g. `enum class`
+        let end = second.point.offset + second.point.length;
+        let keyword =
+            Keyword::from_located(CodePoint::new(end - first.point.offset, first.point.offset));
+        let generics = generics.map(|it| it.into());
+        let contents = contents.map(|it| it.into());
+        if second.token == Class {
+            rlt::Enum::Heap {
+                keyword,
+                id,
+                generics,
+                contents,
+            }
+        } else {
+            rlt::Enum::Stack {
+                keyword,
+                id,
+                generics,
+                contents,
+            }
+        }
     })
     .parse(input)
 }
@@ -43,15 +85,34 @@ fn struct_statement(input: PackedTokenStream) -> ParseResult<rlt::Struct> {
     tuple((
         match_token(Struct),
         r#type::reference.cut(),
+        r#type::generics.opt(),
         paren_enclosed(comma_separated0(typed_parameter)).opt(),
         brace_enclosed(newline_separated(function::bodied)).opt(),
     ))
-    .context(function!())
+    .rule(function!())
     .map(|it| rlt::Struct {
         keyword: Keyword::from_located(it.0),
         id: it.1,
-        parameters: it.2.map(|it| it.into()),
-        body: it.3.map(|it| it.into()),
+        generics: it.2.map(|it| it.into()),
+        parameters: it.3.map(|it| it.into()),
+        body: it.4.map(|it| it.into()),
+    })
+    .parse(input)
+}
+
+fn type_alias_statement(input: PackedTokenStream) -> ParseResult<rlt::TypeAlias> {
+    tuple((
+        match_token(TypeAlias),
+        r#type::reference.cut(),
+        match_token(Equals).cut(),
+        r#type::grammar.cut(),
+    ))
+    .rule(function!())
+    .map(|it| rlt::TypeAlias {
+        keyword: Keyword::from_located(it.0),
+        id: it.1,
+        equals: Symbol::from_located(it.2),
+        aliased: it.3,
     })
     .parse(input)
 }
@@ -60,8 +121,10 @@ pub(super) fn grammar(input: PackedTokenStream) -> ParseResult<TopLevelNode> {
     alt((
         enum_statement.map(TopLevelNode::Enum),
         struct_statement.map(TopLevelNode::Struct),
+        type_alias_statement.map(TopLevelNode::TypeAlias),
+        function::foreign.map(TopLevelNode::ForeignFunction),
         function::bodied.map(TopLevelNode::BodiedFunction),
     ))
-    .context(function!())
+    .rule(function!())
     .parse(input)
 }
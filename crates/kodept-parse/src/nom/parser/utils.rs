@@ -2,7 +2,8 @@ use crate::common::VerboseEnclosed;
 use crate::lexer::traits::ToRepresentation;
 use crate::lexer::PackedToken;
 use crate::lexer::PackedToken::{
-    Comma, LBrace, LParen, Newline, RBrace, RParen, Semicolon, Whitespace,
+    Comma, Comment, Greater, LBrace, LParen, Less, MultilineComment, Newline, RBrace, RParen,
+    Semicolon, Whitespace,
 };
 use crate::nom::parser::{ParseError, ParseResult};
 use crate::nom::TokenVerificationError;
@@ -10,7 +11,7 @@ use crate::token_match::PackedTokenMatch;
 use crate::token_stream::PackedTokenStream;
 use nom::branch::alt;
 use nom::bytes::complete::{take, take_while};
-use nom::multi::{many1, separated_list0, separated_list1};
+use nom::multi::{many0, many1, separated_list0, separated_list1};
 use nom::sequence::tuple;
 use nom::Err::Error;
 use nom::IResult;
@@ -38,7 +39,7 @@ pub(super) fn match_token(
     move |input| {
         let (rest, output) = any_not_ignored_token(input)?;
         let token_match = output.into_single();
-        
+
         if token_match.token == example {
             Ok((rest, token_match))
         } else {
@@ -59,7 +60,7 @@ pub(super) fn match_any_token(
     move |input| {
         let (rest, output) = any_token(input)?;
         let token_match = output.into_single();
-        
+
         if token_match.token == expected {
             Ok((rest, token_match))
         } else {
@@ -73,6 +74,50 @@ pub(super) fn match_any_token(
     }
 }
 
+/// Runs `parser` only if nothing separates it from the previous token
+#[inline]
+pub(super) fn adjacent<'t, O, P>(
+    mut parser: P,
+) -> impl FnMut(PackedTokenStream<'t>) -> ParseResult<'t, O>
+where
+    P: Parser<PackedTokenStream<'t>, O, ParseError<'t>>,
+{
+    move |input| {
+        let (_, output) = any_token(input)?;
+        let token_match = output.into_single();
+
+        if token_match.token.is_ignored() {
+            let error = ParseError::Base {
+                location: output,
+                kind: BaseErrorKind::External(TokenVerificationError::new("<adjacent token>")),
+            };
+            Err(Error(error))
+        } else {
+            parser.parse(input)
+        }
+    }
+}
+
+/// Marks parser as grammar rule `name`: it becomes context of errors
+/// and, when `trace` feature is enabled, is recorded in traces
+pub(super) trait RuleExt<'t, O>:
+    Parser<PackedTokenStream<'t>, O, ParseError<'t>> + Sized
+{
+    fn rule(self, name: &'static str) -> impl Parser<PackedTokenStream<'t>, O, ParseError<'t>> {
+        let mut parser = self.context(name);
+        move |input: PackedTokenStream<'t>| {
+            #[cfg(feature = "trace")]
+            crate::trace::nom_enter(name, &input);
+            let result = parser.parse(input);
+            #[cfg(feature = "trace")]
+            crate::trace::nom_exit(name, &input, result.as_ref().ok().map(|it| &it.0));
+            result
+        }
+    }
+}
+
+impl<'t, O, P> RuleExt<'t, O> for P where P: Parser<PackedTokenStream<'t>, O, ParseError<'t>> {}
+
 #[inline]
 #[allow(unused)]
 pub(super) fn inspect_input<I: Debug, O, E, P>(mut parser: P) -> impl FnMut(I) -> IResult<I, O, E>
@@ -127,19 +172,38 @@ where
     .map(|it| it.into())
 }
 
+/// Opening `<` should immediately follow the previous token, so it is not confused with comparison
+#[inline]
+pub(super) fn angle_enclosed<'t, T, P>(
+    items_parser: P,
+) -> impl Parser<PackedTokenStream<'t>, VerboseEnclosed<T>, ParseError<'t>>
+where
+    P: Parser<PackedTokenStream<'t>, T, ParseError<'t>>,
+{
+    tuple((
+        match_any_token(Less),
+        items_parser.cut(),
+        match_token(Greater).cut(),
+    ))
+    .map(|it| it.into())
+}
+
 #[allow(unused_parens)]
 #[inline]
 pub(super) fn newline_separated<'t, T, P: Parser<PackedTokenStream<'t>, T, ParseError<'t>>>(
     items_parser: P,
 ) -> impl Parser<PackedTokenStream<'t>, Vec<T>, ParseError<'t>> {
-    separated_list0(
-        many1(alt((
-            match_any_token(Newline),
+    let separator = tuple((
+        many0(alt((
             match_any_token(Whitespace),
-            match_token(Semicolon),
+            match_any_token(MultilineComment),
         ))),
-        items_parser,
-    )
+        alt((
+            many1(alt((match_any_token(Newline), match_any_token(Comment)))).value(()),
+            match_any_token(Semicolon).value(()),
+        )),
+    ));
+    separated_list0(separator, items_parser)
 }
 
 #[inline]
@@ -1,11 +1,15 @@
 use crate::common::{ErrorAdapter, RLTProducer};
 use crate::error::{Original, ParseErrors};
-use crate::token_stream::{PackedTokenStream};
+use crate::token_stream::PackedTokenStream;
 
 pub type NomParser = crate::nom::Parser;
 pub type PegParser<const TRACE: bool> = crate::peg::Parser<TRACE>;
+pub type PestParser = crate::pest::Parser;
 
-pub fn parse_from_top<'t, A, E, P, O>(input: PackedTokenStream<'t>, parser: P) -> Result<O, ParseErrors<A>>
+pub fn parse_from_top<'t, A, E, P, O>(
+    input: PackedTokenStream<'t>,
+    parser: P,
+) -> Result<O, ParseErrors<A>>
 where
     P: RLTProducer<O, Error<'t> = E> + 't,
     E: ErrorAdapter<A, PackedTokenStream<'t>>,
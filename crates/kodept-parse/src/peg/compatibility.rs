@@ -21,6 +21,15 @@ impl From<Position> for CodePoint {
     }
 }
 
+impl From<Position> for crate::trace::LineCol {
+    fn from(value: Position) -> Self {
+        Self {
+            line: value.line,
+            column: value.col as usize,
+        }
+    }
+}
+
 impl From<LineCol> for Position {
     fn from(value: LineCol) -> Self {
         Position {
@@ -37,7 +46,8 @@ impl<'t> Parse for PackedTokenStream<'t> {
 
     #[inline(always)]
     fn start(&self) -> usize {
-        self.first().map_or(0, |it| it.point.offset) as usize
+        // Positions are indices of tokens, not offsets in code
+        0
     }
 
     #[inline(always)]
@@ -50,7 +60,7 @@ impl<'t> Parse for PackedTokenStream<'t> {
         let (before, point) = match self.split_at(pos) {
             (a, [b, ..]) => (a, b.point),
             (a @ [.., last], []) => (a, last.point),
-            ([], []) => panic!("Cannot slice empty stream"),
+            ([], []) => (&[][..], CodePoint::new(0, 0)),
         };
         let line = before
             .iter()
@@ -1,6 +1,7 @@
 use crate::common::{EagerTokensProducer, TokenProducer};
 use crate::lexer::PackedToken;
 use crate::token_match::PackedTokenMatch;
+use crate::trace::traced_if;
 use derive_more::Constructor;
 use kodept_core::code_point::CodePoint;
 use peg::error::ParseError;
@@ -23,6 +24,10 @@ peg::parser! {grammar grammar() for str {
     ) { i }}
 
     rule keyword() -> PackedToken =
+        "\\" { PackedToken::Lambda } /
+        k:word_keyword() ![c if c == '_' || c.is_alphanumeric()] { k }
+
+    rule word_keyword() -> PackedToken =
         "fun"       { PackedToken::Fun }       /
         "val"       { PackedToken::Val }       /
         "var"       { PackedToken::Var }       /
@@ -31,7 +36,6 @@ peg::parser! {grammar grammar() for str {
         "module"    { PackedToken::Module }    /
         "extend"    { PackedToken::Extend }    /
         "return"    { PackedToken::Return }    /
-        "\\"        { PackedToken::Lambda }    /
         "if"        { PackedToken::If }        /
         "elif"      { PackedToken::Elif }      /
         "else"      { PackedToken::Else }      /
@@ -58,13 +62,13 @@ peg::parser! {grammar grammar() for str {
         ":"  { PackedToken::Colon }
 
     rule type_() -> PackedToken = (
-        "_"*
+        "_"?
         (quiet!{[cl if cl.is_uppercase()]} / expected!("uppercase letter"))
         ("_" / (quiet!{[c if c.is_alphanumeric()]} / expected!("letter")))*
     ) { PackedToken::Type }
 
     rule reference() -> PackedToken = (
-        "_"*
+        "_"?
         (quiet!{[cl if cl.is_lowercase()]} / expected!("lowercase letter"))
         ("_" / (quiet!{[c if c.is_alphanumeric()]} / expected!("letter")))*
     ) { PackedToken::Identifier }
@@ -73,7 +77,7 @@ peg::parser! {grammar grammar() for str {
 
     rule number<T>(prefix_lower: char, prefix_upper: char, digits: rule<T>) -> () = (
         "0" [c if c == prefix_lower || c == prefix_upper] (
-            ([^'0' | '_'] (digits() {  } / "_")+) /
+            (!['0' | '_'] (digits() {  } / "_")+) /
             digits() {  }
         )
     )
@@ -91,13 +95,26 @@ peg::parser! {grammar grammar() for str {
     rule e_notation() =
         ['e' | 'E'] sign()? ['0'..='9']+
 
+    rule escape() =
+        "\\u{" [^'}' | '\'']* "}" / "\\" [_]
+
+    rule string_lit() =
+        "\"" ("\\" [_] / interpolation() / !"\"" [_])* "\""
+
+    /// Embedded expression is split into separate tokens by the tokenizer
+    rule interpolation() =
+        "${" braced() "}"
+
+    rule braced() =
+        (string_lit() / "{" braced() "}" / [^'{' | '}' | '"'])*
+
     rule literal() -> PackedToken =
         bin_lit()                                                                    /
         oct_lit()                                                                    /
         hex_lit()                                                                    /
         sign()? whitespace()* floating_lit() e_notation()? { PackedToken::Floating } /
-        "'" i:$(!"'" [_]) "'"                              { PackedToken::Char }     /
-        "\"" i:$((!"\"" [_])*) "\""                        { PackedToken::String }
+        "'" (escape() / !"'" [_]) "'"                      { PackedToken::Char }     /
+        string_lit()                                       { PackedToken::String }
 
     rule operator() -> PackedToken =
         "."   { PackedToken::Dot }           /
@@ -127,8 +144,8 @@ peg::parser! {grammar grammar() for str {
     rule token_() -> PackedToken =
         ignore()     /
         keyword()    /
-        symbol()     /
         identifier() /
+        symbol()     /
         operator()   /
         literal()
 
@@ -138,50 +155,14 @@ peg::parser! {grammar grammar() for str {
             PackedTokenMatch::new(t, CodePoint::new(length as u32, start as u32))
         }
 
-    rule traced<T>(e: rule<T>) -> T =
-        &(input:$([_]*) {
-            #[cfg(feature = "trace")]
-            println!("[PEG_INPUT_START]\n{}\n[PEG_TRACE_START]", input);
-        })
-        e:e()? {?
-            #[cfg(feature = "trace")]
-            println!("[PEG_TRACE_STOP]");
-            e.ok_or("")
-        }
-
-    rule tokens_() -> Vec<PackedTokenMatch> = i:token_match()* ![_] { i }
-
-    pub rule tokens() -> Vec<PackedTokenMatch> = traced(<tokens_()>)
+    pub rule tokens() -> Vec<PackedTokenMatch> = i:token_match()* ![_] { i }
     #[no_eof]
-    pub rule token() -> PackedTokenMatch = traced(<token_match()>)
+    pub rule token() -> PackedTokenMatch = token_match()
 }}
 
 #[derive(Constructor, Debug, Copy, Clone)]
 pub struct Lexer<const TRACE: bool>;
 
-#[allow(dead_code)]
-enum GagContainer {
-    Empty,
-    #[cfg(feature = "trace")]
-    Full(gag::Gag),
-}
-
-impl GagContainer {
-    #[must_use]
-    fn enable<const E: bool>() -> Self {
-        #[cfg(feature = "trace")]
-        {
-            return if !E {
-                Self::Full(gag::Gag::stdout().expect("Cannot suppress stdout"))
-            } else {
-                Self::Empty
-            };
-        }
-        #[cfg(not(feature = "trace"))]
-        Self::Empty
-    }
-}
-
 impl<const TRACE: bool> TokenProducer for Lexer<TRACE> {
     type Error<'t> = ParseError<LineCol>;
 
@@ -191,8 +172,7 @@ impl<const TRACE: bool> TokenProducer for Lexer<TRACE> {
         position: usize,
     ) -> Result<PackedTokenMatch, Self::Error<'t>> {
         let input = &whole_input[position..];
-        let _gag = GagContainer::enable::<TRACE>();
-        grammar::token(input)
+        traced_if::<TRACE, _>(|| grammar::token(input))
     }
 }
 
@@ -200,7 +180,6 @@ impl<const TRACE: bool> EagerTokensProducer for Lexer<TRACE> {
     type Error<'t> = ParseError<LineCol>;
 
     fn parse_string<'t>(&self, input: &'t str) -> Result<Vec<PackedTokenMatch>, Self::Error<'t>> {
-        let _gag = GagContainer::enable::<TRACE>();
-        grammar::tokens(input)
+        traced_if::<TRACE, _>(|| grammar::tokens(input))
     }
 }
@@ -1,6 +1,8 @@
-mod lexer;
-mod error;
 mod compatibility;
+mod error;
+#[macro_use]
+mod trace_hook;
+mod lexer;
 mod parser;
 
 pub(crate) use lexer::Lexer;
@@ -8,8 +10,10 @@ pub(crate) use parser::Parser;
 
 mod macros {
     macro_rules! tok {
-        ($pat:pat) => {$crate::token_match::PackedTokenMatch { token: $pat, .. }};
+        ($pat:pat) => {
+            $crate::token_match::PackedTokenMatch { token: $pat, .. }
+        };
     }
 
     pub(crate) use tok;
-}
\ No newline at end of file
+}
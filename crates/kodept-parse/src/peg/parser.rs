@@ -1,37 +1,48 @@
-use crate::common::{RLTProducer, VerboseEnclosed};
+use crate::common::{RLTProducer, RecoveringRLTProducer, VerboseEnclosed};
+use crate::lexer::PackedToken;
 use crate::lexer::PackedToken::*;
 use crate::peg::compatibility::Position;
 use crate::peg::macros::tok;
-use crate::token_stream::PackedTokenStream;
 use crate::token_match::PackedTokenMatch;
+use crate::token_stream::PackedTokenStream;
 use crate::TRACING_OPTION;
 use derive_more::Constructor;
 use kodept_core::structure::rlt::new_types::BinaryOperationSymbol;
 use kodept_core::structure::rlt::new_types::UnaryOperationSymbol;
-use kodept_core::structure::rlt::new_types::{Keyword, Symbol, Identifier};
+use kodept_core::structure::rlt::new_types::{Erroneous, Identifier, Keyword, Symbol};
 use kodept_core::structure::rlt::RLT;
-use kodept_core::structure::*;
 use kodept_core::structure::span::Span;
+use kodept_core::structure::*;
 use peg::error::ParseError;
-use crate::lexer::PackedToken;
+use std::cell::RefCell;
+use std::ops::Range;
 
-peg::parser! {grammar grammar<'t>() for PackedTokenStream<'t> {
+peg::parser! {grammar grammar<'t>(recovery: &Recovery<'t>) for PackedTokenStream<'t> {
     /// UTILITIES
     /// --------------------------------------------------------------------------------------------
     rule _ = quiet! { [tok!(Comment | MultilineComment | Newline | Whitespace)]* }
-    
+
     rule comma_separated0<T>(items: rule<T>) -> Vec<T> =
         i:(items() ** (_ "," _)) _ ","? { i }
 
+    rule comma_separated1<T>(items: rule<T>) -> Vec<T> =
+        i:(items() ++ (_ "," _)) _ ","? { i }
+
     rule paren_enclosed<T>(inner: rule<T>) -> VerboseEnclosed<T> =
         lp:$"(" _ i:inner() _ rp:$")" { VerboseEnclosed::from_located(lp, i, rp) }
 
+    /// Opening `<` should immediately follow the previous token, so it is not confused with comparison
+    rule angle_enclosed<T>(inner: rule<T>) -> VerboseEnclosed<T> =
+        lp:$"<" _ i:inner() _ rp:$">" { VerboseEnclosed::from_located(lp, i, rp) }
+
     rule brace_enclosed<T>(inner: rule<T>) -> VerboseEnclosed<T> =
         lp:$"{" _ i:inner() _ rp:$"}" { VerboseEnclosed::from_located(lp, i, rp) }
-    
+
     rule separation() =
-        (quiet!{ [tok!(Newline)]+ } / expected!("<newline>")) _ /
-        (quiet!{ [tok!(Semicolon)] } / expected!(";")) _
+        quiet!{ [tok!(Whitespace | MultilineComment)]* } (
+            (quiet!{ [tok!(Newline | Comment)]+ } / expected!("<newline>")) _ /
+            (quiet!{ [tok!(Semicolon)] } / expected!(";")) _
+        )
 
     rule separated<T>(inner: rule<T>) -> Vec<T> =
         inner() ** separation()
@@ -53,7 +64,16 @@ peg::parser! {grammar grammar<'t>() for PackedTokenStream<'t> {
     rule tuple() -> rlt::Type =
         i:paren_enclosed(<comma_separated0(<type_grammar()>)>) { rlt::Type::Tuple(i.into()) }
 
+    rule generics() -> VerboseEnclosed<Vec<rlt::new_types::TypeName>> =
+        angle_enclosed(<comma_separated0(<type_ident()>)>)
+
+    rule type_application() -> rlt::Type =
+        id:type_ident() i:angle_enclosed(<comma_separated0(<type_grammar()>)>) {
+            rlt::Type::Application { id, arguments: i.into() }
+        }
+
     pub rule type_grammar() -> rlt::Type =
+        type_application() /
         i:type_ident() { rlt::Type::Reference(i) } /
         tuple()
 
@@ -76,7 +96,7 @@ peg::parser! {grammar grammar<'t>() for PackedTokenStream<'t> {
 
     /// Literals grammar
     /// --------------------------------------------------------------------------------------------
-    
+
     rule lit<T>(inner: rule<T>, name: &'static str) -> T =
         quiet!{ inner() } / expected!(name)
 
@@ -86,7 +106,22 @@ peg::parser! {grammar grammar<'t>() for PackedTokenStream<'t> {
         i:lit(<[tok!(Hex)]>,      "<hex literal>")    { rlt::Literal::Hex(Span::new(i.point)) }      /
         i:lit(<[tok!(Floating)]>, "<number literal>") { rlt::Literal::Floating(Span::new(i.point)) } /
         i:lit(<[tok!(Char)]>,     "<char literal>")   { rlt::Literal::Char(Span::new(i.point)) }     /
-        i:lit(<[tok!(String)]>,   "<string literal>") { rlt::Literal::String(Span::new(i.point)) }
+        i:lit(<[tok!(String)]>,   "<string literal>") { rlt::Literal::String(Span::new(i.point)) }   /
+        interpolated_string()
+
+    rule string_fragment(kind: PackedToken, name: &'static str) -> rlt::Literal =
+        i:lit(<[t if t.token == kind]>, name) { rlt::Literal::String(Span::new(i.point)) }
+
+    rule interpolated_string() -> rlt::Literal =
+        start:string_fragment(StringStart, "<string literal>") _ first:operator_grammar() _
+        rest:(m:string_fragment(StringMiddle, "\"}\"") _ e:operator_grammar() _ { (m, e) })*
+        end:string_fragment(StringEnd, "\"}\"") {
+            let (middles, others): (Vec<_>, Vec<_>) = rest.into_iter().unzip();
+            rlt::Literal::Interpolated(rlt::InterpolatedString {
+                fragments: std::iter::once(start).chain(middles).chain([end]).collect(),
+                expressions: std::iter::once(first).chain(others).collect(),
+            })
+        }
 
     /// Operators grammar
     /// --------------------------------------------------------------------------------------------
@@ -213,6 +248,12 @@ peg::parser! {grammar grammar<'t>() for PackedTokenStream<'t> {
             expr: Box::new(a)
         } }
         --
+        a:(@) _ k:$"with" _ f:record_fields() { rlt::Operation::Update {
+            record: Box::new(a),
+            keyword: Keyword::from_located(k),
+            fields: f.into()
+        } }
+        --
         a:(@) _ op:$"." _ b:@ { rlt::Operation::Access {
             left: Box::new(a),
             dot: Symbol::from_located(op),
@@ -221,8 +262,25 @@ peg::parser! {grammar grammar<'t>() for PackedTokenStream<'t> {
         --
         i:application() { i }
     }
-    
+
+    rule field_init() -> rlt::FieldInit =
+        i:ident() _ e:$"=" _ v:operator_grammar() {
+            rlt::FieldInit {
+                id: Identifier::from_located(i.point),
+                equals: Symbol::from_located(e),
+                value: v
+            }
+        }
+
+    /// At least one field is required, so `if True {}` is not taken for a construction
+    rule record_fields() -> VerboseEnclosed<Vec<rlt::FieldInit>> =
+        brace_enclosed(<i:(field_init() ++ (_ "," _)) _ ","? { i }>)
+
+    rule record() -> rlt::Record =
+        id:type_ident() _ f:record_fields() { rlt::Record { id, fields: f.into() } }
+
     rule atom() -> rlt::Operation =
+        i:record()                                                         { rlt::Operation::Record(Box::new(i)) }                                                 /
         i:expression_grammar()                                             { rlt::Operation::Expression(i) }                                                       /
         i:paren_enclosed(<operator_grammar()>)                             { i.inner }                                                                             /
         i:paren_enclosed(<comma_separated0(<operator_grammar()>)>)         { rlt::Operation::Expression(rlt::Expression::Literal(rlt::Literal::Tuple(i.into()))) } /
@@ -289,7 +347,7 @@ peg::parser! {grammar grammar<'t>() for PackedTokenStream<'t> {
         }
 
     rule global_ref() -> (rlt::Context, rlt::Reference) =
-        g:$"::" ctx:(type_ref() ++ (!("::" variable_ref()) "::")) "::" v:variable_ref() {
+        g:$"::" ctx:(t:type_ref() "::" { t })* v:variable_ref() {
             let start = rlt::Context::Global {
                 colon: Symbol::from_located(g)
             };
@@ -357,7 +415,7 @@ peg::parser! {grammar grammar<'t>() for PackedTokenStream<'t> {
         }
 
     rule if() -> rlt::IfExpr =
-        k:$"if" _ c:operator_grammar() _ i:body() _ el:(elif() ** _) _ es:else()? {
+        k:$"if" _ c:operator_grammar() _ i:body() el:(_ i:elif() { i })* es:(_ i:else() { i })? {
             rlt::IfExpr {
                 keyword: Keyword::from_located(k),
                 condition: c,
@@ -377,7 +435,7 @@ peg::parser! {grammar grammar<'t>() for PackedTokenStream<'t> {
             rlt::ExpressionBlock {
                 lbrace: Symbol::from_located(lb),
                 expression: i.into_boxed_slice(),
-                rbrace: Symbol::from_located(lb)
+                rbrace: Symbol::from_located(rb)
             }
         }
 
@@ -423,10 +481,11 @@ peg::parser! {grammar grammar<'t>() for PackedTokenStream<'t> {
     /// --------------------------------------------------------------------------------------------
 
     rule bodied() -> rlt::BodiedFunction =
-        k:$"fun" _ id:ident() _ ps:paren_enclosed(<comma_separated0(<parameter()>)>)? _
+        k:$"fun" _ id:ident() g:generics()? _ ps:paren_enclosed(<comma_separated0(<parameter()>)>)? _
         ty:return_type()? _ b:body() {
             rlt::BodiedFunction {
                 keyword: Keyword::from_located(k),
+                generics: g.map(|it| it.into()),
                 params: ps.map(|it| it.into()),
                 id: Identifier::from_located(id.point),
                 return_type: ty,
@@ -434,42 +493,111 @@ peg::parser! {grammar grammar<'t>() for PackedTokenStream<'t> {
             }
         }
 
+    rule foreign() -> rlt::ForeignFunction =
+        f:$"foreign" _ k:$"fun" _ id:ident() g:generics()?
+        ps:(_ p:paren_enclosed(<comma_separated0(<typed_parameter()>)>) { p })?
+        ty:(_ t:return_type() { t })? {
+            rlt::ForeignFunction {
+                keyword: Keyword::from_located(f),
+                function: rlt::AbstractFunction {
+                    keyword: Keyword::from_located(k),
+                    id: Identifier::from_located(id.point),
+                    generics: g.map(|it| it.into()),
+                    params: ps.map(|it| it.into()),
+                    return_type: ty
+                }
+            }
+        }
+
     /// Top level grammar
     /// --------------------------------------------------------------------------------------------
 
+    rule variant_payload() -> rlt::VariantPayload =
+        i:paren_enclosed(<comma_separated1(<typed_parameter()>)>) { rlt::VariantPayload::Named(i.into()) }      /
+        i:paren_enclosed(<comma_separated0(<type_grammar()>)>)    { rlt::VariantPayload::Positional(i.into()) }
+
+    rule enum_variant() -> rlt::EnumVariant =
+        id:type_ident() payload:(_ p:variant_payload() { p })? {
+            rlt::EnumVariant { id, payload }
+        }
+
+    rule enum_contents() -> Option<VerboseEnclosed<Vec<rlt::EnumVariant>>> =
+        ";"                                                    { None }    /
+        i:brace_enclosed(<comma_separated0(<enum_variant()>)>) { Some(i) }
+
     rule enum_statement() -> rlt::Enum =
-        k:$"enum" _ "struct" _ id:type_ident() _ i:(
-            ";"                                                  { None }    /
-            i:brace_enclosed(<comma_separated0(<type_ident()>)>) { Some(i) }
-        ) {
+        k:$("enum" _ "struct") _ id:type_ident() g:generics()? _ i:enum_contents() {
             rlt::Enum::Stack {
                 keyword: Keyword::from_located(k),
                 id,
+                generics: g.map(|it| it.into()),
+                contents: i.map(|it| it.into())
+            }
+        } /
+        k:$("enum" _ "class") _ id:type_ident() g:generics()? _ i:enum_contents() {
+            rlt::Enum::Heap {
+                keyword: Keyword::from_located(k),
+                id,
+                generics: g.map(|it| it.into()),
                 contents: i.map(|it| it.into())
             }
         }
 
     rule struct_statement() -> rlt::Struct =
-        k:$"struct" _ id:type_ident() _ ps:paren_enclosed(<comma_separated0(<typed_parameter()>)>)? _
-        i:brace_enclosed(<separated(<bodied()>)>)? {
+        k:$"struct" _ id:type_ident() g:generics()?
+        ps:(_ i:paren_enclosed(<comma_separated0(<typed_parameter()>)>) { i })?
+        i:(_ i:brace_enclosed(<separated(<bodied()>)>) { i })? {
             rlt::Struct {
                 keyword: Keyword::from_located(k),
                 id,
+                generics: g.map(|it| it.into()),
                 parameters: ps.map(|it| it.into()),
                 body: i.map(|it| it.into())
             }
         }
 
+    rule type_alias_statement() -> rlt::TypeAlias =
+        k:$"type" _ id:type_ident() _ e:$"=" _ ty:type_grammar() {
+            rlt::TypeAlias {
+                keyword: Keyword::from_located(k),
+                id,
+                equals: Symbol::from_located(e),
+                aliased: ty
+            }
+        }
+
     pub rule top_level_grammar() -> rlt::TopLevelNode =
-        i:enum_statement()   { rlt::TopLevelNode::Enum(i) }           /
-        i:struct_statement() { rlt::TopLevelNode::Struct(i) }         /
-        i:bodied()           { rlt::TopLevelNode::BodiedFunction(i) }
+        i:enum_statement()       { rlt::TopLevelNode::Enum(i) }           /
+        i:struct_statement()     { rlt::TopLevelNode::Struct(i) }         /
+        i:type_alias_statement() { rlt::TopLevelNode::TypeAlias(i) }      /
+        i:foreign()              { rlt::TopLevelNode::ForeignFunction(i) } /
+        i:bodied()               { rlt::TopLevelNode::BodiedFunction(i) }
+
+    /// Error recovery
+    /// --------------------------------------------------------------------------------------------
+
+    rule balanced() = "{" (balanced() / !"}" quiet!{ [_] })* "}"
+
+    /// Sync point described in [`crate::common::RecoveringRLTProducer`]
+    rule sync_point() =
+        [tok!(Newline | Comment)] _ ([t if t.token.starts_declaration()] / "}") / "}" / ![_]
+
+    rule declaration_end() =
+        quiet!{ [tok!(Whitespace | MultilineComment)]* ([tok!(Newline | Comment | Semicolon)] / "}" / ![_]) }
+
+    /// Parses a declaration, or skips tokens up to the next one reporting the error
+    rule recovering_top_level() -> rlt::TopLevelNode =
+        i:top_level_grammar() &declaration_end() { i } /
+        start:position!() s:$((!sync_point() (balanced() / quiet!{ [_] }))+) end:position!() {
+            recovery.report(start..end);
+            rlt::TopLevelNode::Error(Erroneous::from_located(s))
+        }
 
     /// Modules grammar
     /// --------------------------------------------------------------------------------------------
 
     rule module() -> rlt::Module =
-        k:$"module" _ id:type_ident() _ lb:$"{" _ i:separated(<top_level_grammar()>) _ rb:$"}" {
+        k:$"module" _ id:type_ident() _ lb:$"{" _ i:separated(<recovering_top_level()>) _ rb:$"}" {
             rlt::Module::Ordinary {
                 keyword: Keyword::from_located(k),
                 id,
@@ -480,7 +608,7 @@ peg::parser! {grammar grammar<'t>() for PackedTokenStream<'t> {
         }
 
     rule global_module() -> rlt::Module =
-        k:$"module" _ id:type_ident() _ f:$"=>" _ i:separated(<top_level_grammar()>) {
+        k:$"module" _ id:type_ident() _ f:$"=>" _ i:separated(<recovering_top_level()>) {
             rlt::Module::Global {
                 keyword: Keyword::from_located(k),
                 id,
@@ -497,21 +625,45 @@ peg::parser! {grammar grammar<'t>() for PackedTokenStream<'t> {
         i:global_module() { rlt::File::new(Box::new([i])) }        /
         _                 { rlt::File::new(Box::new([])) }
 
-    rule traced<T>(e: rule<T>) -> T =
-        &(input:$([_]*) {
-            #[cfg(feature = "trace")]
-            println!("[PEG_INPUT_START]\n{}\n[PEG_TRACE_START]", input);
-        })
-        e:e()? {?
-            #[cfg(feature = "trace")]
-            println!("[PEG_TRACE_STOP]");
-            e.ok_or("")
-        }
-
     pub rule kodept() -> RLT =
-        _ i:traced(<file_grammar()>) _ ![_] { RLT(i) }
+        _ i:file_grammar() _ ![_] { RLT(i) }
 }}
 
+/// Collects errors of declarations skipped while parsing `input`
+struct Recovery<'t> {
+    input: PackedTokenStream<'t>,
+    errors: RefCell<Vec<ParseError<Position>>>,
+}
+
+impl<'t> Recovery<'t> {
+    fn new(input: PackedTokenStream<'t>) -> Self {
+        Self {
+            input,
+            errors: RefCell::default(),
+        }
+    }
+
+    /// Parses skipped tokens again along with the token following them to find out the error
+    fn report(&self, skipped: Range<usize>) {
+        let end = self.input[skipped.end..]
+            .iter()
+            .position(|it| !it.token.is_ignored())
+            .map_or(self.input.len(), |it| skipped.end + it + 1);
+        let declaration = self.input.sub_stream(skipped.start..end);
+        if let Err(e) = grammar::top_level_grammar(&declaration, self) {
+            self.errors.borrow_mut().push(e);
+        }
+    }
+
+    fn parse(
+        input: &PackedTokenStream<'t>,
+    ) -> Result<(RLT, Vec<ParseError<Position>>), ParseError<Position>> {
+        let recovery = Recovery::new(*input);
+        let rlt = grammar::kodept(input, &recovery)?;
+        Ok((rlt, recovery.errors.into_inner()))
+    }
+}
+
 #[derive(Constructor, Debug)]
 pub struct Parser<const TRACE: bool = false>;
 
@@ -519,7 +671,20 @@ impl RLTProducer for Parser<TRACING_OPTION> {
     type Error<'t> = ParseError<Position>;
 
     fn parse_stream<'t>(&self, input: &PackedTokenStream<'t>) -> Result<RLT, Self::Error<'t>> {
-        grammar::kodept(input)
+        let (rlt, errors) = self.parse_recovering(input)?;
+        match errors.into_iter().next() {
+            None => Ok(rlt),
+            Some(e) => Err(e),
+        }
+    }
+}
+
+impl RecoveringRLTProducer for Parser<TRACING_OPTION> {
+    fn parse_recovering<'t>(
+        &self,
+        input: &PackedTokenStream<'t>,
+    ) -> Result<(RLT, Vec<Self::Error<'t>>), Self::Error<'t>> {
+        Recovery::parse(input)
     }
 }
 
@@ -528,7 +693,20 @@ impl RLTProducer for Parser<false> {
     type Error<'t> = ParseError<Position>;
 
     fn parse_stream<'t>(&self, input: &PackedTokenStream<'t>) -> Result<RLT, Self::Error<'t>> {
-        let _gag = gag::Gag::stdout().expect("Cannot suppress stdout");
-        grammar::kodept(&input)
+        let (rlt, errors) = self.parse_recovering(input)?;
+        match errors.into_iter().next() {
+            None => Ok(rlt),
+            Some(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(feature = "trace")]
+impl RecoveringRLTProducer for Parser<false> {
+    fn parse_recovering<'t>(
+        &self,
+        input: &PackedTokenStream<'t>,
+    ) -> Result<(RLT, Vec<Self::Error<'t>>), Self::Error<'t>> {
+        crate::trace::traced_if::<false, _>(|| Recovery::parse(input))
     }
 }
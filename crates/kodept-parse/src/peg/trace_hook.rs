@@ -0,0 +1,33 @@
+//! `peg` built with its `trace` feature reports every rule it enters and leaves with `println!`.
+//! Grammars are declared after this module, so generated code uses the `println!` below,
+//! which records the events in [`crate::trace`] instead of printing them.
+
+#[cfg(feature = "trace")]
+macro_rules! println {
+    ("[PEG_TRACE] Attempting to match rule `{}` at {}", $rule:expr, $at:expr) => {
+        $crate::trace::peg_event($rule, $at, $crate::trace::EventKind::Attempt)
+    };
+    ("[PEG_TRACE] Matched rule `{}` at {} to {}", $rule:expr, $at:expr, $end:expr) => {
+        $crate::trace::peg_event(
+            $rule,
+            $at,
+            $crate::trace::EventKind::Match { end: $end.into() },
+        )
+    };
+    ("[PEG_TRACE] Failed to match rule `{}` at {}", $rule:expr, $at:expr) => {
+        $crate::trace::peg_event($rule, $at, $crate::trace::EventKind::Fail)
+    };
+    ("[PEG_TRACE] Cached match of rule {} at {}", $rule:expr, $at:expr) => {
+        $crate::trace::peg_event($rule, $at, $crate::trace::EventKind::CachedMatch)
+    };
+    ("[PEG_TRACE] Cached fail of rule {} at {}", $rule:expr, $at:expr) => {
+        $crate::trace::peg_event($rule, $at, $crate::trace::EventKind::CachedFail)
+    };
+    // Levels of precedence climbing are not rules
+    ("[PEG_TRACE] Entering level {}", $level:expr) => {
+        let _ = $level;
+    };
+    ("[PEG_TRACE] Leaving level {}", $level:expr) => {
+        let _ = $level;
+    };
+}
@@ -28,3 +28,24 @@ where
         }])
     }
 }
+
+/// Error of the pest parser located in code rather than in encoded tokens
+#[derive(Debug)]
+pub struct SyntaxError {
+    pub(super) expected: Vec<Cow<'static, str>>,
+    pub(super) point: CodePoint,
+}
+
+impl<A, O> ErrorAdapter<A, O> for SyntaxError
+where
+    O: Original<A>,
+{
+    fn adapt(self, original_input: O, position: usize) -> ParseErrors<A> {
+        let actual = original_input.actual(self.point);
+        let location = ErrorLocation::new(position, self.point);
+        ParseErrors::new(vec![match actual {
+            None => ParseError::unexpected_eof(self.expected, location),
+            Some(actual) => ParseError::expected(self.expected, actual, location),
+        }])
+    }
+}
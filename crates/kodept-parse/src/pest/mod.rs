@@ -1,4 +1,6 @@
-mod lexer;
 mod error;
+mod lexer;
+mod parser;
 
 pub(crate) use lexer::Lexer;
+pub(crate) use parser::Parser;
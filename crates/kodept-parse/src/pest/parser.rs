@@ -0,0 +1,921 @@
+use std::borrow::Cow;
+
+use derive_more::Constructor;
+use itertools::Itertools;
+use kodept_core::code_point::CodePoint;
+use kodept_core::structure::rlt::new_types::{
+    BinaryOperationSymbol, Erroneous, Identifier, Keyword, Symbol, TypeName, UnaryOperationSymbol,
+};
+use kodept_core::structure::rlt::RLT;
+use kodept_core::structure::span::Span;
+use kodept_core::structure::{rlt, Located};
+use pest::error::{Error, ErrorVariant, InputLocation};
+use pest::iterators::{Pair, Pairs};
+use pest::pratt_parser::{Assoc, Op, PrattParser};
+use pest::Parser as _;
+
+use crate::common::{RLTProducer, RecoveringRLTProducer, VerboseEnclosed};
+use crate::lexer::traits::ToRepresentation;
+use crate::lexer::PackedToken;
+use crate::pest::error::SyntaxError;
+use crate::token_stream::PackedTokenStream;
+
+#[derive(pest_derive::Parser)]
+#[grammar = "pest/parser.pest"]
+struct Grammar;
+
+/// Encodes every token as a single character, so pest positions are indices of tokens
+fn encode(input: &PackedTokenStream) -> String {
+    input.iter().map(|it| it.token as u8 as char).collect()
+}
+
+/// Returns token matched by the terminal `rule` of the grammar
+fn terminal(rule: Rule) -> Option<PackedToken> {
+    Some(match rule {
+        Rule::FUN => PackedToken::Fun,
+        Rule::VAL => PackedToken::Val,
+        Rule::VAR => PackedToken::Var,
+        Rule::IF => PackedToken::If,
+        Rule::ELIF => PackedToken::Elif,
+        Rule::ELSE => PackedToken::Else,
+        Rule::MATCH => PackedToken::Match,
+        Rule::WHILE => PackedToken::While,
+        Rule::MODULE => PackedToken::Module,
+        Rule::EXTEND => PackedToken::Extend,
+        Rule::LAMBDA => PackedToken::Lambda,
+        Rule::ABSTRACT => PackedToken::Abstract,
+        Rule::TRAIT => PackedToken::Trait,
+        Rule::STRUCT => PackedToken::Struct,
+        Rule::CLASS => PackedToken::Class,
+        Rule::ENUM => PackedToken::Enum,
+        Rule::FOREIGN => PackedToken::Foreign,
+        Rule::TYPE_ALIAS => PackedToken::TypeAlias,
+        Rule::WITH => PackedToken::With,
+        Rule::RETURN => PackedToken::Return,
+        Rule::LBRACE => PackedToken::LBrace,
+        Rule::RBRACE => PackedToken::RBrace,
+        Rule::LBRACKET => PackedToken::LBracket,
+        Rule::RBRACKET => PackedToken::RBracket,
+        Rule::LPAREN => PackedToken::LParen,
+        Rule::RPAREN => PackedToken::RParen,
+        Rule::TYPE_GAP => PackedToken::TypeGap,
+        Rule::DOUBLE_COLON => PackedToken::DoubleColon,
+        Rule::COLON => PackedToken::Colon,
+        Rule::IDENTIFIER => PackedToken::Identifier,
+        Rule::TYPE => PackedToken::Type,
+        Rule::BINARY => PackedToken::Binary,
+        Rule::OCTAL => PackedToken::Octal,
+        Rule::HEX => PackedToken::Hex,
+        Rule::FLOATING => PackedToken::Floating,
+        Rule::CHAR => PackedToken::Char,
+        Rule::STRING => PackedToken::String,
+        Rule::STRING_START => PackedToken::StringStart,
+        Rule::STRING_MIDDLE => PackedToken::StringMiddle,
+        Rule::STRING_END => PackedToken::StringEnd,
+        Rule::DOT => PackedToken::Dot,
+        Rule::FLOW => PackedToken::Flow,
+        Rule::PLUS => PackedToken::Plus,
+        Rule::SUB => PackedToken::Sub,
+        Rule::DIV => PackedToken::Div,
+        Rule::MOD => PackedToken::Mod,
+        Rule::POW => PackedToken::Pow,
+        Rule::TIMES => PackedToken::Times,
+        Rule::EQUALS => PackedToken::Equals,
+        Rule::EQUIV => PackedToken::Equiv,
+        Rule::NOT_EQUIV => PackedToken::NotEquiv,
+        Rule::LESS => PackedToken::Less,
+        Rule::LESS_EQUALS => PackedToken::LessEquals,
+        Rule::GREATER => PackedToken::Greater,
+        Rule::GREATER_EQUALS => PackedToken::GreaterEquals,
+        Rule::SPACESHIP => PackedToken::Spaceship,
+        Rule::OR_LOGIC => PackedToken::OrLogic,
+        Rule::AND_LOGIC => PackedToken::AndLogic,
+        Rule::NOT_LOGIC => PackedToken::NotLogic,
+        Rule::OR_BIT => PackedToken::OrBit,
+        Rule::AND_BIT => PackedToken::AndBit,
+        Rule::XOR_BIT => PackedToken::XorBit,
+        Rule::NOT_BIT => PackedToken::NotBit,
+        _ => return None,
+    })
+}
+
+/// Returns tokens an attempt to match `rule` starts with
+fn first_tokens(rule: Rule) -> Vec<PackedToken> {
+    if let Some(token) = terminal(rule) {
+        return vec![token];
+    }
+    let rules: &[Rule] = match rule {
+        Rule::return_type => &[Rule::COLON],
+        Rule::generics | Rule::type_arguments => &[Rule::LESS],
+        Rule::type_grammar => &[Rule::TYPE, Rule::LPAREN],
+        Rule::tuple_type
+        | Rule::parameters
+        | Rule::typed_parameters
+        | Rule::parenthesized
+        | Rule::tuple_literal
+        | Rule::named_payload
+        | Rule::positional_payload => &[Rule::LPAREN],
+        Rule::typed_parameter | Rule::untyped_parameter | Rule::parameter | Rule::field_init => {
+            &[Rule::IDENTIFIER]
+        }
+        Rule::literal => &[
+            Rule::BINARY,
+            Rule::OCTAL,
+            Rule::HEX,
+            Rule::FLOATING,
+            Rule::CHAR,
+            Rule::STRING,
+            Rule::STRING_START,
+        ],
+        Rule::interpolated_string => &[Rule::STRING_START],
+        Rule::unary => &[Rule::SUB, Rule::NOT_LOGIC, Rule::NOT_BIT, Rule::PLUS],
+        Rule::update => &[Rule::WITH],
+        Rule::operator => &[Rule::unary, Rule::application],
+        Rule::application => &[Rule::atom],
+        Rule::atom => &[Rule::record, Rule::expression, Rule::LPAREN, Rule::LBRACE],
+        Rule::record_fields | Rule::block | Rule::struct_body => &[Rule::LBRACE],
+        Rule::binds | Rule::lambda => &[Rule::LBRACKET],
+        Rule::expression => &[Rule::lambda, Rule::term, Rule::literal, Rule::IF],
+        Rule::term => &[Rule::DOUBLE_COLON, Rule::IDENTIFIER, Rule::TYPE],
+        Rule::global_ref | Rule::global_type_ref => &[Rule::DOUBLE_COLON],
+        Rule::type_application
+        | Rule::record
+        | Rule::local_ref
+        | Rule::local_type_ref
+        | Rule::enum_variant => &[Rule::TYPE],
+        Rule::if_expr => &[Rule::IF],
+        Rule::elif_expr => &[Rule::ELIF],
+        Rule::else_expr => &[Rule::ELSE],
+        Rule::simple_body => &[Rule::FLOW],
+        Rule::body => &[Rule::LBRACE, Rule::FLOW],
+        Rule::var_decl | Rule::init_var => &[Rule::VAL, Rule::VAR],
+        Rule::block_level => &[Rule::LBRACE, Rule::init_var, Rule::bodied, Rule::operator],
+        Rule::bodied => &[Rule::FUN],
+        Rule::foreign => &[Rule::FOREIGN],
+        Rule::enum_contents => return vec![PackedToken::Semicolon, PackedToken::LBrace],
+        Rule::enum_stack | Rule::enum_heap | Rule::enum_statement => &[Rule::ENUM],
+        Rule::struct_statement => &[Rule::STRUCT],
+        Rule::type_alias => &[Rule::TYPE_ALIAS],
+        Rule::top_level => &[
+            Rule::enum_statement,
+            Rule::struct_statement,
+            Rule::type_alias,
+            Rule::foreign,
+            Rule::bodied,
+        ],
+        Rule::module | Rule::global_module | Rule::file | Rule::kodept => &[Rule::MODULE],
+        _ => &[],
+    };
+    rules.iter().flat_map(|it| first_tokens(*it)).collect()
+}
+
+/// Names `token` the way the PEG parser does
+fn describe(token: PackedToken) -> Cow<'static, str> {
+    match token {
+        PackedToken::Identifier
+        | PackedToken::Type
+        | PackedToken::Binary
+        | PackedToken::Octal
+        | PackedToken::Hex
+        | PackedToken::Floating
+        | PackedToken::Char
+        | PackedToken::String => Cow::Borrowed(token.representation()),
+        PackedToken::StringStart => Cow::Borrowed(PackedToken::String.representation()),
+        PackedToken::StringMiddle | PackedToken::StringEnd => Cow::Borrowed("\"}\""),
+        _ => Cow::Owned(format!("\"{}\"", token.representation())),
+    }
+}
+
+/// Translates position of `error` from indices of tokens to code,
+/// and names rules by tokens they start with, the way other parsers do
+fn locate(error: Error<Rule>, input: &PackedTokenStream) -> SyntaxError {
+    let expected = match error.variant {
+        ErrorVariant::ParsingError { positives, .. } => positives
+            .into_iter()
+            .flat_map(|rule| match rule {
+                Rule::EOI => vec![Cow::Borrowed("EOF")],
+                _ => first_tokens(rule).into_iter().map(describe).collect(),
+            })
+            .sorted()
+            .dedup()
+            .collect(),
+        ErrorVariant::CustomError { message } => vec![Cow::Owned(message)],
+    };
+    let position = match error.location {
+        InputLocation::Pos(pos) | InputLocation::Span((pos, _)) => pos,
+    };
+    let point = match input.get(position) {
+        Some(token) => token.point,
+        None => CodePoint::single_point(input.location().as_range().end as u32),
+    };
+    SyntaxError { expected, point }
+}
+
+/// Builds RLT from pairs, collecting errors of declarations skipped while parsing `input`
+struct Builder<'t> {
+    input: PackedTokenStream<'t>,
+    encoded: &'t str,
+    pratt: PrattParser<Rule>,
+    errors: Vec<SyntaxError>,
+}
+
+impl<'t> Builder<'t> {
+    fn new(input: PackedTokenStream<'t>, encoded: &'t str) -> Self {
+        let pratt = PrattParser::new()
+            .op(Op::infix(Rule::EQUALS, Assoc::Right))
+            .op(Op::infix(Rule::OR_LOGIC, Assoc::Left) | Op::infix(Rule::AND_LOGIC, Assoc::Left))
+            .op(Op::infix(Rule::OR_BIT, Assoc::Left)
+                | Op::infix(Rule::AND_BIT, Assoc::Left)
+                | Op::infix(Rule::XOR_BIT, Assoc::Left))
+            .op(Op::infix(Rule::LESS, Assoc::Left) | Op::infix(Rule::GREATER, Assoc::Left))
+            .op(Op::infix(Rule::LESS_EQUALS, Assoc::Left)
+                | Op::infix(Rule::NOT_EQUIV, Assoc::Left)
+                | Op::infix(Rule::EQUIV, Assoc::Left)
+                | Op::infix(Rule::GREATER_EQUALS, Assoc::Left))
+            .op(Op::infix(Rule::SPACESHIP, Assoc::Left))
+            .op(Op::infix(Rule::PLUS, Assoc::Left) | Op::infix(Rule::SUB, Assoc::Left))
+            .op(Op::infix(Rule::TIMES, Assoc::Left)
+                | Op::infix(Rule::DIV, Assoc::Left)
+                | Op::infix(Rule::MOD, Assoc::Left))
+            .op(Op::infix(Rule::POW, Assoc::Right))
+            .op(Op::prefix(Rule::unary))
+            .op(Op::postfix(Rule::update))
+            .op(Op::infix(Rule::DOT, Assoc::Left));
+
+        Self {
+            input,
+            encoded,
+            pratt,
+            errors: vec![],
+        }
+    }
+
+    fn point(&self, pair: &Pair<Rule>) -> CodePoint {
+        let span = pair.as_span();
+        self.input.sub_stream(span.start()..span.end()).location()
+    }
+
+    fn enclosed<T>(
+        &self,
+        pair: Pair<Rule>,
+        mut item: impl FnMut(&Self, Pair<Rule>) -> T,
+    ) -> VerboseEnclosed<Vec<T>> {
+        let mut pairs: Vec<_> = pair.into_inner().collect();
+        let (Some(right), Some(left)) = (pairs.pop(), pairs.first().cloned()) else {
+            unreachable!("Enclosed rules start and end with a token")
+        };
+        let inner = pairs.into_iter().skip(1).map(|it| item(self, it)).collect();
+        VerboseEnclosed::from_located(self.point(&left), inner, self.point(&right))
+    }
+
+    fn type_name(&self, pair: Pair<Rule>) -> TypeName {
+        TypeName::from_located(self.point(&pair))
+    }
+
+    fn identifier(&self, pair: Pair<Rule>) -> Identifier {
+        Identifier::from_located(self.point(&pair))
+    }
+
+    fn symbol(&self, pair: Pair<Rule>) -> Symbol {
+        Symbol::from_located(self.point(&pair))
+    }
+
+    fn keyword(&self, pair: Pair<Rule>) -> Keyword {
+        Keyword::from_located(self.point(&pair))
+    }
+
+    // Type grammar
+    // ---------------------------------------------------------------------------------------------
+
+    fn return_type(&self, pair: Pair<Rule>) -> (Symbol, rlt::Type) {
+        let mut pairs = pair.into_inner();
+        let colon = self.symbol(next(&mut pairs));
+        (colon, self.type_grammar(next(&mut pairs)))
+    }
+
+    fn generics(&self, pair: Pair<Rule>) -> VerboseEnclosed<Vec<TypeName>> {
+        self.enclosed(pair, Self::type_name)
+    }
+
+    fn type_grammar(&self, pair: Pair<Rule>) -> rlt::Type {
+        let pair = inner(pair);
+        match pair.as_rule() {
+            Rule::type_application => {
+                let mut pairs = pair.into_inner();
+                rlt::Type::Application {
+                    id: self.type_name(next(&mut pairs)),
+                    arguments: self.enclosed(next(&mut pairs), Self::type_grammar).into(),
+                }
+            }
+            Rule::TYPE => rlt::Type::Reference(self.type_name(pair)),
+            Rule::tuple_type => rlt::Type::Tuple(self.enclosed(pair, Self::type_grammar).into()),
+            x => unreachable!("Unexpected rule {x:?}"),
+        }
+    }
+
+    // Parameters grammar
+    // ---------------------------------------------------------------------------------------------
+
+    fn typed_parameter(&self, pair: Pair<Rule>) -> rlt::TypedParameter {
+        let mut pairs = pair.into_inner();
+        let id = self.identifier(next(&mut pairs));
+        let _colon = next(&mut pairs);
+        rlt::TypedParameter {
+            id,
+            parameter_type: self.type_grammar(next(&mut pairs)),
+        }
+    }
+
+    fn parameter(&self, pair: Pair<Rule>) -> rlt::Parameter {
+        let pair = inner(pair);
+        match pair.as_rule() {
+            Rule::typed_parameter => rlt::Parameter::Typed(self.typed_parameter(pair)),
+            Rule::untyped_parameter => rlt::Parameter::Untyped(rlt::UntypedParameter {
+                id: self.identifier(inner(pair)),
+            }),
+            x => unreachable!("Unexpected rule {x:?}"),
+        }
+    }
+
+    // Literals grammar
+    // ---------------------------------------------------------------------------------------------
+
+    fn literal(&self, pair: Pair<Rule>) -> rlt::Literal {
+        let pair = inner(pair);
+        let span = Span::new(self.point(&pair));
+        match pair.as_rule() {
+            Rule::BINARY => rlt::Literal::Binary(span),
+            Rule::OCTAL => rlt::Literal::Octal(span),
+            Rule::HEX => rlt::Literal::Hex(span),
+            Rule::FLOATING => rlt::Literal::Floating(span),
+            Rule::CHAR => rlt::Literal::Char(span),
+            Rule::STRING => rlt::Literal::String(span),
+            Rule::interpolated_string => {
+                let (fragments, expressions): (Vec<_>, Vec<_>) = pair
+                    .into_inner()
+                    .partition(|it| it.as_rule() != Rule::operator);
+                rlt::Literal::Interpolated(rlt::InterpolatedString {
+                    fragments: fragments
+                        .iter()
+                        .map(|it| rlt::Literal::String(Span::new(self.point(it))))
+                        .collect(),
+                    expressions: expressions
+                        .into_iter()
+                        .map(|it| self.operator(it))
+                        .collect(),
+                })
+            }
+            x => unreachable!("Unexpected rule {x:?}"),
+        }
+    }
+
+    // Operators grammar
+    // ---------------------------------------------------------------------------------------------
+
+    fn operator(&self, pair: Pair<Rule>) -> rlt::Operation {
+        self.pratt
+            .map_primary(|it| self.application(it))
+            .map_prefix(|op, expr| {
+                let token = inner(op);
+                let symbol = self.symbol(token.clone());
+                rlt::Operation::TopUnary {
+                    operator: match token.as_rule() {
+                        Rule::SUB => UnaryOperationSymbol::Neg(symbol),
+                        Rule::NOT_LOGIC => UnaryOperationSymbol::Not(symbol),
+                        Rule::NOT_BIT => UnaryOperationSymbol::Inv(symbol),
+                        Rule::PLUS => UnaryOperationSymbol::Plus(symbol),
+                        x => unreachable!("Unexpected rule {x:?}"),
+                    },
+                    expr: Box::new(expr),
+                }
+            })
+            .map_postfix(|expr, op| {
+                let mut pairs = op.into_inner();
+                rlt::Operation::Update {
+                    record: Box::new(expr),
+                    keyword: self.keyword(next(&mut pairs)),
+                    fields: self.record_fields(next(&mut pairs)).into(),
+                }
+            })
+            .map_infix(|left, op, right| {
+                let rule = op.as_rule();
+                let symbol = self.symbol(op);
+                if rule == Rule::DOT {
+                    return rlt::Operation::Access {
+                        left: Box::new(left),
+                        dot: symbol,
+                        right: Box::new(right),
+                    };
+                }
+                rlt::Operation::Binary {
+                    left: Box::new(left),
+                    operation: match rule {
+                        Rule::EQUALS => BinaryOperationSymbol::Assign(symbol),
+                        Rule::OR_LOGIC | Rule::AND_LOGIC => BinaryOperationSymbol::Logic(symbol),
+                        Rule::OR_BIT | Rule::AND_BIT | Rule::XOR_BIT => {
+                            BinaryOperationSymbol::Bit(symbol)
+                        }
+                        Rule::LESS | Rule::GREATER => BinaryOperationSymbol::Comparison(symbol),
+                        Rule::LESS_EQUALS
+                        | Rule::NOT_EQUIV
+                        | Rule::EQUIV
+                        | Rule::GREATER_EQUALS => BinaryOperationSymbol::CompoundComparison(symbol),
+                        Rule::SPACESHIP => BinaryOperationSymbol::ComplexComparison(symbol),
+                        Rule::PLUS | Rule::SUB => BinaryOperationSymbol::Add(symbol),
+                        Rule::TIMES | Rule::DIV | Rule::MOD => BinaryOperationSymbol::Mul(symbol),
+                        Rule::POW => BinaryOperationSymbol::Pow(symbol),
+                        x => unreachable!("Unexpected rule {x:?}"),
+                    },
+                    right: Box::new(right),
+                }
+            })
+            .parse(pair.into_inner())
+    }
+
+    fn field_init(&self, pair: Pair<Rule>) -> rlt::FieldInit {
+        let mut pairs = pair.into_inner();
+        rlt::FieldInit {
+            id: self.identifier(next(&mut pairs)),
+            equals: self.symbol(next(&mut pairs)),
+            value: self.operator(next(&mut pairs)),
+        }
+    }
+
+    fn record_fields(&self, pair: Pair<Rule>) -> VerboseEnclosed<Vec<rlt::FieldInit>> {
+        self.enclosed(pair, Self::field_init)
+    }
+
+    fn atom(&self, pair: Pair<Rule>) -> rlt::Operation {
+        let pair = inner(pair);
+        match pair.as_rule() {
+            Rule::record => {
+                let mut pairs = pair.into_inner();
+                rlt::Operation::Record(Box::new(rlt::Record {
+                    id: self.type_name(next(&mut pairs)),
+                    fields: self.record_fields(next(&mut pairs)).into(),
+                }))
+            }
+            Rule::expression => rlt::Operation::Expression(self.expression(pair)),
+            Rule::parenthesized => {
+                let mut pairs = pair.into_inner();
+                let _lparen = next(&mut pairs);
+                self.operator(next(&mut pairs))
+            }
+            Rule::tuple_literal => rlt::Operation::Expression(rlt::Expression::Literal(
+                rlt::Literal::Tuple(self.enclosed(pair, Self::operator).into()),
+            )),
+            Rule::block => rlt::Operation::Block(self.block(pair)),
+            x => unreachable!("Unexpected rule {x:?}"),
+        }
+    }
+
+    fn application(&self, pair: Pair<Rule>) -> rlt::Operation {
+        let mut pairs = pair.into_inner();
+        let expr = self.atom(next(&mut pairs));
+        match pairs.next() {
+            None => expr,
+            Some(params) => rlt::Operation::Application(Box::new(rlt::Application {
+                expr,
+                params: Some(self.enclosed(params, Self::operator).into()),
+            })),
+        }
+    }
+
+    // Expressions grammar
+    // ---------------------------------------------------------------------------------------------
+
+    fn lambda(&self, pair: Pair<Rule>) -> rlt::Expression {
+        let mut pairs = pair.into_inner();
+        let binds = self.enclosed(next(&mut pairs), Self::parameter);
+        rlt::Expression::Lambda {
+            binds: VerboseEnclosed::from_located(
+                binds.left,
+                binds.inner.into_boxed_slice(),
+                binds.right,
+            )
+            .into(),
+            flow: self.symbol(next(&mut pairs)),
+            expr: Box::new(self.operator(next(&mut pairs))),
+        }
+    }
+
+    fn expression(&self, pair: Pair<Rule>) -> rlt::Expression {
+        let pair = inner(pair);
+        match pair.as_rule() {
+            Rule::lambda => self.lambda(pair),
+            Rule::term => rlt::Expression::Term(self.term(pair)),
+            Rule::literal => rlt::Expression::Literal(self.literal(pair)),
+            Rule::if_expr => rlt::Expression::If(Box::new(self.if_expr(pair))),
+            x => unreachable!("Unexpected rule {x:?}"),
+        }
+    }
+
+    // References grammar
+    // ---------------------------------------------------------------------------------------------
+
+    fn reference(&self, pair: Pair<Rule>) -> rlt::Reference {
+        match pair.as_rule() {
+            Rule::IDENTIFIER => rlt::Reference::Identifier(self.identifier(pair)),
+            Rule::TYPE => rlt::Reference::Type(self.type_name(pair)),
+            x => unreachable!("Unexpected rule {x:?}"),
+        }
+    }
+
+    fn term(&self, pair: Pair<Rule>) -> rlt::Term {
+        let pair = inner(pair);
+        if matches!(pair.as_rule(), Rule::IDENTIFIER | Rule::TYPE) {
+            return rlt::Term::Reference(self.reference(pair));
+        }
+
+        let mut pairs = pair.into_inner().peekable();
+        let start = match pairs.next_if(|it| it.as_rule() == Rule::DOUBLE_COLON) {
+            None => rlt::Context::Local,
+            Some(colon) => rlt::Context::Global {
+                colon: self.symbol(colon),
+            },
+        };
+        let mut references: Vec<_> = pairs
+            .filter(|it| it.as_rule() != Rule::DOUBLE_COLON)
+            .map(|it| self.reference(it))
+            .collect();
+        let Some(inner) = references.pop() else {
+            unreachable!("Contextual references have at least one part")
+        };
+        let context = references
+            .into_iter()
+            .fold(start, |acc, next| rlt::Context::Inner {
+                parent: Box::new(acc),
+                needle: next,
+            });
+        rlt::Term::Contextual(rlt::ContextualReference { context, inner })
+    }
+
+    // Code flow grammar
+    // ---------------------------------------------------------------------------------------------
+
+    fn if_expr(&self, pair: Pair<Rule>) -> rlt::IfExpr {
+        let mut pairs = pair.into_inner().peekable();
+        let keyword = self.keyword(next(&mut pairs));
+        let condition = self.operator(next(&mut pairs));
+        let body = self.body(next(&mut pairs));
+        let mut elif = vec![];
+        let mut el = None;
+        for pair in pairs {
+            let rule = pair.as_rule();
+            let mut pairs = pair.into_inner();
+            let keyword = self.keyword(next(&mut pairs));
+            match rule {
+                Rule::elif_expr => elif.push(rlt::ElifExpr {
+                    keyword,
+                    condition: self.operator(next(&mut pairs)),
+                    body: self.body(next(&mut pairs)),
+                }),
+                Rule::else_expr => {
+                    el = Some(rlt::ElseExpr {
+                        keyword,
+                        body: self.body(next(&mut pairs)),
+                    })
+                }
+                x => unreachable!("Unexpected rule {x:?}"),
+            }
+        }
+        rlt::IfExpr {
+            keyword,
+            condition,
+            body,
+            elif: elif.into_boxed_slice(),
+            el,
+        }
+    }
+
+    // Block level grammar
+    // ---------------------------------------------------------------------------------------------
+
+    fn block(&self, pair: Pair<Rule>) -> rlt::ExpressionBlock {
+        let block = self.enclosed(pair, Self::block_level);
+        rlt::ExpressionBlock {
+            lbrace: block.left.into(),
+            expression: block.inner.into_boxed_slice(),
+            rbrace: block.right.into(),
+        }
+    }
+
+    fn body(&self, pair: Pair<Rule>) -> rlt::Body {
+        let pair = inner(pair);
+        match pair.as_rule() {
+            Rule::block => rlt::Body::Block(self.block(pair)),
+            Rule::simple_body => {
+                let mut pairs = pair.into_inner();
+                let flow = self.symbol(next(&mut pairs));
+                let pair = next(&mut pairs);
+                rlt::Body::Simplified {
+                    flow,
+                    expression: match pair.as_rule() {
+                        Rule::block => rlt::BlockLevelNode::Block(self.block(pair)),
+                        _ => rlt::BlockLevelNode::Operation(self.operator(pair)),
+                    },
+                }
+            }
+            x => unreachable!("Unexpected rule {x:?}"),
+        }
+    }
+
+    fn var_decl(&self, pair: Pair<Rule>) -> rlt::Variable {
+        let mut pairs = pair.into_inner();
+        let keyword = next(&mut pairs);
+        let mutable = keyword.as_rule() == Rule::VAR;
+        let keyword = self.keyword(keyword);
+        let id = self.identifier(next(&mut pairs));
+        let assigned_type = pairs.next().map(|it| self.return_type(it));
+        match mutable {
+            false => rlt::Variable::Immutable {
+                keyword,
+                id,
+                assigned_type,
+            },
+            true => rlt::Variable::Mutable {
+                keyword,
+                id,
+                assigned_type,
+            },
+        }
+    }
+
+    fn block_level(&self, pair: Pair<Rule>) -> rlt::BlockLevelNode {
+        let pair = inner(pair);
+        match pair.as_rule() {
+            Rule::block => rlt::BlockLevelNode::Block(self.block(pair)),
+            Rule::init_var => {
+                let mut pairs = pair.into_inner();
+                rlt::BlockLevelNode::InitVar(rlt::InitializedVariable {
+                    variable: self.var_decl(next(&mut pairs)),
+                    equals: self.symbol(next(&mut pairs)),
+                    expression: self.operator(next(&mut pairs)),
+                })
+            }
+            Rule::bodied => rlt::BlockLevelNode::Function(self.bodied(pair)),
+            Rule::operator => rlt::BlockLevelNode::Operation(self.operator(pair)),
+            x => unreachable!("Unexpected rule {x:?}"),
+        }
+    }
+
+    // Functions grammar
+    // ---------------------------------------------------------------------------------------------
+
+    fn bodied(&self, pair: Pair<Rule>) -> rlt::BodiedFunction {
+        let mut pairs = pair.into_inner().peekable();
+        let keyword = self.keyword(next(&mut pairs));
+        let id = self.identifier(next(&mut pairs));
+        let generics = next_if(&mut pairs, Rule::generics).map(|it| self.generics(it));
+        let params =
+            next_if(&mut pairs, Rule::parameters).map(|it| self.enclosed(it, Self::parameter));
+        let return_type = next_if(&mut pairs, Rule::return_type).map(|it| self.return_type(it));
+        rlt::BodiedFunction {
+            keyword,
+            generics: generics.map(|it| it.into()),
+            params: params.map(|it| it.into()),
+            id,
+            return_type,
+            body: Box::new(self.body(next(&mut pairs))),
+        }
+    }
+
+    fn foreign(&self, pair: Pair<Rule>) -> rlt::ForeignFunction {
+        let mut pairs = pair.into_inner().peekable();
+        let foreign = self.keyword(next(&mut pairs));
+        let keyword = self.keyword(next(&mut pairs));
+        let id = self.identifier(next(&mut pairs));
+        let generics = next_if(&mut pairs, Rule::generics).map(|it| self.generics(it));
+        let params = next_if(&mut pairs, Rule::typed_parameters)
+            .map(|it| self.enclosed(it, Self::typed_parameter));
+        let return_type = next_if(&mut pairs, Rule::return_type).map(|it| self.return_type(it));
+        rlt::ForeignFunction {
+            keyword: foreign,
+            function: rlt::AbstractFunction {
+                keyword,
+                id,
+                generics: generics.map(|it| it.into()),
+                params: params.map(|it| it.into()),
+                return_type,
+            },
+        }
+    }
+
+    // Top level grammar
+    // ---------------------------------------------------------------------------------------------
+
+    fn enum_variant(&self, pair: Pair<Rule>) -> rlt::EnumVariant {
+        let mut pairs = pair.into_inner();
+        let id = self.type_name(next(&mut pairs));
+        let payload = pairs.next().map(|it| match it.as_rule() {
+            Rule::named_payload => {
+                rlt::VariantPayload::Named(self.enclosed(it, Self::typed_parameter).into())
+            }
+            Rule::positional_payload => {
+                rlt::VariantPayload::Positional(self.enclosed(it, Self::type_grammar).into())
+            }
+            x => unreachable!("Unexpected rule {x:?}"),
+        });
+        rlt::EnumVariant { id, payload }
+    }
+
+    fn enum_statement(&self, pair: Pair<Rule>) -> rlt::Enum {
+        let mut pairs = pair.into_inner().peekable();
+        let keyword = next(&mut pairs);
+        let heap = keyword.as_rule() == Rule::enum_heap;
+        let keyword = self.keyword(keyword);
+        let id = self.type_name(next(&mut pairs));
+        let generics = next_if(&mut pairs, Rule::generics).map(|it| self.generics(it).into());
+        let contents = next(&mut pairs);
+        let contents = contents
+            .clone()
+            .into_inner()
+            .next()
+            .map(|_| self.enclosed(contents, Self::enum_variant).into());
+        match heap {
+            false => rlt::Enum::Stack {
+                keyword,
+                id,
+                generics,
+                contents,
+            },
+            true => rlt::Enum::Heap {
+                keyword,
+                id,
+                generics,
+                contents,
+            },
+        }
+    }
+
+    fn struct_statement(&self, pair: Pair<Rule>) -> rlt::Struct {
+        let mut pairs = pair.into_inner().peekable();
+        let keyword = self.keyword(next(&mut pairs));
+        let id = self.type_name(next(&mut pairs));
+        let generics = next_if(&mut pairs, Rule::generics).map(|it| self.generics(it));
+        let parameters = next_if(&mut pairs, Rule::typed_parameters)
+            .map(|it| self.enclosed(it, Self::typed_parameter));
+        let body = next_if(&mut pairs, Rule::struct_body).map(|it| self.enclosed(it, Self::bodied));
+        rlt::Struct {
+            keyword,
+            id,
+            generics: generics.map(|it| it.into()),
+            parameters: parameters.map(|it| it.into()),
+            body: body.map(|it| it.into()),
+        }
+    }
+
+    fn top_level(&self, pair: Pair<Rule>) -> rlt::TopLevelNode {
+        let pair = inner(pair);
+        match pair.as_rule() {
+            Rule::enum_statement => rlt::TopLevelNode::Enum(self.enum_statement(pair)),
+            Rule::struct_statement => rlt::TopLevelNode::Struct(self.struct_statement(pair)),
+            Rule::type_alias => {
+                let mut pairs = pair.into_inner();
+                rlt::TopLevelNode::TypeAlias(rlt::TypeAlias {
+                    keyword: self.keyword(next(&mut pairs)),
+                    id: self.type_name(next(&mut pairs)),
+                    equals: self.symbol(next(&mut pairs)),
+                    aliased: self.type_grammar(next(&mut pairs)),
+                })
+            }
+            Rule::foreign => rlt::TopLevelNode::ForeignFunction(self.foreign(pair)),
+            Rule::bodied => rlt::TopLevelNode::BodiedFunction(self.bodied(pair)),
+            x => unreachable!("Unexpected rule {x:?}"),
+        }
+    }
+
+    /// Parses skipped tokens again along with the token following them to find out the error
+    fn report(&mut self, pair: Pair<Rule>) -> rlt::TopLevelNode {
+        let span = pair.as_span();
+        let end = self.input[span.end()..]
+            .iter()
+            .position(|it| !it.token.is_ignored())
+            .map_or(self.input.len(), |it| span.end() + it + 1);
+        let declaration = self.input.sub_stream(span.start()..end);
+        if let Err(e) = Grammar::parse(Rule::declaration, &self.encoded[span.start()..end]) {
+            self.errors.push(locate(e, &declaration));
+        }
+        rlt::TopLevelNode::Error(Erroneous::from_located(self.point(&pair)))
+    }
+
+    fn top_levels<'i>(
+        &mut self,
+        pairs: impl Iterator<Item = Pair<'i, Rule>>,
+    ) -> Box<[rlt::TopLevelNode]> {
+        pairs
+            .map(|it| match it.as_rule() {
+                Rule::skipped => self.report(it),
+                _ => self.top_level(it),
+            })
+            .collect()
+    }
+
+    // Modules grammar
+    // ---------------------------------------------------------------------------------------------
+
+    fn module(&mut self, pair: Pair<Rule>) -> rlt::Module {
+        let rule = pair.as_rule();
+        let mut pairs: Vec<_> = pair.into_inner().collect();
+        let (keyword, id, opening) = (pairs.remove(0), pairs.remove(0), pairs.remove(0));
+        let keyword = self.keyword(keyword);
+        let id = self.type_name(id);
+        match rule {
+            Rule::module => {
+                let Some(rbrace) = pairs.pop() else {
+                    unreachable!("Module ends with a brace")
+                };
+                rlt::Module::Ordinary {
+                    keyword,
+                    id,
+                    lbrace: self.symbol(opening),
+                    rbrace: self.symbol(rbrace),
+                    rest: self.top_levels(pairs.into_iter()),
+                }
+            }
+            Rule::global_module => rlt::Module::Global {
+                keyword,
+                id,
+                flow: self.symbol(opening),
+                rest: self.top_levels(pairs.into_iter()),
+            },
+            x => unreachable!("Unexpected rule {x:?}"),
+        }
+    }
+
+    fn kodept(&mut self, mut pairs: Pairs<Rule>) -> RLT {
+        let file = inner(next(&mut pairs));
+        let modules = file.into_inner().map(|it| self.module(it)).collect();
+        RLT(rlt::File::new(modules))
+    }
+
+    fn parse(input: &PackedTokenStream<'t>) -> Result<(RLT, Vec<SyntaxError>), SyntaxError> {
+        let encoded = encode(input);
+        let pairs = Grammar::parse(Rule::kodept, &encoded).map_err(|e| locate(e, input))?;
+        let mut builder = Builder::new(*input, &encoded);
+        let rlt = builder.kodept(pairs);
+        Ok((rlt, builder.errors))
+    }
+}
+
+fn next<'i>(pairs: &mut impl Iterator<Item = Pair<'i, Rule>>) -> Pair<'i, Rule> {
+    pairs
+        .next()
+        .expect("Grammar guarantees presence of the pair")
+}
+
+fn next_if<'i, I>(pairs: &mut std::iter::Peekable<I>, rule: Rule) -> Option<Pair<'i, Rule>>
+where
+    I: Iterator<Item = Pair<'i, Rule>>,
+{
+    pairs.next_if(|it| it.as_rule() == rule)
+}
+
+fn inner(pair: Pair<Rule>) -> Pair<Rule> {
+    next(&mut pair.into_inner())
+}
+
+#[derive(Constructor, Debug)]
+pub struct Parser;
+
+impl RLTProducer for Parser {
+    type Error<'t> = SyntaxError;
+
+    fn parse_stream<'t>(&self, input: &PackedTokenStream<'t>) -> Result<RLT, Self::Error<'t>> {
+        let (rlt, errors) = self.parse_recovering(input)?;
+        match errors.into_iter().next() {
+            None => Ok(rlt),
+            Some(e) => Err(e),
+        }
+    }
+}
+
+impl RecoveringRLTProducer for Parser {
+    fn parse_recovering<'t>(
+        &self,
+        input: &PackedTokenStream<'t>,
+    ) -> Result<(RLT, Vec<Self::Error<'t>>), Self::Error<'t>> {
+        Builder::parse(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pest::Parser as _;
+
+    use super::{terminal, Grammar, Rule};
+
+    #[test]
+    fn test_terminals_match_their_tokens() {
+        for rule in Rule::all_rules() {
+            let Some(token) = terminal(*rule) else {
+                continue;
+            };
+            let input = (token as u8 as char).to_string();
+            assert!(
+                Grammar::parse(*rule, &input).is_ok(),
+                "{rule:?} does not match {token:?}"
+            );
+        }
+    }
+}
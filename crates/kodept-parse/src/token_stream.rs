@@ -15,14 +15,12 @@ pub struct PackedTokenStream<'t> {
 static_assert_size!(PackedTokenStream<'static>, 16);
 
 pub struct PackedTokenStreamIter<'t> {
-    slice_iter: std::slice::Iter<'t, PackedTokenMatch>
+    slice_iter: std::slice::Iter<'t, PackedTokenMatch>,
 }
 
 impl<'t> PackedTokenStream<'t> {
     pub fn new(slice: &'t [PackedTokenMatch]) -> Self {
-        Self {
-            slice,
-        }
+        Self { slice }
     }
 
     pub fn len(&self) -> usize {
@@ -79,8 +77,9 @@ impl<'t> InputIter for PackedTokenStream<'t> {
 
     fn iter_indices(&self) -> Self::Iter {
         PackedTokenStreamIter {
-            slice_iter: self.slice.iter()
-        }.enumerate()
+            slice_iter: self.slice.iter(),
+        }
+        .enumerate()
     }
 
     fn iter_elements(&self) -> Self::IterElem {
@@ -149,6 +148,7 @@ impl Offset for PackedTokenStream<'_> {
         let fst = self.as_ptr();
         let snd = second.as_ptr();
 
-        snd as usize - fst as usize
+        // Offset is measured in tokens, not in bytes
+        (snd as usize - fst as usize) / size_of::<PackedTokenMatch>()
     }
 }
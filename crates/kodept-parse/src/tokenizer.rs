@@ -34,13 +34,19 @@ mod lazy {
     use super::{Tok, TokCtor};
     use crate::common::{ErrorAdapter, TokenProducer};
     use crate::error::{Original, ParseErrors};
+    use crate::interpolation::split_interpolations;
+    use crate::lexer::PackedToken;
     use crate::token_match::PackedTokenMatch;
+    use std::collections::VecDeque;
     use std::iter::FusedIterator;
+    use std::ops::Range;
 
     pub struct Tokenizer<'t, F> {
         buffer: &'t str,
         pos: usize,
         tokenizing_fn: F,
+        // tokens of a split string literal that are not yet yielded
+        pending: VecDeque<PackedTokenMatch>,
     }
 
     impl<'t, F> Iterator for Tokenizer<'t, F>
@@ -51,6 +57,10 @@ mod lazy {
 
         #[inline]
         fn next(&mut self) -> Option<Self::Item> {
+            if let Some(token_match) = self.pending.pop_front() {
+                return Some(Ok(token_match));
+            }
+
             let slice = &self.buffer[self.pos..];
             if slice.is_empty() {
                 return None;
@@ -64,6 +74,27 @@ mod lazy {
             token_match.point.offset = self.pos as u32;
             self.pos += token_match.point.length as usize;
 
+            if token_match.token == PackedToken::String {
+                let buffer = self.buffer;
+                let lexer = &self.tokenizing_fn;
+                let mut lex = |range: Range<usize>| {
+                    let mut tokens = vec![];
+                    let mut pos = range.start;
+                    while pos < range.end {
+                        let mut token_match = lexer.parse_string(buffer, pos)?;
+                        token_match.point.offset = pos as u32;
+                        pos += token_match.point.length as usize;
+                        tokens.push(token_match);
+                    }
+                    Ok(tokens)
+                };
+                match split_interpolations(buffer, vec![token_match], &mut lex) {
+                    Ok(tokens) => self.pending.extend(tokens),
+                    Err(e) => return Some(Err(e)),
+                }
+                return self.pending.pop_front().map(Ok);
+            }
+
             Some(Ok(token_match))
         }
     }
@@ -100,6 +131,7 @@ mod lazy {
                 buffer: input,
                 pos: 0,
                 tokenizing_fn: lexer,
+                pending: VecDeque::new(),
             }
         }
     }
@@ -109,9 +141,11 @@ mod eager {
     use super::{Tok, TokCtor};
     use crate::common::{EagerTokensProducer, ErrorAdapter};
     use crate::error::{Original, ParseErrors};
+    use crate::interpolation::split_interpolations;
     use crate::token_match::PackedTokenMatch;
     use std::fmt::Debug;
     use std::marker::PhantomData;
+    use std::ops::Range;
 
     #[derive(Debug)]
     pub struct Tokenizer<'t, E, F> {
@@ -145,7 +179,16 @@ mod eager {
         F: EagerTokensProducer,
     {
         fn new(input: &'t str, lexer: F) -> Self {
-            let tokens = lexer.parse_string(input);
+            let mut lex = |range: Range<usize>| {
+                let mut tokens = lexer.parse_string(&input[range.clone()])?;
+                for token_match in &mut tokens {
+                    token_match.point.offset += range.start as u32;
+                }
+                Ok(tokens)
+            };
+            let tokens = lexer
+                .parse_string(input)
+                .and_then(|it| split_interpolations(input, it, &mut lex));
             Self {
                 input,
                 result: tokens,
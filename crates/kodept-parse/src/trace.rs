@@ -0,0 +1,486 @@
+use std::fmt::Write;
+
+use derive_more::Display;
+
+/// Human-readable position of a traced rule
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Display)]
+#[display("{line}:{column}")]
+pub struct LineCol {
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventKind<P = LineCol> {
+    Attempt,
+    Match { end: P },
+    Fail,
+    CachedMatch,
+    CachedFail,
+}
+
+/// Single step of a parser: rule `rule` was entered or left at `position`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceEvent<P = LineCol> {
+    pub rule: String,
+    pub position: P,
+    pub kind: EventKind<P>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Matched(LineCol),
+    Failed,
+    CachedMatch,
+    CachedFail,
+    /// Trace ended before the rule has finished
+    Unfinished,
+}
+
+/// Attempt to match rule along with attempts of its subrules
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceNode {
+    pub rule: String,
+    pub start: LineCol,
+    pub outcome: Outcome,
+    pub children: Vec<TraceNode>,
+}
+
+/// Events emitted while parsing one input
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Trace {
+    pub events: Vec<TraceEvent>,
+}
+
+impl From<peg::str::LineCol> for LineCol {
+    fn from(value: peg::str::LineCol) -> Self {
+        Self {
+            line: value.line,
+            column: value.column,
+        }
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '&' => result.push_str("&amp;"),
+            '"' => result.push_str("&quot;"),
+            _ => result.push(ch),
+        }
+    }
+    result
+}
+
+const HTML_STYLE: &str = "\
+body { font-family: monospace; }
+details, .leaf { margin-left: 1.5em; }
+summary { cursor: pointer; }
+.matched { color: #1a7f37; }
+.failed { color: #cf222e; }
+.cached { color: #6e7781; }
+.unfinished { color: #9a6700; }
+pre { background: #f6f8fa; padding: 1em; overflow: auto; }
+";
+
+impl Outcome {
+    fn describe(&self, start: LineCol) -> String {
+        match self {
+            Outcome::Matched(end) => format!("matched {start}..{end}"),
+            Outcome::Failed => format!("failed at {start}"),
+            Outcome::CachedMatch => format!("cached match at {start}"),
+            Outcome::CachedFail => format!("cached fail at {start}"),
+            Outcome::Unfinished => format!("unfinished at {start}"),
+        }
+    }
+
+    fn class(&self) -> &'static str {
+        match self {
+            Outcome::Matched(_) => "matched",
+            Outcome::Failed => "failed",
+            Outcome::CachedMatch | Outcome::CachedFail => "cached",
+            Outcome::Unfinished => "unfinished",
+        }
+    }
+}
+
+impl TraceNode {
+    fn render_text(&self, depth: usize, output: &mut String) {
+        let _ = writeln!(
+            output,
+            "{:indent$}{} {}",
+            "",
+            self.rule,
+            self.outcome.describe(self.start),
+            indent = depth * 2
+        );
+        for child in &self.children {
+            child.render_text(depth + 1, output);
+        }
+    }
+
+    fn render_html(&self, output: &mut String) {
+        let summary = format!(
+            "<span class=\"{}\">{}</span> {}",
+            self.outcome.class(),
+            escape_html(&self.rule),
+            self.outcome.describe(self.start)
+        );
+        if self.children.is_empty() {
+            let _ = writeln!(output, "<div class=\"leaf\">{summary}</div>");
+            return;
+        }
+        let _ = writeln!(output, "<details><summary>{summary}</summary>");
+        for child in &self.children {
+            child.render_html(output);
+        }
+        output.push_str("</details>\n");
+    }
+}
+
+impl Trace {
+    /// Pairs attempts with their results. Every top level attempt becomes a root.
+    pub fn tree(&self) -> Vec<TraceNode> {
+        fn attach(node: TraceNode, stack: &mut [TraceNode], roots: &mut Vec<TraceNode>) {
+            match stack.last_mut() {
+                None => roots.push(node),
+                Some(parent) => parent.children.push(node),
+            }
+        }
+
+        let mut roots = vec![];
+        let mut stack: Vec<TraceNode> = vec![];
+        for event in &self.events {
+            let leaf = |outcome| TraceNode {
+                rule: event.rule.clone(),
+                start: event.position,
+                outcome,
+                children: vec![],
+            };
+            let outcome = match &event.kind {
+                EventKind::Attempt => {
+                    stack.push(leaf(Outcome::Unfinished));
+                    continue;
+                }
+                EventKind::CachedMatch => {
+                    attach(leaf(Outcome::CachedMatch), &mut stack, &mut roots);
+                    continue;
+                }
+                EventKind::CachedFail => {
+                    attach(leaf(Outcome::CachedFail), &mut stack, &mut roots);
+                    continue;
+                }
+                EventKind::Match { end } => Outcome::Matched(*end),
+                EventKind::Fail => Outcome::Failed,
+            };
+            // Unbalanced events are left unfinished
+            while let Some(mut node) = stack.pop() {
+                let finished = node.rule == event.rule;
+                if finished {
+                    node.outcome = outcome;
+                }
+                attach(node, &mut stack, &mut roots);
+                if finished {
+                    break;
+                }
+            }
+        }
+        while let Some(node) = stack.pop() {
+            attach(node, &mut stack, &mut roots);
+        }
+        roots
+    }
+
+    /// Renders trace as an indented tree of rules
+    pub fn render_text(&self) -> String {
+        let mut output = String::new();
+        for root in self.tree() {
+            root.render_text(0, &mut output);
+        }
+        output
+    }
+
+    /// Renders trace as a self-contained html page with collapsible rules and the traced `source`
+    pub fn render_html(&self, title: &str, source: &str) -> String {
+        let title = escape_html(title);
+        let mut output = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>{title}</title>\n<style>\n{HTML_STYLE}</style>\n</head>\n<body>\n\
+             <h1>{title}</h1>\n<pre>"
+        );
+        for (number, line) in source.lines().enumerate() {
+            let _ = writeln!(output, "{:>4} | {}", number + 1, escape_html(line));
+        }
+        output.push_str("</pre>\n");
+        for root in self.tree() {
+            root.render_html(&mut output);
+        }
+        output.push_str("</body>\n</html>\n");
+        output
+    }
+}
+
+#[cfg(feature = "trace")]
+pub use collect::{collect_nom, collect_peg};
+#[cfg(feature = "trace")]
+pub(crate) use collect::{nom_enter, nom_exit, peg_event};
+
+/// Runs `f`, which uses `peg` lexers or parsers, recording their events only if `TRACE` is set
+pub(crate) fn traced_if<const TRACE: bool, T>(f: impl FnOnce() -> T) -> T {
+    #[cfg(feature = "trace")]
+    if !TRACE {
+        return collect::untraced(f);
+    }
+    f()
+}
+
+#[cfg(feature = "trace")]
+mod collect {
+    use std::cell::RefCell;
+
+    use crate::token_stream::PackedTokenStream;
+    use crate::trace::{EventKind, LineCol, Trace, TraceEvent};
+
+    type RawEvent = TraceEvent<Option<u32>>;
+
+    thread_local! {
+        static NOM_EVENTS: RefCell<Option<Vec<RawEvent>>> = const { RefCell::new(None) };
+        static PEG_EVENTS: RefCell<Option<Vec<TraceEvent>>> = const { RefCell::new(None) };
+    }
+
+    /// Shortens full path of a parser function to its module and name
+    fn rule_name(path: &str) -> String {
+        let path = path.trim_end_matches("::{{closure}}");
+        let name_start = path.rmatch_indices("::").nth(1).map_or(0, |(it, _)| it + 2);
+        path[name_start..].to_string()
+    }
+
+    fn offset(input: &PackedTokenStream) -> Option<u32> {
+        input.first().map(|it| it.point.offset)
+    }
+
+    fn record(event: impl FnOnce() -> RawEvent) {
+        NOM_EVENTS.with_borrow_mut(|events| {
+            if let Some(events) = events {
+                events.push(event())
+            }
+        })
+    }
+
+    pub(crate) fn nom_enter(rule: &str, input: &PackedTokenStream) {
+        record(|| TraceEvent {
+            rule: rule_name(rule),
+            position: offset(input),
+            kind: EventKind::Attempt,
+        })
+    }
+
+    pub(crate) fn nom_exit(
+        rule: &str,
+        input: &PackedTokenStream,
+        rest: Option<&PackedTokenStream>,
+    ) {
+        record(|| TraceEvent {
+            rule: rule_name(rule),
+            position: offset(input),
+            kind: match rest {
+                None => EventKind::Fail,
+                Some(rest) => EventKind::Match { end: offset(rest) },
+            },
+        })
+    }
+
+    fn line_col(source: &str, offset: Option<u32>) -> LineCol {
+        let offset = offset.map_or(source.len(), |it| it as usize);
+        let before = source.get(..offset).unwrap_or(source);
+        let line_start = before.rfind('\n').map_or(0, |it| it + 1);
+        LineCol {
+            line: before.matches('\n').count() + 1,
+            column: offset - line_start + 1,
+        }
+    }
+
+    /// Receives events of `peg` rules, see `crate::peg::trace_hook`
+    pub(crate) fn peg_event(rule: &str, position: impl Into<LineCol>, kind: EventKind) {
+        PEG_EVENTS.with_borrow_mut(|events| {
+            if let Some(events) = events {
+                events.push(TraceEvent {
+                    rule: rule.to_string(),
+                    position: position.into(),
+                    kind,
+                })
+            }
+        })
+    }
+
+    /// Runs `f` without recording events of `peg` rules
+    pub(super) fn untraced<T>(f: impl FnOnce() -> T) -> T {
+        let events = PEG_EVENTS.take();
+        let result = f();
+        PEG_EVENTS.set(events);
+        result
+    }
+
+    /// Runs `f` collecting events of the `peg` lexers and parsers with enabled tracing
+    pub fn collect_peg<T>(f: impl FnOnce() -> T) -> (T, Trace) {
+        PEG_EVENTS.set(Some(vec![]));
+        let result = f();
+        let events = PEG_EVENTS.take().unwrap_or_default();
+        (result, Trace { events })
+    }
+
+    /// Runs `f` collecting events of the `nom` parser.
+    /// Tokens are located in `source` the parsed stream was produced from.
+    pub fn collect_nom<T>(source: &str, f: impl FnOnce() -> T) -> (T, Trace) {
+        NOM_EVENTS.set(Some(vec![]));
+        let result = f();
+        let events = NOM_EVENTS.take().unwrap_or_default();
+        let events = events
+            .into_iter()
+            .map(|it| TraceEvent {
+                rule: it.rule,
+                position: line_col(source, it.position),
+                kind: match it.kind {
+                    EventKind::Attempt => EventKind::Attempt,
+                    EventKind::Match { end } => EventKind::Match {
+                        end: line_col(source, end),
+                    },
+                    EventKind::Fail => EventKind::Fail,
+                    EventKind::CachedMatch => EventKind::CachedMatch,
+                    EventKind::CachedFail => EventKind::CachedFail,
+                },
+            })
+            .collect();
+        (result, Trace { events })
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use crate::trace::{EventKind, LineCol, Outcome, Trace, TraceEvent};
+
+    fn at(line: usize, column: usize) -> LineCol {
+        LineCol { line, column }
+    }
+
+    fn event(rule: &str, position: LineCol, kind: EventKind) -> TraceEvent {
+        TraceEvent {
+            rule: rule.to_string(),
+            position,
+            kind,
+        }
+    }
+
+    fn trace() -> Trace {
+        Trace {
+            events: vec![
+                event("module", at(1, 1), EventKind::Attempt),
+                event("keyword", at(1, 1), EventKind::Attempt),
+                event("keyword", at(1, 1), EventKind::Match { end: at(1, 7) }),
+                event("type_ident", at(1, 8), EventKind::CachedFail),
+                event("module", at(1, 1), EventKind::Fail),
+                event("global_module", at(1, 1), EventKind::Attempt),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_events_form_tree() {
+        let tree = trace().tree();
+
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree[0].rule, "module");
+        assert_eq!(tree[0].outcome, Outcome::Failed);
+        assert_eq!(
+            tree[0].children[0].outcome,
+            Outcome::Matched(LineCol { line: 1, column: 7 })
+        );
+        assert_eq!(tree[0].children[1].outcome, Outcome::CachedFail);
+        assert_eq!(tree[1].outcome, Outcome::Unfinished);
+    }
+
+    #[test]
+    fn test_render_text() {
+        let text = trace().render_text();
+
+        similar_asserts::assert_eq!(
+            text,
+            "\
+module failed at 1:1
+  keyword matched 1:1..1:7
+  type_ident cached fail at 1:8
+global_module unfinished at 1:1
+"
+        );
+    }
+
+    #[test]
+    fn test_render_html_escapes_source() {
+        let html = trace().render_html("a.kd", "a < b");
+
+        assert!(html.contains("a &lt; b"));
+        assert!(html.contains("<details><summary><span class=\"failed\">module</span>"));
+    }
+}
+
+#[cfg(all(test, feature = "trace"))]
+#[allow(clippy::unwrap_used)]
+mod collect_tests {
+    use crate::common::RLTProducer;
+    use crate::lexer::PegLexer;
+    use crate::parser::{NomParser, PegParser};
+    use crate::token_stream::PackedTokenStream;
+    use crate::tokenizer::{LazyTokenizer, Tok, TokCtor};
+    use crate::trace::{collect_nom, collect_peg, LineCol, Outcome};
+
+    const SOURCE: &str = "module A {\n  fun f => 1\n}";
+
+    #[test]
+    fn test_collect_nom() {
+        let tokens = LazyTokenizer::new(SOURCE, PegLexer::<false>::new()).into_vec();
+        let stream = PackedTokenStream::new(&tokens);
+
+        let (rlt, trace) = collect_nom(SOURCE, || NomParser::new().parse_stream(&stream));
+        let text = trace.render_text();
+
+        assert!(rlt.is_ok());
+        assert!(trace
+            .tree()
+            .iter()
+            .all(|it| it.outcome != Outcome::Unfinished));
+        assert!(text.starts_with("file::grammar matched 1:1..3:2"), "{text}");
+        assert!(
+            text.contains("  function::bodied matched 1:11..2:13"),
+            "{text}"
+        );
+        // Events are recorded only while collecting
+        NomParser::new().parse_stream(&stream).unwrap();
+        assert_eq!(collect_nom(SOURCE, || ()).1.events, []);
+    }
+
+    #[test]
+    fn test_collect_peg() {
+        let ((tokens, rlt), trace) = collect_peg(|| {
+            let tokens = LazyTokenizer::new(SOURCE, PegLexer::<false>::new()).into_vec();
+            let rlt = PegParser::<true>::new().parse_stream(&PackedTokenStream::new(&tokens));
+            (tokens, rlt)
+        });
+        let tree = trace.tree();
+
+        assert!(rlt.is_ok());
+        // Untraced lexer records nothing
+        assert_eq!(tree.len(), 1);
+        assert_eq!(tree[0].rule, "kodept");
+        assert_eq!(tree[0].start, LineCol { line: 1, column: 1 });
+        assert!(matches!(tree[0].outcome, Outcome::Matched(_)));
+
+        let (_, trace) =
+            collect_peg(|| LazyTokenizer::new(SOURCE, PegLexer::<true>::new()).into_vec());
+        assert_eq!(trace.tree().len(), tokens.len());
+        assert!(trace.tree().iter().all(|it| it.rule == "token"));
+    }
+}
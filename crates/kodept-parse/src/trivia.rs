@@ -0,0 +1,183 @@
+use kodept_core::code_point::CodePoint;
+use kodept_core::structure::Located;
+
+use crate::lexer::PackedToken;
+use crate::token_match::PackedTokenMatch;
+
+/// Significant token along with comments and whitespace around it
+#[derive(Debug, Clone, PartialEq)]
+pub struct TriviaToken {
+    /// Trivia on the lines before the token, as well as its indentation
+    pub leading: Box<[PackedTokenMatch]>,
+    pub token: PackedTokenMatch,
+    /// Trivia after the token up to the end of its line
+    pub trailing: Box<[PackedTokenMatch]>,
+}
+
+/// Lossless view of a token stream: every significant token keeps the trivia around it,
+/// so the source can be reproduced byte-for-byte while RLT nodes are still addressed by location
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LosslessTokens {
+    tokens: Vec<TriviaToken>,
+    /// Trivia after the last significant token
+    rest: Box<[PackedTokenMatch]>,
+}
+
+/// Returns the length of the first line in `trivia`, including the line break.
+/// Depending on the lexer, line comments may or may not include the line break.
+fn line_length(trivia: &[PackedTokenMatch]) -> Option<usize> {
+    let end = trivia
+        .iter()
+        .position(|it| matches!(it.token, PackedToken::Newline | PackedToken::Comment))?;
+    match (trivia[end].token, trivia.get(end + 1)) {
+        (PackedToken::Comment, Some(next)) if next.token == PackedToken::Newline => Some(end + 2),
+        _ => Some(end + 1),
+    }
+}
+
+/// Splits `trivia` into lines. The last one is unfinished (possibly empty).
+fn lines(mut trivia: &[PackedTokenMatch]) -> (Vec<&[PackedTokenMatch]>, &[PackedTokenMatch]) {
+    let mut result = vec![];
+    while let Some(length) = line_length(trivia) {
+        let (line, rest) = trivia.split_at(length);
+        result.push(line);
+        trivia = rest;
+    }
+    (result, trivia)
+}
+
+fn is_comment(token: &PackedTokenMatch) -> bool {
+    matches!(
+        token.token,
+        PackedToken::Comment | PackedToken::MultilineComment
+    )
+}
+
+impl LosslessTokens {
+    pub fn new(tokens: &[PackedTokenMatch]) -> Self {
+        let mut result = Self::default();
+        let mut trivia = vec![];
+        for token in tokens {
+            if token.token.is_ignored() {
+                trivia.push(*token);
+                continue;
+            }
+            // Trivia up to the first line break belongs to the previous token
+            if let Some(last) = result.tokens.last_mut() {
+                let split = line_length(&trivia).unwrap_or(trivia.len());
+                last.trailing = trivia.drain(..split).collect();
+            }
+            result.tokens.push(TriviaToken {
+                leading: std::mem::take(&mut trivia).into_boxed_slice(),
+                token: *token,
+                trailing: Box::new([]),
+            });
+        }
+        if let Some(last) = result.tokens.last_mut() {
+            let split = line_length(&trivia).unwrap_or(trivia.len());
+            last.trailing = trivia.drain(..split).collect();
+        }
+        result.rest = trivia.into_boxed_slice();
+        result
+    }
+
+    /// Tokens with trivia in source order
+    pub fn tokens(&self) -> &[TriviaToken] {
+        &self.tokens
+    }
+
+    /// All tokens, including trivia, in source order
+    pub fn iter(&self) -> impl Iterator<Item = &PackedTokenMatch> {
+        self.tokens
+            .iter()
+            .flat_map(|it| {
+                it.leading
+                    .iter()
+                    .chain([&it.token])
+                    .chain(it.trailing.iter())
+            })
+            .chain(self.rest.iter())
+    }
+
+    /// Significant token starting at the location of `item`, e.g. RLT node
+    pub fn get(&self, item: &impl Located) -> Option<&TriviaToken> {
+        let offset = item.location().offset;
+        self.tokens
+            .binary_search_by_key(&offset, |it| it.token.point.offset)
+            .ok()
+            .map(|index| &self.tokens[index])
+    }
+
+    /// Comments on the lines right above `item`, not separated from it by a blank line
+    pub fn doc_comments(&self, item: &impl Located) -> Vec<CodePoint> {
+        let Some(token) = self.get(item) else {
+            return vec![];
+        };
+
+        let (above, own_line) = lines(&token.leading);
+        let mut result: Vec<_> = above
+            .into_iter()
+            .rev()
+            .take_while(|line| line.iter().any(is_comment))
+            .flat_map(|line| line.iter().rev())
+            .filter(|it| is_comment(it))
+            .map(|it| it.point)
+            .collect();
+        result.reverse();
+        result.extend(
+            own_line
+                .iter()
+                .filter(|it| is_comment(it))
+                .map(|it| it.point),
+        );
+        result
+    }
+
+    /// Reproduces the source these tokens were produced from
+    pub fn to_source(&self, input: &str) -> String {
+        self.iter().map(|it| &input[it.point.as_range()]).collect()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use kodept_core::code_point::CodePoint;
+    use rstest::rstest;
+
+    use super::LosslessTokens;
+    use crate::lexer::PegLexer;
+    use crate::tokenizer::{LazyTokenizer, Tok, TokCtor};
+
+    fn lossless(input: &str) -> LosslessTokens {
+        let tokens = LazyTokenizer::new(input, PegLexer::<false>::new()).into_vec();
+        LosslessTokens::new(&tokens)
+    }
+
+    #[rstest]
+    #[case::empty("")]
+    #[case::only_trivia("  // comment\n\n")]
+    #[case::declarations("module A =>\n  fun f() => 1 // one\n\n  /* two */ fun g() => 2\n")]
+    #[case::interpolation("module A =>\n  fun f(x) => \"${ x }!\"  \n")]
+    fn test_to_source(#[case] input: &str) {
+        assert_eq!(lossless(input).to_source(input), input);
+    }
+
+    #[rstest]
+    #[case::line_comments("module A =>\n// a\n  // b\n  fun f() => 1", &["// a", "// b"])]
+    #[case::block_comment("module A =>\n/* a */\nfun f() => 1", &["/* a */"])]
+    #[case::blank_line("module A =>\n// a\n\n// b\nfun f() => 1", &["// b"])]
+    #[case::trailing_comment("module A => fun g() => 2 // a\nfun f() => 1", &[])]
+    #[case::same_line("module A =>\n// a\n/* b */ fun f() => 1", &["// a", "/* b */"])]
+    #[case::none("module A =>\nfun f() => 1", &[])]
+    fn test_doc_comments(#[case] input: &str, #[case] expected: &[&str]) {
+        let tokens = lossless(input);
+        let offset = input.rfind("fun").unwrap() as u32;
+        let comments: Vec<_> = tokens
+            .doc_comments(&CodePoint::new(3, offset))
+            .into_iter()
+            .map(|it| input[it.as_range()].trim_end())
+            .collect();
+        assert_eq!(comments, expected);
+    }
+}
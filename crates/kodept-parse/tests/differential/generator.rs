@@ -0,0 +1,433 @@
+use fastrand::Rng;
+
+const IDENTIFIERS: &[&str] = &["a", "b", "x", "foo", "bar2", "acc", "item_1", "_tmp"];
+const TYPES: &[&str] = &["Int", "Bool", "Option", "Point", "T", "U"];
+const BINARY_OPERATORS: &[&str] = &[
+    "+", "-", "*", "/", "%", "**", "<", ">", "<=", ">=", "==", "!=", "<=>", "|", "&", "^", "||",
+    "&&", "=",
+];
+const UNARY_OPERATORS: &[&str] = &["-", "!", "~", "+"];
+const NUMBERS: &[&str] = &["0", "1", "42", "3.14", "1e10", "0b101", "0c17", "0xFF"];
+const CHARS: &[&str] = &["'a'", "'\\n'", "'\\''", "'\\u{41}'"];
+const STRINGS: &[&str] = &[
+    "\"\"",
+    "\"hello\"",
+    "\"with \\\"quotes\\\"\"",
+    "\"{braces}\"",
+];
+const TRIVIA: &[&str] = &[" ", "  ", "\t", " /* note */ ", " /* multi\nline */ "];
+const MAX_DEPTH: usize = 4;
+
+/// Produces random programs following Kodept grammar
+pub(crate) struct Generator {
+    rng: Rng,
+    depth: usize,
+}
+
+impl Generator {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self {
+            rng: Rng::with_seed(seed),
+            depth: 0,
+        }
+    }
+
+    fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+        items[self.rng.usize(..items.len())]
+    }
+
+    fn chance(&mut self, percent: u8) -> bool {
+        self.rng.u8(..100) < percent
+    }
+
+    fn ident(&mut self) -> &'static str {
+        self.pick(IDENTIFIERS)
+    }
+
+    fn type_name(&mut self) -> &'static str {
+        self.pick(TYPES)
+    }
+
+    /// Space between tokens, sometimes with a comment
+    fn gap(&mut self) -> &'static str {
+        if self.chance(10) {
+            self.pick(TRIVIA)
+        } else {
+            " "
+        }
+    }
+
+    /// Line break between declarations or statements, sometimes with a comment
+    fn line_end(&mut self) -> &'static str {
+        match self.rng.u8(..10) {
+            0 => " // comment\n",
+            1 => "\n\n",
+            2 => "; ",
+            _ => "\n",
+        }
+    }
+
+    fn nested<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+        self.depth += 1;
+        let result = f(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn list(&mut self, count: usize, mut item: impl FnMut(&mut Self) -> String) -> String {
+        let items: Vec<_> = (0..count).map(|_| item(self)).collect();
+        let separator = if self.chance(20) { ",\n" } else { ", " };
+        items.join(separator)
+    }
+
+    /// Items separated by line breaks or semicolons, ending with a line break
+    fn lines(&mut self, count: usize, mut item: impl FnMut(&mut Self) -> String) -> String {
+        let mut result = String::new();
+        for index in 0..count {
+            result += &item(self);
+            result += if index + 1 == count {
+                "\n"
+            } else {
+                self.line_end()
+            };
+        }
+        result
+    }
+
+    pub(crate) fn program(&mut self) -> String {
+        if self.chance(50) {
+            let id = self.type_name();
+            let body = self.declarations();
+            format!("module {id} =>\n{body}")
+        } else {
+            let count = self.rng.usize(1..=3);
+            let modules: Vec<_> = (0..count)
+                .map(|_| {
+                    let id = self.type_name();
+                    let body = self.declarations();
+                    format!("module {id} {{\n{body}}}")
+                })
+                .collect();
+            modules.join("\n")
+        }
+    }
+
+    fn declarations(&mut self) -> String {
+        let count = self.rng.usize(0..5);
+        self.lines(count, Self::top_level)
+    }
+
+    fn top_level(&mut self) -> String {
+        match self.rng.u8(..10) {
+            0 | 1 => self.enum_statement(),
+            2 => self.struct_statement(),
+            3 => {
+                let id = self.type_name();
+                let ty = self.type_grammar();
+                format!("type {id} = {ty}")
+            }
+            4 => {
+                let function = self.abstract_function();
+                format!("foreign {function}")
+            }
+            _ => self.bodied_function(),
+        }
+    }
+
+    fn generics(&mut self) -> String {
+        if !self.chance(30) {
+            return String::new();
+        }
+        let count = self.rng.usize(1..=2);
+        let generics = self.list(count, |this| this.type_name().to_string());
+        format!("<{generics}>")
+    }
+
+    fn type_grammar(&mut self) -> String {
+        if self.depth >= MAX_DEPTH {
+            return self.type_name().to_string();
+        }
+        self.nested(|this| match this.rng.u8(..6) {
+            0 => {
+                let count = this.rng.usize(0..3);
+                let items = this.list(count, Self::type_grammar);
+                format!("({items})")
+            }
+            1 => {
+                let id = this.type_name();
+                let count = this.rng.usize(1..3);
+                let items = this.list(count, Self::type_grammar);
+                format!("{id}<{items}>")
+            }
+            _ => this.type_name().to_string(),
+        })
+    }
+
+    fn typed_parameter(&mut self) -> String {
+        let id = self.ident();
+        let ty = self.type_grammar();
+        format!("{id}: {ty}")
+    }
+
+    fn parameter(&mut self) -> String {
+        match self.rng.u8(..3) {
+            0 => self.typed_parameter(),
+            1 => format!("{}: _", self.ident()),
+            _ => self.ident().to_string(),
+        }
+    }
+
+    fn enum_statement(&mut self) -> String {
+        let kind = if self.chance(50) { "struct" } else { "class" };
+        let id = self.type_name();
+        let generics = self.generics();
+        if self.chance(10) {
+            return format!("enum {kind} {id}{generics};");
+        }
+        let count = self.rng.usize(0..4);
+        let variants = self.list(count, |this| {
+            let id = this.type_name();
+            match this.rng.u8(..3) {
+                0 => {
+                    let count = this.rng.usize(0..3);
+                    let types = this.list(count, Self::type_grammar);
+                    format!("{id}({types})")
+                }
+                1 => {
+                    let count = this.rng.usize(0..3);
+                    let fields = this.list(count, Self::typed_parameter);
+                    format!("{id}({fields})")
+                }
+                _ => id.to_string(),
+            }
+        });
+        let trailing = if count > 0 && self.chance(30) {
+            ","
+        } else {
+            ""
+        };
+        format!("enum {kind} {id}{generics} {{ {variants}{trailing} }}")
+    }
+
+    fn struct_statement(&mut self) -> String {
+        let id = self.type_name();
+        let generics = self.generics();
+        let parameters = if self.chance(70) {
+            let count = self.rng.usize(0..3);
+            format!("({})", self.list(count, Self::typed_parameter))
+        } else {
+            String::new()
+        };
+        let body = if self.chance(50) {
+            let count = self.rng.usize(0..3);
+            let functions: String = (0..count)
+                .map(|_| format!("{}\n", self.bodied_function()))
+                .collect();
+            format!(" {{\n{functions}}}")
+        } else {
+            String::new()
+        };
+        format!("struct {id}{generics}{parameters}{body}")
+    }
+
+    fn return_type(&mut self) -> String {
+        if self.chance(40) {
+            format!(": {}", self.type_grammar())
+        } else {
+            String::new()
+        }
+    }
+
+    fn abstract_function(&mut self) -> String {
+        let id = self.ident();
+        let generics = self.generics();
+        let params = if self.chance(80) {
+            let count = self.rng.usize(0..3);
+            format!("({})", self.list(count, Self::typed_parameter))
+        } else {
+            String::new()
+        };
+        let return_type = self.return_type();
+        format!("fun {id}{generics}{params}{return_type}")
+    }
+
+    fn bodied_function(&mut self) -> String {
+        let id = self.ident();
+        let generics = self.generics();
+        let params = if self.chance(80) {
+            let count = self.rng.usize(0..3);
+            format!("({})", self.list(count, Self::parameter))
+        } else {
+            String::new()
+        };
+        let return_type = self.return_type();
+        let body = self.body();
+        format!("fun {id}{generics}{params}{return_type} {body}")
+    }
+
+    fn body(&mut self) -> String {
+        if self.chance(30) {
+            self.block()
+        } else {
+            let gap = if self.chance(20) { "\n    " } else { " " };
+            format!("=>{gap}{}", self.operation())
+        }
+    }
+
+    fn block(&mut self) -> String {
+        if self.depth >= MAX_DEPTH {
+            return "{}".to_string();
+        }
+        self.nested(|this| {
+            let count = this.rng.usize(0..4);
+            let statements = this.lines(count, Self::statement);
+            format!("{{\n{statements}}}")
+        })
+    }
+
+    fn statement(&mut self) -> String {
+        match self.rng.u8(..10) {
+            0 | 1 => {
+                let keyword = if self.chance(50) { "val" } else { "var" };
+                let id = self.ident();
+                let ty = self.return_type();
+                let value = self.operation();
+                format!("{keyword} {id}{ty} = {value}")
+            }
+            2 => self.bodied_function(),
+            _ => self.operation(),
+        }
+    }
+
+    /// Expression that is safe to put anywhere, so it does not take the rest of the input
+    fn atom(&mut self) -> String {
+        if self.depth >= MAX_DEPTH {
+            return self.simple();
+        }
+        self.nested(|this| match this.rng.u8(..12) {
+            0 => {
+                let count = this.rng.usize(0..3);
+                let items = this.list(count, Self::operation);
+                format!("({items})")
+            }
+            1 => format!("({})", this.operation()),
+            2 => {
+                let id = this.type_name();
+                let fields = this.fields();
+                format!("{id} {fields}")
+            }
+            3 => this.block(),
+            4 => this.string(),
+            _ => this.simple(),
+        })
+    }
+
+    fn simple(&mut self) -> String {
+        match self.rng.u8(..8) {
+            0 => self.pick(NUMBERS).to_string(),
+            1 => self.pick(CHARS).to_string(),
+            2 => self.pick(STRINGS).to_string(),
+            3 => format!("::{}::{}", self.type_name(), self.ident()),
+            4 => format!("{}::{}", self.type_name(), self.type_name()),
+            5 => self.type_name().to_string(),
+            _ => self.ident().to_string(),
+        }
+    }
+
+    fn string(&mut self) -> String {
+        let count = self.rng.usize(1..3);
+        let parts: String = (0..count)
+            .map(|_| format!("text ${{{}}}", self.operation()))
+            .collect();
+        format!("\"{parts}!\"")
+    }
+
+    fn fields(&mut self) -> String {
+        let count = self.rng.usize(1..3);
+        let fields = self.list(count, |this| {
+            let id = this.ident();
+            let value = this.operation();
+            format!("{id} = {value}")
+        });
+        format!("{{ {fields} }}")
+    }
+
+    /// Applications, accesses and updates of atoms.
+    /// Only an atom or an accessed name can be applied, and only once
+    fn postfix(&mut self) -> String {
+        let mut result = self.atom();
+        // Body `=> {}` is a block and `0.` is a number, so they cannot be followed by anything
+        if self.depth >= MAX_DEPTH || result.starts_with(|c: char| c == '{' || c.is_ascii_digit()) {
+            return result;
+        }
+        self.nested(|this| {
+            let mut applicable = true;
+            for _ in 0..this.rng.usize(0..3) {
+                match this.rng.u8(..3) {
+                    0 if applicable => {
+                        let count = this.rng.usize(0..3);
+                        let args = this.list(count, Self::operation);
+                        result += &format!("({args})");
+                        applicable = false;
+                    }
+                    0 | 1 => {
+                        result += &format!(".{}", this.ident());
+                        applicable = true;
+                    }
+                    _ => {
+                        result += &format!(" with {}", this.fields());
+                        applicable = false;
+                    }
+                }
+            }
+            result
+        })
+    }
+
+    fn operation(&mut self) -> String {
+        if self.depth >= MAX_DEPTH {
+            return self.simple();
+        }
+        self.nested(|this| match this.rng.u8(..12) {
+            0..=2 => {
+                let mut left = this.postfix();
+                // Statement starting with `{` is a block
+                if left.starts_with('{') {
+                    left = format!("({left})");
+                }
+                let operator = this.pick(BINARY_OPERATORS);
+                let gap = this.gap();
+                let right = this.operation();
+                format!("{left}{gap}{operator} {right}")
+            }
+            3 => {
+                let operator = this.pick(UNARY_OPERATORS);
+                format!("{operator}{}", this.postfix())
+            }
+            4 => {
+                let count = this.rng.usize(0..3);
+                let binds = this.list(count, Self::parameter);
+                format!("[{binds}] => {}", this.operation())
+            }
+            5 => this.if_expression(),
+            _ => this.postfix(),
+        })
+    }
+
+    fn if_expression(&mut self) -> String {
+        let condition = self.postfix();
+        let body = self.body();
+        let mut result = format!("if {condition} {body}");
+        for _ in 0..self.rng.usize(0..3) {
+            let condition = self.postfix();
+            let body = self.body();
+            let gap = if self.chance(50) { "\n" } else { " " };
+            result += &format!("{gap}elif {condition} {body}");
+        }
+        if self.chance(60) {
+            let body = self.body();
+            result += &format!(" else {body}");
+        }
+        result
+    }
+}
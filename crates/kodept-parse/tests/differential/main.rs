@@ -0,0 +1,213 @@
+//! Checks that every lexer and parser backend sees the same program in the same input.
+//!
+//! Besides the examples, inputs are produced by a grammar-aware generator and by random edits
+//! of generated programs. Set `KODEPT_FUZZ_SEED` and `KODEPT_FUZZ_CASES` to explore more inputs.
+
+use kodept_core::structure::rlt::RLT;
+use kodept_parse::common::RLTProducer;
+use kodept_parse::lexer::{NomLexer, PegLexer, PestLexer};
+use kodept_parse::parser::{NomParser, PegParser, PestParser};
+use kodept_parse::token_match::PackedTokenMatch;
+use kodept_parse::token_stream::PackedTokenStream;
+use kodept_parse::tokenizer::{LazyTokenizer, Tok, TokCtor};
+use rstest::rstest;
+use std::fmt::Debug;
+use std::panic::AssertUnwindSafe;
+use std::path::PathBuf;
+
+use crate::generator::Generator;
+use crate::minimize::minimize;
+
+mod generator;
+mod minimize;
+
+const DEFAULT_SEED: u64 = 0x6b6f_6465_7074;
+const DEFAULT_CASES: u64 = 200;
+
+/// Result of running a single backend
+#[derive(Debug, PartialEq)]
+enum Outcome<T> {
+    Accepted(T),
+    Rejected,
+    Panicked,
+}
+
+impl<T> Outcome<T> {
+    fn run(f: impl FnOnce() -> Option<T>) -> Self {
+        match std::panic::catch_unwind(AssertUnwindSafe(f)) {
+            Ok(Some(it)) => Outcome::Accepted(it),
+            Ok(None) => Outcome::Rejected,
+            Err(_) => Outcome::Panicked,
+        }
+    }
+}
+
+fn tokenize<'t, F>(input: &'t str, lexer: F) -> Outcome<Vec<PackedTokenMatch>>
+where
+    LazyTokenizer<'t, F>: Tok<'t> + TokCtor<'t, F>,
+{
+    Outcome::run(|| LazyTokenizer::new(input, lexer).try_into_vec().ok())
+}
+
+fn parse<P: RLTProducer>(tokens: &[PackedTokenMatch], parser: P) -> Outcome<RLT> {
+    Outcome::run(|| parser.parse_stream(&PackedTokenStream::new(tokens)).ok())
+}
+
+/// Describes the first difference between results of two backends
+fn compare<T: PartialEq + Debug>(
+    what: &str,
+    backends: [&str; 2],
+    results: [&T; 2],
+) -> Option<String> {
+    if results[0] == results[1] {
+        return None;
+    }
+    let [left, right] = results.map(|it| format!("{it:#?}"));
+    let line = left
+        .lines()
+        .zip(right.lines())
+        .position(|(a, b)| a != b)
+        .unwrap_or(left.lines().count().min(right.lines().count()));
+    let [left, right] = [&left, &right].map(|it| it.lines().nth(line).unwrap_or("<end>").trim());
+    Some(format!(
+        "{what} of {} and {} differ at line {line}: `{left}` vs `{right}`",
+        backends[0], backends[1]
+    ))
+}
+
+/// Returns description of the first divergence between backends on `input`
+fn divergence(input: &str) -> Option<String> {
+    let peg = tokenize(input, PegLexer::<false>::new());
+    let nom = tokenize(input, NomLexer::new());
+    let pest = tokenize(input, PestLexer::new());
+    if let Some(reason) = compare("Tokens", ["peg", "nom"], [&peg, &nom])
+        .or_else(|| compare("Tokens", ["peg", "pest"], [&peg, &pest]))
+    {
+        return Some(reason);
+    }
+
+    let Outcome::Accepted(tokens) = peg else {
+        return None;
+    };
+    let peg = parse(&tokens, PegParser::<false>::new());
+    let nom = parse(&tokens, NomParser::new());
+    let pest = parse(&tokens, PestParser::new());
+    compare("Trees", ["peg", "nom"], [&peg, &nom])
+        .or_else(|| compare("Trees", ["peg", "pest"], [&peg, &pest]))
+}
+
+fn assert_agree(input: &str) {
+    if let Some(reason) = divergence(input) {
+        let minimized = minimize(input, |it| divergence(it).is_some());
+        panic!(
+            "{reason}\n--- input ---\n{input}\n--- minimized ---\n{minimized}\n--- reason ---\n{}",
+            divergence(&minimized).unwrap_or_default()
+        );
+    }
+}
+
+fn env_or(name: &str, default: u64) -> u64 {
+    std::env::var(name)
+        .ok()
+        .and_then(|it| it.parse().ok())
+        .unwrap_or(default)
+}
+
+fn seeds() -> impl Iterator<Item = u64> {
+    let seed = env_or("KODEPT_FUZZ_SEED", DEFAULT_SEED);
+    let cases = env_or("KODEPT_FUZZ_CASES", DEFAULT_CASES);
+    (0..cases).map(move |it| seed.wrapping_add(it))
+}
+
+#[rstest]
+fn test_corpus(
+    #[files("../../examples/*.kd")]
+    #[files("tests/*.kd")]
+    path: PathBuf,
+) {
+    let input = std::fs::read_to_string(path).unwrap();
+    assert_agree(&input);
+}
+
+/// Divergent inputs found by this harness
+#[rstest]
+#[case::empty_file("")]
+#[case::keyword_prefix("module M => fun elsewhere => valid")]
+#[case::leading_underscore("module M => fun _tmp => _tmp")]
+#[case::trailing_comment("module M =>\n//")]
+#[case::comment_separator("module M { fun a => b // c\nfun d => e }")]
+#[case::quote_char("'''")]
+#[case::signed_number("module M => fun a => -1")]
+#[case::unterminated_comment("/*")]
+#[case::empty_hex("0x}")]
+#[case::underscore_hex("0x_1")]
+#[case::underscore_binary("0b_1")]
+#[case::lambda("module M => fun a => [x] => x")]
+#[case::type_reference("module M => fun a => T")]
+#[case::global_reference("module M => fun a => ::b")]
+#[case::contextual_lines("module M => fun a { A\n::B::c }")]
+#[case::application_lines("module M => fun a { b\n(c) }")]
+#[case::application_access("module M => fun a => b.c(d).e")]
+#[case::update_access("module M => fun a => b with { c = d }.e")]
+#[case::unary_access("module M => fun a => b.-c.d with { e = f }")]
+#[case::block_atom("module M => fun a => ({} + b)")]
+#[case::empty_enum("module M => enum struct E {}")]
+#[case::empty_payload("module M => enum struct E { A() }")]
+#[case::if_before_declaration("module M => fun a => if b => c\nfun d => e")]
+#[case::semicolons("module M => fun a => b; fun c => d")]
+fn test_regressions(#[case] input: &str) {
+    assert_agree(input);
+}
+
+#[test]
+fn test_generated() {
+    for seed in seeds() {
+        let input = Generator::new(seed).program();
+        assert_agree(&input);
+    }
+}
+
+/// Generated programs are valid, so the previous test does not compare errors only
+#[test]
+fn test_generated_are_valid() {
+    for seed in seeds() {
+        let input = Generator::new(seed).program();
+        let Outcome::Accepted(tokens) = tokenize(&input, PegLexer::<false>::new()) else {
+            panic!("Generated program is not tokenized:\n{input}");
+        };
+        assert!(
+            matches!(
+                parse(&tokens, PegParser::<false>::new()),
+                Outcome::Accepted(_)
+            ),
+            "Generated program is not valid:\n{input}"
+        );
+    }
+}
+
+/// Random edits of valid programs drive backends into their error paths
+#[test]
+fn test_mutated() {
+    const ALPHABET: &[char] = &[
+        '(', ')', '{', '}', '[', ']', '"', '\'', '/', '*', '=', '>', ' ', '\n', 'a', '1', '.', '_',
+        '0', 'x',
+    ];
+
+    for seed in seeds() {
+        let mut rng = fastrand::Rng::with_seed(seed);
+        let mut input: Vec<char> = Generator::new(seed).program().chars().collect();
+        for _ in 0..rng.usize(1..4) {
+            let position = rng.usize(..=input.len());
+            match rng.u8(..3) {
+                0 if position < input.len() => {
+                    input.remove(position);
+                }
+                1 if position < input.len() => {
+                    input[position] = ALPHABET[rng.usize(..ALPHABET.len())]
+                }
+                _ => input.insert(position, ALPHABET[rng.usize(..ALPHABET.len())]),
+            }
+        }
+        assert_agree(&input.into_iter().collect::<String>());
+    }
+}
@@ -0,0 +1,24 @@
+/// Shrinks `input` while `diverges` holds for it, removing ever smaller chunks of characters
+pub(crate) fn minimize(input: &str, diverges: impl Fn(&str) -> bool) -> String {
+    let mut current: Vec<char> = input.chars().collect();
+    let mut chunk = current.len() / 2;
+
+    while chunk > 0 {
+        let mut start = 0;
+        let mut reduced = false;
+        while start < current.len() {
+            let end = (start + chunk).min(current.len());
+            let candidate: String = current[..start].iter().chain(&current[end..]).collect();
+            if diverges(&candidate) {
+                current.drain(start..end);
+                reduced = true;
+            } else {
+                start += chunk;
+            }
+        }
+        if !reduced {
+            chunk /= 2;
+        }
+    }
+    current.into_iter().collect()
+}
@@ -0,0 +1,31 @@
+use kodept_parse::common::RLTProducer;
+use kodept_parse::formatter::format;
+use kodept_parse::lexer::PegLexer;
+use kodept_parse::parser::PegParser;
+use kodept_parse::token_stream::PackedTokenStream;
+use kodept_parse::tokenizer::{LazyTokenizer, Tok, TokCtor};
+use kodept_parse::trivia::LosslessTokens;
+use rstest::rstest;
+use std::path::PathBuf;
+
+fn format_source(input: &str) -> String {
+    let tokens = LazyTokenizer::new(input, PegLexer::<false>::new()).into_vec();
+    let stream = PackedTokenStream::new(&tokens);
+    let rlt = PegParser::<false>::new()
+        .parse_stream(&stream)
+        .expect("Source should be well-formed");
+    format(input, &LosslessTokens::new(&tokens), &rlt)
+}
+
+/// Formatting of already formatted code changes nothing
+#[rstest]
+fn test_idempotence(
+    #[files("../../examples/*.kd")]
+    #[exclude("error_eof")]
+    path: PathBuf,
+) {
+    let input = std::fs::read_to_string(path).unwrap();
+    let formatted = format_source(&input);
+
+    similar_asserts::assert_eq!(format_source(&formatted), formatted);
+}
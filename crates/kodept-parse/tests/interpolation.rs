@@ -0,0 +1,102 @@
+use kodept_core::structure::rlt::{
+    BlockLevelNode, Body, Expression, Literal, Module, Operation, Term, TopLevelNode, RLT,
+};
+use kodept_core::structure::Located;
+use kodept_parse::common::RLTProducer;
+use kodept_parse::lexer::PegLexer;
+use kodept_parse::parser::{NomParser, PegParser, PestParser};
+use kodept_parse::token_stream::PackedTokenStream;
+use kodept_parse::tokenizer::{LazyTokenizer, Tok, TokCtor};
+use rstest::rstest;
+
+fn parse<P: RLTProducer>(input: &str, parser: P) -> Option<RLT> {
+    let tokens = LazyTokenizer::new(input, PegLexer::<false>::new())
+        .try_into_vec()
+        .ok()?;
+    parser.parse_stream(&PackedTokenStream::new(&tokens)).ok()
+}
+
+/// Literal written back with embedded expressions in brackets
+fn describe(input: &str, operation: &Operation) -> String {
+    match operation {
+        Operation::Expression(Expression::Literal(Literal::Interpolated(x))) => {
+            let mut result = String::new();
+            for (index, fragment) in x.fragments.iter().enumerate() {
+                result.push_str(&input[fragment.location().as_range()]);
+                if let Some(expression) = x.expressions.get(index) {
+                    result.push_str(&format!("[{}]", describe(input, expression)));
+                }
+            }
+            result
+        }
+        Operation::Expression(Expression::Literal(Literal::String(x))) => {
+            format!("string {}", &input[x.location().as_range()])
+        }
+        Operation::Expression(Expression::Term(Term::Reference(x))) => {
+            input[x.location().as_range()].to_string()
+        }
+        _ => panic!("Unexpected expression"),
+    }
+}
+
+/// Body of the only function `f` of the only module
+fn body(rlt: &RLT) -> &Operation {
+    let [Module::Ordinary { rest, .. }] = &rlt.0 .0[..] else {
+        panic!("Expected a single module")
+    };
+    let [TopLevelNode::BodiedFunction(function)] = &rest[..] else {
+        panic!("Expected a single function")
+    };
+    match function.body.as_ref() {
+        Body::Simplified {
+            expression: BlockLevelNode::Operation(x),
+            ..
+        } => x,
+        _ => panic!("Expected a simplified body"),
+    }
+}
+
+#[rstest]
+#[case::single(r#""Hello, ${name}!""#, r#""Hello, ${[name]}!""#)]
+#[case::many(r#""${a} and ${b}""#, r#""${[a]} and ${[b]}""#)]
+#[case::nested(r#""a ${"b ${c} d"} e""#, r#""a ${["b ${[c]} d"]} e""#)]
+#[case::escaped(r#""\${a}""#, r#"string "\${a}""#)]
+#[case::escaped_inside(r#""${"\${a}"}""#, r#""${[string "\${a}"]}""#)]
+#[case::unclosed(r#""${a""#, r#"string "${a""#)]
+fn test_interpolated_string<P: RLTProducer>(
+    #[case] literal: &str,
+    #[case] expected: &str,
+    #[values(PegParser::<false>::new(), NomParser::new(), PestParser::new())] parser: P,
+) {
+    let input = format!("module A {{ fun f => {literal} }}");
+    let rlt = parse(&input, parser).expect("Program should be parsed");
+
+    assert_eq!(describe(&input, body(&rlt)), expected);
+}
+
+#[rstest]
+fn test_malformed_interpolation<P: RLTProducer>(
+    #[values(r#""${}""#, r#""${a b}""#, r#""${ + }""#)] literal: &str,
+    #[values(PegParser::<false>::new(), NomParser::new(), PestParser::new())] parser: P,
+) {
+    let input = format!("module A {{ fun f => {literal} }}");
+    assert!(parse(&input, parser).is_none());
+}
+
+#[rstest]
+#[case::operand(r#""${ + }""#)]
+#[case::closing(r#""${a b}""#)]
+fn test_malformed_interpolation_expects_tokens(#[case] literal: &str) {
+    let input = format!("module A {{ fun f => {literal} }}");
+    let tokens = LazyTokenizer::new(&input, PegLexer::<false>::new()).into_vec();
+    let Err(error) = PegParser::<false>::new().parse_stream(&PackedTokenStream::new(&tokens))
+    else {
+        panic!("Expected a syntax error")
+    };
+    let expected: Vec<_> = error.expected.tokens().collect();
+
+    assert!(
+        expected.iter().all(|it| !it.starts_with('[')),
+        "Guards of the grammar are shown: {expected:?}"
+    );
+}
@@ -0,0 +1,16 @@
+use kodept_parse::lexer::PegLexer;
+use kodept_parse::tokenizer::{LazyTokenizer, Tok, TokCtor};
+use kodept_parse::trivia::LosslessTokens;
+use rstest::rstest;
+
+#[rstest]
+#[case("tests/testing_file.kd")]
+#[case("../../examples/fibonacci.kd")]
+#[case("../../examples/rule110.kd")]
+fn test_roundtrip(#[case] filename: &str) {
+    let input = std::fs::read_to_string(filename).unwrap();
+    let tokens = LazyTokenizer::new(&input, PegLexer::<false>::new()).into_vec();
+    let lossless = LosslessTokens::new(&tokens);
+
+    similar_asserts::assert_eq!(lossless.to_source(&input), input);
+}
@@ -0,0 +1,55 @@
+use kodept_core::structure::rlt::{Module, TopLevelNode, RLT};
+use kodept_parse::common::RecoveringRLTProducer;
+use kodept_parse::lexer::PegLexer;
+use kodept_parse::parser::{NomParser, PegParser, PestParser};
+use kodept_parse::token_match::PackedTokenMatch;
+use kodept_parse::token_stream::PackedTokenStream;
+use kodept_parse::tokenizer::{LazyTokenizer, Tok, TokCtor};
+
+const SOURCE: &str = "module Main {
+  fun a(x: Int) => x +
+  fun b() => 1
+  type = Int
+  fun c() => 2 // comment
+}
+";
+
+fn get_tokens() -> Vec<PackedTokenMatch> {
+    LazyTokenizer::new(SOURCE, PegLexer::<false>::new()).into_vec()
+}
+
+fn declarations(rlt: &RLT) -> Vec<bool> {
+    match &rlt.0 .0[..] {
+        [Module::Ordinary { rest, .. }] => rest.iter().map(TopLevelNode::is_error).collect(),
+        _ => panic!("Expected a single module"),
+    }
+}
+
+fn make_test_impl<P>(parser: P)
+where
+    P: RecoveringRLTProducer,
+{
+    let tokens = get_tokens();
+    let stream = PackedTokenStream::new(&tokens);
+    let Ok((rlt, errors)) = parser.parse_recovering(&stream) else {
+        panic!("Expected to recover from errors")
+    };
+
+    assert_eq!(errors.len(), 2);
+    assert_eq!(declarations(&rlt), [true, false, true, false]);
+}
+
+#[test]
+fn test_peg() {
+    make_test_impl(PegParser::<false>::new());
+}
+
+#[test]
+fn test_nom() {
+    make_test_impl(NomParser::new());
+}
+
+#[test]
+fn test_pest() {
+    make_test_impl(PestParser::new());
+}
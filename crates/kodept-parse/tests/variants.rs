@@ -0,0 +1,135 @@
+use kodept_core::structure::rlt::{
+    Enum, EnumVariant, Module, TopLevelNode, Type, VariantPayload, RLT,
+};
+use kodept_core::structure::Located;
+use kodept_parse::common::RLTProducer;
+use kodept_parse::lexer::PegLexer;
+use kodept_parse::parser::{NomParser, PegParser, PestParser};
+use kodept_parse::token_stream::PackedTokenStream;
+use kodept_parse::tokenizer::{LazyTokenizer, Tok, TokCtor};
+use rstest::rstest;
+
+const SOURCE: &str = "module Shapes {
+  enum class List<T> { Nil, Cons(T, List<T>) }
+  enum struct Shape {
+    Circle(radius: Int),
+    Rect(width: Int, height: Int,),
+    Point(),
+    Segment((Int, Int), (Int, Int))
+  }
+}
+";
+
+fn parse<P: RLTProducer>(input: &str, parser: P) -> Option<RLT> {
+    let tokens = LazyTokenizer::new(input, PegLexer::<false>::new())
+        .try_into_vec()
+        .ok()?;
+    parser.parse_stream(&PackedTokenStream::new(&tokens)).ok()
+}
+
+fn text<L: Located>(item: &L) -> &str {
+    &SOURCE[item.location().as_range()]
+}
+
+fn describe_type(ty: &Type) -> String {
+    match ty {
+        Type::Reference(x) => text(x).to_string(),
+        Type::Tuple(x) => format!(
+            "({})",
+            x.inner
+                .iter()
+                .map(describe_type)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Type::Application { id, arguments } => format!(
+            "{}<{}>",
+            text(id),
+            arguments
+                .inner
+                .iter()
+                .map(describe_type)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+/// Variants written back in a normalized form, with named fields in braces
+fn describe(variant: &EnumVariant) -> String {
+    let name = text(&variant.id);
+    match &variant.payload {
+        None => name.to_string(),
+        Some(VariantPayload::Positional(x)) => format!(
+            "{name}({})",
+            x.inner
+                .iter()
+                .map(describe_type)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Some(VariantPayload::Named(x)) => format!(
+            "{name}{{{}}}",
+            x.inner
+                .iter()
+                .map(|it| format!("{}: {}", text(&it.id), describe_type(&it.parameter_type)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+fn enums(rlt: &RLT) -> Vec<Vec<String>> {
+    let [Module::Ordinary { rest, .. }] = &rlt.0 .0[..] else {
+        panic!("Expected a single module")
+    };
+    rest.iter()
+        .map(|it| match it {
+            TopLevelNode::Enum(Enum::Heap { contents, .. } | Enum::Stack { contents, .. }) => {
+                contents
+                    .iter()
+                    .flat_map(|it| it.inner.iter())
+                    .map(describe)
+                    .collect()
+            }
+            _ => panic!("Expected only enums"),
+        })
+        .collect()
+}
+
+#[rstest]
+#[case::peg(PegParser::<false>::new())]
+#[case::nom(NomParser::new())]
+#[case::pest(PestParser::new())]
+fn test_variant_payloads<P: RLTProducer>(#[case] parser: P) {
+    let rlt = parse(SOURCE, parser).expect("Program should be parsed");
+
+    assert_eq!(
+        enums(&rlt),
+        [
+            vec!["Nil", "Cons(T, List<T>)"],
+            vec![
+                "Circle{radius: Int}",
+                "Rect{width: Int, height: Int}",
+                "Point()",
+                "Segment((Int, Int), (Int, Int))",
+            ],
+        ]
+    );
+}
+
+#[rstest]
+#[case::peg(PegParser::<false>::new())]
+#[case::nom(NomParser::new())]
+#[case::pest(PestParser::new())]
+fn test_payload_does_not_mix_named_and_positional<P: RLTProducer>(
+    #[case] parser: P,
+    #[values(
+        "module A { enum struct E { V(x: Int, Int) } }",
+        "module A { enum struct E { V(Int, x: Int) } }",
+        "module A { enum struct E { V(x: Int } }"
+    )]
+    input: &str,
+) {
+    assert!(parse(input, parser).is_none());
+}
@@ -1,19 +1,23 @@
 use crate::cli::commands::to_diagnostics;
-use crate::cli::configs::{LoadingConfig, ParsingConfig};
+use crate::cli::configs::{LintConfig, LoadingConfig, ParsingConfig};
 use crate::cli::traits::CommandWithSources;
 use clap::Args;
 use kodept::codespan_settings::{ProvideCollector, Reports};
 use kodept::loader::Loader;
 use kodept::source_files::{SourceFiles, SourceView};
 use kodept::steps::common::Config;
+use kodept::time_passes::time_pass;
 use kodept_ast::graph::SyntaxTree;
 use kodept_ast::interning::{debug_interning_efficiency, InterningCodeHolder};
 use kodept_core::Freeze;
+use kodept_interpret::lints::registry;
 use kodept_macros::context::Context;
 use kodept_macros::error::report_collector::{ReportCollector, Reporter};
 use kodept_macros::error::traits::DrainReports;
+use kodept_macros::lint::{LintLevels, Lints, Suppressions};
 use std::num::NonZeroU16;
 use std::path::Path;
+use std::sync::OnceLock;
 use tracing::debug;
 
 #[derive(Debug, Args, Clone)]
@@ -25,10 +29,24 @@ pub struct Execute {
     parsing_config: ParsingConfig,
     #[command(flatten)]
     loading_config: LoadingConfig,
+    #[command(flatten)]
+    lint_config: LintConfig,
+    /// Resolved once for all sources
+    #[arg(skip)]
+    lint_levels: OnceLock<LintLevels>,
 }
 
 impl CommandWithSources for Execute {
-    fn build_sources(&self, collector: &mut ReportCollector<()>) -> Option<SourceFiles> {
+    fn build_sources(&self, collector: &ReportCollector<()>) -> Option<SourceFiles> {
+        match self.lint_config.resolve(&registry()) {
+            Ok(levels) => {
+                let _ = self.lint_levels.set(levels);
+            }
+            Err(e) => {
+                collector.report((), e);
+                return None;
+            }
+        }
         let loader: Loader = match self.loading_config.clone().try_into() {
             Ok(x) => x,
             Err(e) => {
@@ -40,15 +58,17 @@ impl CommandWithSources for Execute {
     }
 
     fn exec_for_source(&self, source: SourceView, reports: &mut Reports, _: &Path) -> Option<()> {
-        let rlt = reports.provide_collector(source.all_files(), |collector| {
+        let (rlt, syntax_errors) = reports.provide_collector(source.all_files(), |collector| {
             self.parsing_config
                 .build_rlt(&source)
-                .map_err(to_diagnostics)
+                .map_err(|it| to_diagnostics(it, source.contents()))
                 .drain(*source.id, collector)
         })?;
 
         let code_holder = InterningCodeHolder::new(&*source);
-        let (tree, accessor) = SyntaxTree::recursively_build(&rlt, code_holder);
+        let (tree, accessor) = time_pass("building AST", || {
+            SyntaxTree::recursively_build(&rlt, code_holder)
+        });
         debug_interning_efficiency();
         debug!("Produced AST with node count = {}", tree.node_count());
 
@@ -58,12 +78,23 @@ impl CommandWithSources for Execute {
                 rlt: accessor,
                 collector,
                 current_file: Freeze::new(source.describe()),
+                lints: Lints {
+                    levels: self.lint_levels.get().cloned().unwrap_or_default(),
+                    suppressions: Suppressions::parse(source.contents()),
+                },
             };
             let config = Config {
                 recursion_depth: self.type_checking_recursion_depth,
             };
 
-            kodept::steps::common::run_common_steps(&mut context, &config)
+            let result = kodept::steps::common::run_common_steps(&mut context, &config);
+            // Reported only now, so that passes still run on well-formed declarations
+            let has_syntax_errors = !syntax_errors.is_empty();
+            for diagnostic in to_diagnostics(syntax_errors, source.contents()) {
+                context.collector.report(*source.id, diagnostic);
+            }
+            // Type checking does not run yet, so nothing consumes the analysis further
+            result.filter(|_| !has_syntax_errors).map(drop)
         })
     }
 }
@@ -0,0 +1,42 @@
+use clap::Parser;
+use kodept_macros::error::code::{CodedError, ErrorCode, KC000};
+use kodept_macros::error::report_collector::{ReportCollector, Reporter};
+use thiserror::Error;
+
+#[derive(Parser, Debug, Clone)]
+pub struct Explain {
+    /// Code of the diagnostic, i.e. `KC001`. Lists all known codes if omitted
+    code: Option<String>,
+}
+
+#[derive(Error, Debug)]
+#[error("Unknown error code `{0}`")]
+struct UnknownCode(String);
+
+impl CodedError for UnknownCode {
+    fn code(&self) -> ErrorCode {
+        KC000
+    }
+}
+
+impl Explain {
+    pub fn exec(&self, collector: &ReportCollector<()>) -> Option<()> {
+        let Some(code) = &self.code else {
+            for code in ErrorCode::ALL {
+                println!("{code}: {}", code.title());
+            }
+            return Some(());
+        };
+
+        match ErrorCode::find(code) {
+            Some(code) => {
+                println!("{}", code.explanation().trim_end());
+                Some(())
+            }
+            None => {
+                collector.report((), UnknownCode(code.clone()));
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use crate::cli::commands::to_diagnostics;
+use crate::cli::configs::{LoadingConfig, ParsingConfig};
+use crate::cli::traits::CommandWithSources;
+use clap::Parser;
+use kodept::codespan_settings::{ProvideCollector, Reports};
+use kodept::loader::Loader;
+use kodept::source_files::{SourceFiles, SourceView};
+use kodept_core::code_point::CodePoint;
+use kodept_core::file_name::FileName;
+use kodept_macros::error::code::KC080;
+use kodept_macros::error::report::{Label, Severity};
+use kodept_macros::error::report_collector::{ReportCollector, Reporter};
+use kodept_macros::error::traits::DrainReports;
+use kodept_macros::error::Diagnostic;
+use kodept_parse::formatter::format;
+use std::io::Write;
+use std::path::Path;
+
+#[derive(Parser, Debug, Clone)]
+pub struct Format {
+    /// Do not change files, fail if any of them is not formatted
+    #[arg(long)]
+    check: bool,
+    #[command(flatten)]
+    parsing_config: ParsingConfig,
+    #[command(flatten)]
+    loading_config: LoadingConfig,
+}
+
+/// Returns location of the first line that differs
+fn first_difference(original: &str, formatted: &str) -> CodePoint {
+    let offset = original
+        .char_indices()
+        .zip(formatted.chars())
+        .find(|((_, a), b)| a != b)
+        .map_or(original.len().min(formatted.len()), |((index, _), _)| index);
+    let line_start = original[..offset].rfind('\n').map_or(0, |it| it + 1);
+    let line_end = original[offset..]
+        .find('\n')
+        .map_or(original.len(), |it| offset + it);
+    CodePoint::new((line_end - line_start) as u32, line_start as u32)
+}
+
+/// Replaces file contents at once, so it is never left partially written
+fn write_file(path: &Path, contents: &str) -> std::io::Result<()> {
+    let temporary = path.with_extension("kd.fmt");
+    std::fs::write(&temporary, contents)?;
+    std::fs::rename(temporary, path)
+}
+
+impl CommandWithSources for Format {
+    fn build_sources(&self, collector: &ReportCollector<()>) -> Option<SourceFiles> {
+        let loader: Loader = match self.loading_config.clone().try_into() {
+            Ok(x) => x,
+            Err(e) => {
+                collector.report((), e);
+                return None;
+            }
+        };
+        Some(SourceFiles::from_sources(loader.into_sources()))
+    }
+
+    fn exec_for_source(&self, source: SourceView, reports: &mut Reports, _: &Path) -> Option<()> {
+        let (tokens, rlt) = reports.provide_collector(source.all_files(), |collector| {
+            self.parsing_config
+                .build_lossless_rlt(&source)
+                .map_err(|it| to_diagnostics(it, source.contents()))
+                .drain(*source.id, collector)
+        })?;
+
+        let formatted = format(source.contents(), &tokens, &rlt);
+        let unchanged = formatted == source.contents();
+        if self.check && !unchanged {
+            return reports.provide_collector(source.all_files(), |collector| {
+                let location = first_difference(source.contents(), &formatted);
+                collector.report(
+                    *source.id,
+                    Diagnostic::new(Severity::Error, KC080)
+                        .with_message("File is not formatted")
+                        .with_label(Label::primary("formatting differs here", location))
+                        .with_note("run `kodept fmt` to format it".into()),
+                );
+                None
+            });
+        }
+
+        let result = match source.path() {
+            _ if self.check => Ok(()),
+            FileName::Real(_) if unchanged => Ok(()),
+            FileName::Real(path) => write_file(&path, &formatted),
+            FileName::Anon | FileName::Custom(_) => {
+                std::io::stdout().lock().write_all(formatted.as_bytes())
+            }
+        };
+        result
+            .map_err(|e| {
+                reports.provide_collector(source.all_files(), |collector| {
+                    collector.report(*source.id, e)
+                })
+            })
+            .ok()
+    }
+}
@@ -7,6 +7,7 @@ use kodept::loader::Loader;
 use kodept::source_files::{SourceFiles, SourceView};
 use kodept::steps::pipeline::Pipeline;
 use kodept::steps::Step;
+use kodept::time_passes::time_pass;
 use kodept_ast::graph::SyntaxTree;
 use kodept_core::Freeze;
 use kodept_macros::context::Context;
@@ -25,7 +26,7 @@ pub struct Graph {
 }
 
 impl CommandWithSources for Graph {
-    fn build_sources(&self, collector: &mut ReportCollector<()>) -> Option<SourceFiles> {
+    fn build_sources(&self, collector: &ReportCollector<()>) -> Option<SourceFiles> {
         let loader: Loader = match self.loading_config.clone().try_into() {
             Ok(x) => x,
             Err(e) => {
@@ -42,15 +43,17 @@ impl CommandWithSources for Graph {
         reports: &mut Reports,
         output: &Path,
     ) -> Option<()> {
-        let rlt = reports.provide_collector(source.all_files(), |collector| {
+        let (rlt, syntax_errors) = reports.provide_collector(source.all_files(), |collector| {
             self.parsing_config
                 .build_rlt(&source)
-                .map_err(to_diagnostics)
+                .map_err(|it| to_diagnostics(it, source.contents()))
                 .drain(*source.id, collector)
         })?;
 
         let code_holder = InterningCodeHolder::new(&*source);
-        let (tree, accessor) = SyntaxTree::recursively_build(&rlt, code_holder);
+        let (tree, accessor) = time_pass("building AST", || {
+            SyntaxTree::recursively_build(&rlt, code_holder)
+        });
         let output_file = match get_output_file(&source, output) {
             Ok(x) => x,
             Err(e) => {
@@ -67,12 +70,18 @@ impl CommandWithSources for Graph {
                 rlt: accessor,
                 collector,
                 current_file: Freeze::new(source.describe()),
+                lints: Default::default(),
             };
 
-            let _: (_,) = Pipeline
+            let result: Option<(_,)> = Pipeline
                 .define_step((ASTDotFormatter::new(output_file),))
-                .apply_with_context(&mut context)?;
-            Some(())
+                .apply_with_context(&mut context);
+            // Well-formed declarations are still drawn
+            let has_syntax_errors = !syntax_errors.is_empty();
+            for diagnostic in to_diagnostics(syntax_errors, source.contents()) {
+                context.collector.report(*source.id, diagnostic);
+            }
+            result.filter(|_| !has_syntax_errors).map(|_| ())
         })
     }
 }
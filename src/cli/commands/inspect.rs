@@ -1,11 +1,11 @@
 use std::path::Path;
-use std::string::FromUtf8Error;
 
 use crate::cli::traits::CommandWithSources;
 use clap::{Parser, ValueEnum};
 use derive_more::Display;
 use kodept::codespan_settings::{Reports};
 use kodept::source_files::{SourceFiles, SourceView};
+use kodept_macros::error::code::{CodedError, ErrorCode};
 use kodept_macros::error::report_collector::{ReportCollector, Reporter};
 use thiserror::Error;
 use crate::cli::configs::LoadingConfig;
@@ -17,49 +17,65 @@ enum InspectingOptions {
     Both,
 }
 
+#[derive(Debug, ValueEnum, Clone, Display)]
+enum TracedParser {
+    Peg,
+    Nom,
+}
+
+#[derive(Debug, ValueEnum, Clone, Display)]
+enum TraceFormat {
+    Html,
+    Text,
+}
+
 #[derive(Debug, Parser, Clone)]
 pub struct InspectParser {
     /// Controls which step will be traced
     #[arg(default_value = "both", long = "inspect")]
     option: InspectingOptions,
-    /// Additionally launch `pegviz` to produce html output
-    #[arg(default_value_t = true, short = 'p', long = "pegviz")]
-    use_pegviz: bool,
+    /// Parser to trace. Tokenizer is always traced with `peg`
+    #[arg(default_value = "peg", long = "parser")]
+    parser: TracedParser,
+    /// Format of produced traces
+    #[arg(default_value = "html", short = 'f', long = "format")]
+    format: TraceFormat,
     #[command(flatten)]
     loading_config: LoadingConfig
 }
 
-#[allow(dead_code)]
-#[derive(Debug, Error)]
-enum LaunchPegvizError {
-    #[error(transparent)]
-    OutputIsNotUTF8(#[from] FromUtf8Error),
-    #[error("`pegviz` exited with non-zero exit code: {0}")]
-    NonZeroExit(i32),
-    #[error(transparent)]
-    IO(#[from] std::io::Error),
-}
-
 #[cfg(feature = "trace")]
 #[derive(Debug, Error)]
 enum InspectError<A> {
     #[error("Error happened while parsing")]
     TokenizationError(kodept_parse::error::ParseErrors<A>),
     #[error(transparent)]
-    RedirectError(#[from] gag::RedirectError<std::fs::File>),
-    #[error(transparent)]
     IO(#[from] std::io::Error),
-    #[error(transparent)]
-    Pegviz(#[from] LaunchPegvizError),
+}
+
+#[cfg(feature = "trace")]
+impl<A> CodedError for InspectError<A> {
+    fn code(&self) -> ErrorCode {
+        match self {
+            InspectError::TokenizationError(_) => kodept_macros::error::code::KC001,
+            InspectError::IO(_) => kodept_macros::error::code::KC011,
+        }
+    }
 }
 
 #[cfg(not(feature = "trace"))]
 impl CommandWithSources for InspectParser {
-    fn build_sources(&self, collector: &mut ReportCollector<()>) -> Option<SourceFiles> {
+    fn build_sources(&self, collector: &ReportCollector<()>) -> Option<SourceFiles> {
         #[derive(Error, Debug)]
         #[error("Program is compiled without inspecting support")]
         struct Unsupported;
 
+        impl CodedError for Unsupported {
+            fn code(&self) -> ErrorCode {
+                kodept_macros::error::code::KC000
+            }
+        }
+
         collector.report((), Unsupported);
         None
     }
@@ -71,76 +87,59 @@ impl CommandWithSources for InspectParser {
 
 #[cfg(feature = "trace")]
 impl InspectParser {
-    fn launch_pegviz<P: AsRef<std::path::Path>>(
+    fn write_trace(
         &self,
-        input_file_path: P,
-    ) -> Result<(), LaunchPegvizError> {
-        use std::fs::File;
-        use std::process::{Command, Output};
-        use tracing::{debug, error, info, warn};
-
-        if !self.use_pegviz {
-            return Ok(());
-        }
-
-        let output_path = input_file_path.as_ref().with_extension("html");
-        let input_file = File::open(input_file_path)?;
-        let Output { status, stdout, .. } = Command::new("pegviz")
-            .args(["--output".into(), output_path])
-            .stdin(input_file)
-            .output()?;
-        let stdout = String::from_utf8(stdout)?;
-        stdout.lines().for_each(|line| match line.split_once(":") {
-            None if line.starts_with("= pegviz generated to") => {
-                info!("{}", line.strip_prefix("= ").unwrap())
+        trace: kodept_parse::trace::Trace,
+        source: &SourceView,
+        file_output_path: &Path,
+        step: &str,
+    ) -> std::io::Result<()> {
+        let (contents, extension) = match self.format {
+            TraceFormat::Html => {
+                let title = source.path().to_string();
+                (trace.render_html(&title, source.contents()), "html")
             }
-            None => debug!("{line}"),
-            Some((a, _)) if a.contains("error") => error!("{line}"),
-            Some(_) => debug!("{line}"),
-        });
-
-        match status.code() {
-            None => warn!("`pegviz` exited by signal"),
-            Some(0) => {}
-            Some(code) => Err(LaunchPegvizError::NonZeroExit(code))?,
+            TraceFormat::Text => (trace.render_text(), "txt"),
+        };
+        let path = file_output_path.with_extension(format!("{step}.{extension}"));
+        if let Some(parent) = path.parent() {
+            super::ensure_path_exists(parent)?;
         }
+        std::fs::write(&path, contents)?;
+        tracing::info!("Trace written to {}", path.display());
         Ok(())
     }
 
     fn inspect_tokenizer(
         &self,
         source: &SourceView,
-        file_output_path: &std::path::Path,
+        file_output_path: &Path,
     ) -> Result<(), InspectError<String>> {
         use kodept_parse::{
             lexer::PegLexer, tokenizer::EagerTokenizer, tokenizer::Tok, tokenizer::TokCtor,
         };
-        use std::fs::File;
         use InspectError::TokenizationError;
 
-        let file = File::create(file_output_path.with_extension("tok.peg"))?;
-        {
-            let _gag = gag::Redirect::stdout(file)?;
+        let (tokens, trace) = kodept_parse::trace::collect_peg(|| {
             EagerTokenizer::new(source.contents(), PegLexer::<true>::new())
                 .try_collect_adapted::<String>()
-                .map_err(TokenizationError)?;
-        }
-
-        self.launch_pegviz(file_output_path.with_extension("tok.peg"))?;
+        });
+        self.write_trace(trace, source, file_output_path, "tok")?;
+        tokens.map_err(TokenizationError)?;
         Ok(())
     }
 
     fn inspect_parser(
         &self,
         source: &SourceView,
-        file_output_path: &std::path::Path,
+        file_output_path: &Path,
     ) -> Result<(), InspectError<String>> {
         use kodept_parse::{
             lexer::PegLexer,
-            parser::{parse_from_top, PegParser},
+            common::RLTProducer,
+            parser::{NomParser, PegParser},
             tokenizer::{EagerTokenizer, Tok, TokCtor},
         };
-        use std::fs::File;
         use InspectError::TokenizationError;
 
         let tokens = EagerTokenizer::new(source.contents(), PegLexer::<false>::new())
@@ -148,20 +147,25 @@ impl InspectParser {
             .map_err(TokenizationError)?;
         let tokens = kodept_parse::token_stream::PackedTokenStream::new(&tokens);
 
-        let file = File::create(file_output_path.with_extension("par.peg"))?;
-        {
-            let _gag = gag::Redirect::stdout(file)?;
-            let _ = parse_from_top(tokens, PegParser::<true>::new());
-        }
-
-        self.launch_pegviz(file_output_path.with_extension("par.peg"))?;
+        let trace = match self.parser {
+            TracedParser::Peg => {
+                kodept_parse::trace::collect_peg(|| PegParser::<true>::new().parse_stream(&tokens)).1
+            }
+            TracedParser::Nom => {
+                kodept_parse::trace::collect_nom(source.contents(), || {
+                    NomParser::new().parse_stream(&tokens)
+                })
+                .1
+            }
+        };
+        self.write_trace(trace, source, file_output_path, "par")?;
         Ok(())
     }
 }
 
 #[cfg(feature = "trace")]
 impl CommandWithSources for InspectParser {
-    fn build_sources(&self, collector: &mut ReportCollector<()>) -> Option<SourceFiles> {
+    fn build_sources(&self, collector: &ReportCollector<()>) -> Option<SourceFiles> {
         let loader: kodept::loader::Loader = match self.loading_config.clone().try_into() {
             Ok(x) => x,
             Err(e) => {
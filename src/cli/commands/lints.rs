@@ -0,0 +1,24 @@
+use clap::Parser;
+use kodept_interpret::lints::registry;
+
+#[derive(Parser, Debug, Clone)]
+pub struct ListLints;
+
+impl ListLints {
+    pub fn exec(&self) {
+        let registry = registry();
+        let width = registry
+            .iter()
+            .map(|it| it.name.len())
+            .max()
+            .unwrap_or_default();
+        for lint in registry.iter() {
+            println!(
+                "{:width$}  {:5}  {}",
+                lint.name,
+                lint.default_level.to_string(),
+                lint.description
+            );
+        }
+    }
+}
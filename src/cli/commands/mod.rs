@@ -1,13 +1,19 @@
 use crate::cli::commands::execute::Execute;
+use crate::cli::commands::explain::Explain;
+use crate::cli::commands::fmt::Format;
 use crate::cli::commands::graph::Graph;
 use crate::cli::commands::inspect::InspectParser;
+use crate::cli::commands::lints::ListLints;
 use crate::cli::traits::CommandWithSources;
 use clap::Subcommand;
 use itertools::Itertools;
 use kodept::codespan_settings::{ConsumeCollector, ProvideCollector, Reports};
 use kodept::read_code_source::ReadCodeSource;
 use kodept::source_files::GlobalReports;
+use kodept::time_passes::time_pass;
+use kodept_macros::error::code::{KC001, KC002};
 use kodept_macros::error::report::{Label, Severity};
+use kodept_macros::error::suggestion::{Applicability, Suggestion};
 use kodept_macros::error::{Diagnostic, ErrorReported};
 use kodept_parse::error::{ParseError, ParseErrors};
 use std::borrow::Cow;
@@ -18,8 +24,11 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 mod execute;
+mod explain;
+mod fmt;
 mod graph;
 mod inspect;
+mod lints;
 
 #[derive(Subcommand, Debug, Clone)]
 pub enum Commands {
@@ -29,6 +38,12 @@ pub enum Commands {
     InspectParser(InspectParser),
     /// Run type checker
     Execute(Execute),
+    /// Format source files in the canonical style
+    Fmt(Format),
+    /// Print long-form description of an error code
+    Explain(Explain),
+    /// List available lints along with their default levels
+    Lints(ListLints),
 }
 
 impl Commands {
@@ -36,7 +51,9 @@ impl Commands {
         match self {
             Commands::Graph(x) => {
                 let sources = reports
-                    .provide_collector(&GlobalReports, |collector| x.build_sources(collector))
+                    .provide_collector(&GlobalReports, |collector| {
+                        time_pass("loading", || x.build_sources(collector))
+                    })
                     .map(Arc::new)
                     .ok_or(ErrorReported::new())?;
                 let result = x
@@ -47,7 +64,9 @@ impl Commands {
             }
             Commands::InspectParser(x) => {
                 let sources = reports
-                    .provide_collector(&GlobalReports, |collector| x.build_sources(collector))
+                    .provide_collector(&GlobalReports, |collector| {
+                        time_pass("loading", || x.build_sources(collector))
+                    })
                     .map(Arc::new)
                     .ok_or(ErrorReported::new())?;
                 let result = x
@@ -56,9 +75,31 @@ impl Commands {
                 reports.consume(&*sources);
                 result
             }
+            Commands::Fmt(x) => {
+                let sources = reports
+                    .provide_collector(&GlobalReports, |collector| {
+                        time_pass("loading", || x.build_sources(collector))
+                    })
+                    .map(Arc::new)
+                    .ok_or(ErrorReported::new())?;
+                let result = x
+                    .exec(sources.clone(), &mut reports, output)
+                    .ok_or(ErrorReported::new());
+                reports.consume(&*sources);
+                result
+            }
+            Commands::Explain(x) => reports
+                .provide_collector(&GlobalReports, |collector| x.exec(collector))
+                .ok_or(ErrorReported::new()),
+            Commands::Lints(x) => {
+                x.exec();
+                Ok(())
+            }
             Commands::Execute(x) => {
                 let sources = reports
-                    .provide_collector(&GlobalReports, |collector| x.build_sources(collector))
+                    .provide_collector(&GlobalReports, |collector| {
+                        time_pass("loading", || x.build_sources(collector))
+                    })
                     .map(Arc::new)
                     .ok_or(ErrorReported::new())?;
                 let result = x
@@ -71,7 +112,14 @@ impl Commands {
     }
 }
 
-fn to_diagnostic<A: Display>(error: ParseError<A>) -> Diagnostic {
+/// Suggests to close the block if the parser expected `}`
+fn close_block(expected: &[Cow<'static, str>], offset: u32) -> Option<Suggestion> {
+    expected.iter().any(|it| it.trim_matches('"') == "}").then(|| {
+        Suggestion::insert("close the block", offset, "}", Applicability::MachineApplicable)
+    })
+}
+
+fn to_diagnostic<A: Display>(error: ParseError<A>, source: &str) -> Diagnostic {
     let (expected, actual, location, hints) = match error {
         ParseError::ExpectedInstead {
             expected,
@@ -86,26 +134,44 @@ fn to_diagnostic<A: Display>(error: ParseError<A>) -> Diagnostic {
         } => (expected, None, location, hints),
     };
 
+    let code = if actual.is_some() { KC001 } else { KC002 };
     let diagnostic = if expected.is_empty() {
         let actual = actual
             .map(|it| Cow::Owned(it.to_string()))
             .unwrap_or(Cow::Borrowed("EOF"));
 
-        Diagnostic::new(Severity::Error)
+        Diagnostic::new(Severity::Error, code)
             .with_message(format!("Unexpected {actual}"))
             .with_label(Label::primary("here", location.in_code))
     } else if let Some(actual) = actual {
+        // The last token is reported when the source ends before the block is closed
+        let end = location.in_code.as_range().end;
+        let at_last_token = source.get(end..).is_some_and(|it| it.trim().is_empty());
+        let suggestion = if at_last_token {
+            close_block(&expected, source.len() as u32)
+        } else {
+            None
+        };
         let exp_msg = expected_to_string(expected);
 
-        Diagnostic::new(Severity::Error)
+        let diagnostic = Diagnostic::new(Severity::Error, code)
             .with_message(format!("Expected {exp_msg}, got {actual}"))
-            .with_label(Label::primary("here", location.in_code))
+            .with_label(Label::primary("here", location.in_code));
+        match suggestion {
+            Some(it) => diagnostic.with_suggestion(it),
+            None => diagnostic,
+        }
     } else {
+        let suggestion = close_block(&expected, location.in_code.offset);
         let exp_msg = expected_to_string(expected);
-        
-        Diagnostic::new(Severity::Error)
+
+        let diagnostic = Diagnostic::new(Severity::Error, code)
             .with_message(format!("Expected {exp_msg} after, got EOF"))
-            .with_label(Label::primary("here", location.in_code))
+            .with_label(Label::primary("here", location.in_code));
+        match suggestion {
+            Some(it) => diagnostic.with_suggestion(it),
+            None => diagnostic,
+        }
     };
 
     hints
@@ -125,8 +191,11 @@ fn expected_to_string(mut expected: Vec<Cow<'static, str>>) -> Cow<'static, str>
     }
 }
 
-fn to_diagnostics<A: Display>(errors: ParseErrors<A>) -> Vec<Diagnostic> {
-    errors.into_iter().map(to_diagnostic).collect()
+fn to_diagnostics<A: Display>(errors: ParseErrors<A>, source: &str) -> Vec<Diagnostic> {
+    errors
+        .into_iter()
+        .map(|it| to_diagnostic(it, source))
+        .collect()
 }
 
 fn get_output_file(source: &ReadCodeSource, output_path: &Path) -> std::io::Result<File> {
@@ -143,3 +212,43 @@ fn ensure_path_exists(path: &Path) -> std::io::Result<()> {
         _ => Ok(()),
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use crate::cli::common::Kodept;
+    use clap::Parser;
+    use kodept::codespan_settings::{ConsumeCollector, StreamOutput};
+    use kodept::source_files::GlobalReports;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::newline("module M {\n    fun main() => 1\n", "module M {\n    fun main() => 1\n}")]
+    #[case::last_token("module M {\n    fun main() => 1", "module M {\n    fun main() => 1}")]
+    #[case::after_body(
+        "module M {\n    fun main() {\n        1\n    }\n",
+        "module M {\n    fun main() {\n        1\n    }\n}"
+    )]
+    fn test_fix_closes_block(#[case] source: &str, #[case] expected: &str) {
+        let output = tempfile::tempdir().unwrap();
+        let path = output.path().join("main.kd");
+        std::fs::write(&path, source).unwrap();
+        let cli = Kodept::try_parse_from([
+            "kodept".as_ref(),
+            "--fix".as_ref(),
+            "--out".as_ref(),
+            output.path().as_os_str(),
+            "execute".as_ref(),
+            path.as_os_str(),
+        ])
+        .unwrap();
+
+        let reports = cli.diagnostic_config.into_reports(StreamOutput::NoOp);
+        let fixes = reports.fix_log().unwrap();
+        let _ = cli.subcommands.execute(cli.output, reports.clone());
+        reports.consume(&GlobalReports);
+        fixes.apply().unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), expected);
+    }
+}
@@ -5,6 +5,7 @@ use tracing::Level;
 
 use crate::cli::commands::Commands;
 use crate::cli::configs::DiagnosticConfig;
+use crate::cli::utils::TimePassesFormat;
 
 const ABOUT_MESSAGE: &str =
     "Typechecks or interprets passed INPUT using Kodept programming language";
@@ -32,6 +33,17 @@ pub struct Kodept {
     /// Write all output to specified path
     #[arg(short = 'o', long = "out", default_value = "./build", global = true)]
     pub output: PathBuf,
+    /// Record wall time and allocations of every compiler pass.
+    /// `table` prints them after compilation, `chrome` writes `time-passes.json` trace to output
+    #[arg(
+    long,
+    value_name = "FORMAT",
+    num_args = 0..=1,
+    require_equals = true,
+    default_missing_value = "table",
+    global = true
+    )]
+    pub time_passes: Option<TimePassesFormat>,
 
     #[command(flatten)]
     pub diagnostic_config: DiagnosticConfig,
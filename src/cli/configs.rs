@@ -1,6 +1,7 @@
 use std::io::{stdin, Read};
-use std::path::PathBuf;
-use std::sync::{Arc, Mutex};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
 
 use crate::cli::utils::{DisplayStyle, Extension};
 use clap::{Args, ValueEnum};
@@ -9,15 +10,27 @@ use codespan_reporting::term::{ColorArg, Config};
 use derive_more::From;
 use kodept::codespan_settings::{CodespanSettings, Reports, StreamOutput};
 use kodept::loader::{Loader, LoadingError};
+use kodept::manifest::{Manifest, ManifestError, DEFAULT_MANIFEST};
 use kodept::read_code_source::ReadCodeSource;
+use kodept::time_passes::time_pass;
+use kodept_macros::error::code::{CodedError, ErrorCode};
+use kodept_macros::error::report_collector::{ErrorLimit, ReportCollector};
+use kodept_macros::error::structured::{ReportFormat, SarifLog};
+use kodept_macros::error::suggestion::FixLog;
+use kodept_macros::lint::{LintLevel, LintLevels, LintRegistry, UnknownLint};
 use kodept_core::structure::rlt::RLT;
-use kodept_parse::common::{EagerTokensProducer, ErrorAdapter, RLTProducer, TokenProducer};
+use kodept_parse::common::{
+    EagerTokensProducer, ErrorAdapter, RLTProducer, RecoveringRLTProducer, TokenProducer,
+};
 use kodept_parse::error::ParseErrors;
 use kodept_parse::lexer::traits::ToRepresentation;
+use kodept_parse::lexer::PackedToken;
 use kodept_parse::lexer::{NomLexer, PegLexer, PestLexer};
-use kodept_parse::parser::{NomParser, PegParser};
+use kodept_parse::parser::{NomParser, PegParser, PestParser};
 use kodept_parse::token_match::PackedTokenMatch;
 use kodept_parse::token_stream::PackedTokenStream;
+use kodept_parse::trivia::LosslessTokens;
+use thiserror::Error;
 use tracing::debug;
 
 #[derive(Debug, Args, Clone)]
@@ -51,6 +64,7 @@ pub enum LexerChoice {
 #[derive(Debug, ValueEnum, Clone)]
 pub enum ParserChoice {
     Peg,
+    Pest,
     Nom,
     Auto,
 }
@@ -66,6 +80,7 @@ enum LexerImpl {
 enum ParserImpl {
     Peg(PegParser<false>),
     Nom(NomParser),
+    Pest(PestParser),
 }
 
 impl LexerImpl {
@@ -122,10 +137,39 @@ impl RLTProducer for ParserImpl {
                 .map_err(|e| e.adapt(*input, 0).map(|it| it.representation())),
             ParserImpl::Nom(x) => RLTProducer::parse_stream(x, input)
                 .map_err(|e| e.adapt(*input, 0).map(|it| it.representation())),
+            ParserImpl::Pest(x) => RLTProducer::parse_stream(x, input)
+                .map_err(|e| e.adapt(*input, 0).map(|it| it.representation())),
+        }
+    }
+}
+
+impl RecoveringRLTProducer for ParserImpl {
+    fn parse_recovering<'t>(
+        &self,
+        input: &PackedTokenStream<'t>,
+    ) -> Result<(RLT, Vec<Self::Error<'t>>), Self::Error<'t>> {
+        match self {
+            ParserImpl::Peg(x) => adapt_recovered(*input, x.parse_recovering(input)),
+            ParserImpl::Nom(x) => adapt_recovered(*input, x.parse_recovering(input)),
+            ParserImpl::Pest(x) => adapt_recovered(*input, x.parse_recovering(input)),
         }
     }
 }
 
+fn adapt_recovered<'t, E>(
+    input: PackedTokenStream<'t>,
+    result: Result<(RLT, Vec<E>), E>,
+) -> Result<(RLT, Vec<ParseErrors<&'static str>>), ParseErrors<&'static str>>
+where
+    E: ErrorAdapter<PackedToken, PackedTokenStream<'t>>,
+{
+    let adapt = |e: E| e.adapt(input, 0).map(|it| it.representation());
+    match result {
+        Ok((rlt, errors)) => Ok((rlt, errors.into_iter().map(adapt).collect())),
+        Err(e) => Err(adapt(e)),
+    }
+}
+
 #[derive(Debug, Args, Clone)]
 pub struct DiagnosticConfig {
     /// The display style to use when rendering a diagnostic
@@ -140,9 +184,18 @@ pub struct DiagnosticConfig {
     /// Output diagnostics eagerly
     #[arg(long, default_value_t = false)]
     eager: bool,
-    /// Disable output of diagnostics to stderr
+    /// Additionally write all diagnostics to the file in SARIF 2.1 format
+    #[arg(long = "sarif", value_name = "FILE")]
+    sarif: Option<PathBuf>,
+    /// Apply machine-applicable suggestions to the source files in place
+    #[arg(long, default_value_t = false)]
+    fix: bool,
+    /// Stop compilation after this many errors
+    #[arg(long, value_name = "N")]
+    max_errors: Option<NonZeroUsize>,
+    /// Disable output of diagnostics to stderr, or to stdout for `--style json`
     #[arg(
-    conflicts_with_all = ["style", "tab_width", "color", "eager"],
+    conflicts_with_all = ["style", "tab_width", "color", "eager", "sarif", "fix", "max_errors"],
     long = "disable-diagnostics",
     default_value_t = false
     )]
@@ -162,6 +215,39 @@ pub struct LoadingConfig {
     extension: Extension,
 }
 
+#[derive(Debug, Args, Clone)]
+pub struct LintConfig {
+    /// Report the lint as a warning
+    #[arg(short = 'W', long = "warn", value_name = "LINT")]
+    warn: Vec<String>,
+    /// Report the lint as an error
+    #[arg(short = 'D', long = "deny", value_name = "LINT")]
+    deny: Vec<String>,
+    /// Do not report the lint
+    #[arg(short = 'A', long = "allow", value_name = "LINT")]
+    allow: Vec<String>,
+    /// Read lint levels from the `[lints]` table of this manifest, `kodept.toml` is used if it exists
+    #[arg(long, value_name = "FILE")]
+    manifest: Option<PathBuf>,
+}
+
+#[derive(Debug, Error)]
+pub enum LintConfigError {
+    #[error(transparent)]
+    Manifest(#[from] ManifestError),
+    #[error(transparent)]
+    UnknownLint(#[from] UnknownLint),
+}
+
+impl CodedError for LintConfigError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            LintConfigError::Manifest(e) => e.code(),
+            LintConfigError::UnknownLint(e) => e.code(),
+        }
+    }
+}
+
 impl ParsingConfig {
     fn get_lexing_backend(&self, source_len: usize) -> LexerImpl {
         const ONE_MB: usize = 1024 * 1024;
@@ -197,6 +283,7 @@ impl ParsingConfig {
             (ParserChoice::Peg, true, true) => {
                 panic!("Cannot use peg parser when parallelization and tracing are enabled")
             }
+            (ParserChoice::Pest, _, _) => PestParser::new().into(),
             (ParserChoice::Nom, _, _) => NomParser::new().into(),
             (ParserChoice::Auto, _, false) => PegParser::new().into(),
             (ParserChoice::Auto, false, true) => PegParser::new().into(),
@@ -212,63 +299,159 @@ impl ParsingConfig {
 
         let backend = self.get_lexing_backend(source.contents().len());
 
-        if cfg!(feature = "parallel")
-            && self.parallel
-            && source.contents().len() > self.parallel_threshold * 1024
-        {
-            debug!(backend = backend.type_name(), "Using parallel lexer");
-            #[cfg(feature = "parallel")]
-            return ParallelTokenizer::new(source.contents(), backend).try_into_vec();
-            #[cfg(not(feature = "parallel"))]
+        time_pass(format!("lexing ({})", backend.type_name()), || {
+            if cfg!(feature = "parallel")
+                && self.parallel
+                && source.contents().len() > self.parallel_threshold * 1024
             {
-                unreachable!()
+                debug!(backend = backend.type_name(), "Using parallel lexer");
+                #[cfg(feature = "parallel")]
+                return ParallelTokenizer::new(source.contents(), backend).try_into_vec();
+                #[cfg(not(feature = "parallel"))]
+                {
+                    unreachable!()
+                }
             }
-        }
-        if matches!(self.lexer, LexerChoice::Nom) {
-            LazyTokenizer::new(source.contents(), backend).try_into_vec()
-        } else {
-            EagerTokenizer::new(source.contents(), backend).try_into_vec()
-        }
+            if matches!(self.lexer, LexerChoice::Nom) {
+                LazyTokenizer::new(source.contents(), backend).try_into_vec()
+            } else {
+                EagerTokenizer::new(source.contents(), backend).try_into_vec()
+            }
+        })
     }
 
-    pub fn build_rlt<'a>(&self, source: &'a ReadCodeSource) -> Result<RLT, ParseErrors<&'a str>> {
+    /// Returns RLT with malformed declarations skipped along with syntax errors found in them,
+    /// or fails if the source cannot be parsed at all
+    pub fn build_rlt<'a>(
+        &self,
+        source: &'a ReadCodeSource,
+    ) -> Result<(RLT, ParseErrors<&'a str>), ParseErrors<&'a str>> {
         let tokens = self.tokenize(source)?;
         let stream = PackedTokenStream::new(&tokens);
         debug!(length = tokens.len(), "Produced token stream");
 
         let backend = self.get_parsing_backend();
-        let rlt = backend.parse_stream(&stream)?;
+        let (rlt, errors) = time_pass("parsing", || backend.parse_recovering(&stream))?;
 
         debug!("Produced RLT with modules count {}", rlt.0 .0.len());
-        Ok(rlt)
+        Ok((rlt, errors.into_iter().flatten().collect()))
+    }
+
+    /// Returns RLT along with all tokens of the source, failing on any syntax error
+    pub fn build_lossless_rlt<'a>(
+        &self,
+        source: &'a ReadCodeSource,
+    ) -> Result<(LosslessTokens, RLT), ParseErrors<&'a str>> {
+        let tokens = self.tokenize(source)?;
+        let stream = PackedTokenStream::new(&tokens);
+
+        let backend = self.get_parsing_backend();
+        let (rlt, errors) = time_pass("parsing", || backend.parse_recovering(&stream))?;
+        let errors: ParseErrors<_> = errors.into_iter().flatten().collect();
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        Ok((LosslessTokens::new(&tokens), rlt))
     }
 }
 
-impl From<DiagnosticConfig> for Reports {
-    fn from(value: DiagnosticConfig) -> Self {
+impl DiagnosticConfig {
+    pub fn sarif_path(&self) -> Option<&Path> {
+        self.sarif.as_deref()
+    }
+
+    /// Diagnostics are rendered to the given stream instead of stderr
+    pub fn into_reports(self, stream: StreamOutput) -> Reports {
+        let format = ReportFormat::from(&self.style);
+        let sarif = self.sarif.as_ref().map(|_| SarifLog::default());
+        let fixes = self.fix.then(FixLog::default);
+        let error_limit = self.max_errors.map(ErrorLimit::new);
         let config = Config {
-            tab_width: value.tab_width,
-            display_style: value.style.into(),
+            tab_width: self.tab_width,
+            display_style: self.style.into(),
             ..Default::default()
         };
-        let stream = if !value.disable {
-            StreamOutput::Standard(Arc::new(Mutex::new(StandardStream::stderr(value.color.0))))
-        } else {
-            StreamOutput::NoOp
-        };
 
-        match (value.disable, value.eager) {
-            (true, _) => Self::Disabled,
-            (false, true) => Self::Eager(CodespanSettings { config, stream }),
-            (false, false) => Self::Lazy {
-                local_reports: Default::default(),
-                global_reports: Default::default(),
-                settings: CodespanSettings { config, stream },
+        match (self.disable, self.eager) {
+            (true, _) => Reports::Disabled,
+            (false, true) => Reports::Eager {
+                settings: CodespanSettings {
+                    config,
+                    stream,
+                    format,
+                    sarif,
+                    fixes,
+                    summary: Default::default(),
+                    error_limit,
+                },
+                order: Default::default(),
+            },
+            (false, false) => Reports::Lazy {
+                local_reports: Arc::new(RwLock::new(ReportCollector::with_limit(
+                    error_limit.clone(),
+                ))),
+                global_reports: Arc::new(RwLock::new(ReportCollector::with_limit(
+                    error_limit.clone(),
+                ))),
+                settings: CodespanSettings {
+                    config,
+                    stream,
+                    format,
+                    sarif,
+                    fixes,
+                    summary: Default::default(),
+                    error_limit,
+                },
             },
         }
     }
 }
 
+impl From<DiagnosticConfig> for Reports {
+    fn from(value: DiagnosticConfig) -> Self {
+        if value.disable {
+            return value.into_reports(StreamOutput::NoOp);
+        }
+        // JSON is meant to be read by tools, so it is kept apart from logs on stderr
+        let stream = match value.style {
+            DisplayStyle::Json => StandardStream::stdout(value.color.0),
+            _ => StandardStream::stderr(value.color.0),
+        };
+        value.into_reports(StreamOutput::Standard(Arc::new(Mutex::new(stream))))
+    }
+}
+
+impl LintConfig {
+    fn read_manifest(&self) -> Result<Manifest, ManifestError> {
+        match &self.manifest {
+            Some(path) => Manifest::read(path),
+            None if Path::new(DEFAULT_MANIFEST).is_file() => {
+                Manifest::read(DEFAULT_MANIFEST.as_ref())
+            }
+            None => Ok(Manifest::default()),
+        }
+    }
+
+    /// Levels from the manifest are overridden by the command line,
+    /// where `--deny` takes precedence over `--warn` and `--allow`
+    pub fn resolve(&self, registry: &LintRegistry) -> Result<LintLevels, LintConfigError> {
+        let manifest = self.read_manifest()?;
+        let from_manifest = manifest
+            .lints
+            .iter()
+            .map(|(name, level)| (name.as_str(), *level));
+        let from_cli = [
+            (&self.allow, LintLevel::Allow),
+            (&self.warn, LintLevel::Warn),
+            (&self.deny, LintLevel::Deny),
+        ]
+        .into_iter()
+        .flat_map(|(names, level)| names.iter().map(move |it| (it.as_str(), level)));
+
+        Ok(registry.levels(from_manifest.chain(from_cli))?)
+    }
+}
+
 impl TryFrom<LoadingConfig> for Loader {
     type Error = LoadingError;
 
@@ -2,4 +2,6 @@ pub(crate) mod commands;
 pub mod common;
 pub mod configs;
 pub mod traits;
+#[cfg(test)]
+mod ui_tests;
 pub mod utils;
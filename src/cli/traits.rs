@@ -1,14 +1,15 @@
 use kodept::codespan_settings::{ProvideCollector, Reports};
 use kodept::common_iter::CommonIter;
+use kodept::crash::{catch_crash, Crash};
 use kodept::source_files::{SourceFiles, SourceView};
-use kodept_macros::error::report::Severity;
+use kodept::time_passes::in_file;
+use kodept_macros::error::code::KC666;
+use kodept_macros::error::report::{Label, Severity};
 use kodept_macros::error::report_collector::{ReportCollector, Reporter};
 use kodept_macros::error::Diagnostic;
-use std::panic::UnwindSafe;
 use std::path::{Path, PathBuf};
-use std::sync::atomic::{AtomicU16, Ordering};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::thread::panicking;
 use std::time::{Duration, Instant};
 use tracing::warn;
 
@@ -24,52 +25,44 @@ fn pick_appropriate_suffix(dur: Duration) -> (f32, &'static str) {
     }
 }
 
-static PANICKED_SOURCE: AtomicU16 = AtomicU16::new(u16::MAX);
-
-struct SetPanickedSourceId(u16);
-
-impl Drop for SetPanickedSourceId {
-    fn drop(&mut self) {
-        if panicking() {
-            PANICKED_SOURCE.store(self.0, Ordering::Relaxed);
-        }
-    }
-}
-
 pub trait CommandWithSources: Sized {
-    fn build_sources(&self, collector: &mut ReportCollector<()>) -> Option<SourceFiles>;
+    fn build_sources(&self, collector: &ReportCollector<()>) -> Option<SourceFiles>;
 
     fn exec(self, sources: Arc<SourceFiles>, reports: &mut Reports, output: PathBuf) -> Option<()>
     where
-        Self: UnwindSafe + Sync,
+        Self: Sync,
     {
-        let rpt = reports.clone();
-        let src = sources.clone();
-        match std::panic::catch_unwind(move || {
-            src.into_common_iter()
-                .panic_fuse()
-                .try_foreach_with(rpt, |reports, source| {
-                    let _ = SetPanickedSourceId(*source.id);
-                    let now = Instant::now();
-                    let result = self.exec_for_source(source.clone(), reports, &output);
-                    let (elapsed, suffix) = pick_appropriate_suffix(now.elapsed());
-                    warn!("Finished `{}` in {elapsed:.2}{suffix}", source.path());
-                    result
-                })
-        }) {
-            Ok(Some(())) => Some(()),
-            Ok(None) => None,
-            Err(_) => {
-                reports.provide_collector(&*sources, |c| {
-                    c.report(
-                        PANICKED_SOURCE.load(Ordering::Relaxed),
-                        Diagnostic::new(Severity::Bug)
-                            .with_message("Unknown panic happened. Contact Kodept developers."),
-                    )
-                });
-                None
-            }
-        }
+        let failed = AtomicBool::new(false);
+        let result = sources
+            .into_common_iter()
+            .panic_fuse()
+            .try_foreach_with(reports.clone(), |reports, source| {
+                if reports.is_error_limit_reached() {
+                    return None;
+                }
+                let now = Instant::now();
+                let result = match catch_crash(|| {
+                    in_file(source.path().to_string(), || {
+                        self.exec_for_source(source.clone(), reports, &output)
+                    })
+                }) {
+                    Ok(result) => result,
+                    Err(crash) => {
+                        report_crash(crash, &source, reports, &output);
+                        None
+                    }
+                };
+                reports.finish_file(*source.id, source.all_files());
+                let (elapsed, suffix) = pick_appropriate_suffix(now.elapsed());
+                warn!("Finished `{}` in {elapsed:.2}{suffix}", source.path());
+                // Other files are still worth checking, and stopping at the first failed one
+                // would make reports depend on the order files are processed in
+                if result.is_none() {
+                    failed.store(true, Ordering::Relaxed);
+                }
+                Some(())
+            });
+        result.filter(|_| !failed.load(Ordering::Relaxed))
     }
 
     fn exec_for_source(
@@ -79,3 +72,163 @@ pub trait CommandWithSources: Sized {
         output: &Path,
     ) -> Option<()>;
 }
+
+fn report_crash(crash: Crash, source: &SourceView, reports: &mut Reports, output: &Path) {
+    let note = match crash.write_bundle(source, output) {
+        Ok(path) => format!("crash report is written to `{}`", path.display()),
+        Err(e) => format!("cannot write crash report: {e}"),
+    };
+    let diagnostic = Diagnostic::new(Severity::Bug, KC666)
+        .with_message(format!("Compiler panicked: {}", crash.payload))
+        .with_note(note.into())
+        .with_note("this is a bug, please report it to Kodept developers".into());
+    let diagnostic = match crash.location {
+        Some(at) => diagnostic.with_label(Label::primary("while visiting this node", at)),
+        None => diagnostic,
+    };
+    reports.provide_collector(source.all_files(), |c| c.report(*source.id, diagnostic));
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use crate::cli::common::Kodept;
+    use crate::cli::traits::CommandWithSources;
+    use clap::Parser;
+    use kodept::codespan_settings::{ConsumeCollector, ProvideCollector, Reports, StreamOutput};
+    use kodept::source_files::{GlobalReports, SourceFiles, SourceView};
+    use kodept::steps::pipeline::Pipeline;
+    use kodept::steps::Step;
+    use kodept_ast::graph::SyntaxTree;
+    use kodept_ast::interning::InterningCodeHolder;
+    use kodept_ast::utils::Skip;
+    use kodept_ast::utils::Skip::Skipped;
+    use kodept_ast::visit_side::VisitSide;
+    use kodept_ast::ModDecl;
+    use kodept_core::code_source::CodeSource;
+    use kodept_core::Freeze;
+    use kodept_macros::context::Context;
+    use kodept_macros::error::report_collector::ReportCollector;
+    use kodept_macros::visit_guard::VisitGuard;
+    use kodept_macros::{Macro, MacroExt};
+    use kodept_parse::common::RecoveringRLTProducer;
+    use kodept_parse::lexer::PegLexer;
+    use kodept_parse::parser::PegParser;
+    use kodept_parse::token_stream::PackedTokenStream;
+    use kodept_parse::tokenizer::{LazyTokenizer, Tok, TokCtor};
+    use std::convert::Infallible;
+    use std::fs::File;
+    use std::path::Path;
+    use std::sync::{Arc, Mutex};
+
+    /// Panics when visiting a module named `Boom`
+    struct Panicking;
+
+    impl Macro for Panicking {
+        type Error = Infallible;
+        type Node = ModDecl;
+        type Ctx<'a> = Context<'a>;
+
+        fn apply(
+            &mut self,
+            guard: VisitGuard<Self::Node>,
+            ctx: &mut Self::Ctx<'_>,
+        ) -> Result<(), Skip<Self::Error>> {
+            let id = guard.allow_only(VisitSide::Entering).ok_or(Skipped)?;
+            if self.resolve(id, ctx).name.as_ref() == "Boom" {
+                panic!("Cannot expand `Boom`");
+            }
+            Ok(())
+        }
+    }
+
+    /// Runs [`Panicking`] on every source, remembering the ones it went through
+    #[derive(Default)]
+    struct RunPanicking {
+        finished: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl CommandWithSources for RunPanicking {
+        fn build_sources(&self, _: &ReportCollector<()>) -> Option<SourceFiles> {
+            // Sources are given to `exec` directly
+            None
+        }
+
+        fn exec_for_source(
+            &self,
+            source: SourceView,
+            reports: &mut Reports,
+            _: &Path,
+        ) -> Option<()> {
+            let tokens = LazyTokenizer::new(source.contents(), PegLexer::<false>::new()).into_vec();
+            let stream = PackedTokenStream::new(&tokens);
+            let (rlt, _) = PegParser::<false>::new().parse_recovering(&stream).unwrap();
+            let code_holder = InterningCodeHolder::new(&*source);
+            let (ast, rlt) = SyntaxTree::recursively_build(&rlt, code_holder);
+
+            reports.provide_collector(source.all_files(), |collector| {
+                let mut context = Context {
+                    ast,
+                    rlt,
+                    collector,
+                    current_file: Freeze::new(source.describe()),
+                    lints: Default::default(),
+                };
+                let (_,) = Pipeline
+                    .define_step((Panicking,))
+                    .apply_with_context(&mut context)?;
+                Some(())
+            })?;
+            self.finished.lock().unwrap().push(source.path().to_string());
+            Some(())
+        }
+    }
+
+    #[test]
+    fn test_crash_is_reported_and_other_files_are_processed() {
+        let input = tempfile::tempdir().unwrap();
+        let output = tempfile::tempdir().unwrap();
+        let sources = [("a/main.kd", "Boom"), ("b/main.kd", "Boom"), ("c/main.kd", "Fine")]
+            .map(|(path, module)| {
+                let path = input.path().join(path);
+                std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+                std::fs::write(&path, format!("module {module} {{\n    fun main() => 1\n}}"))
+                    .unwrap();
+                CodeSource::file(&path, File::open(&path).unwrap())
+            });
+        let sources = Arc::new(SourceFiles::from_sources(sources.into()));
+        let sarif = output.path().join("report.sarif");
+        let cli = Kodept::try_parse_from([
+            "kodept".as_ref(),
+            "--sarif".as_ref(),
+            sarif.as_os_str(),
+            "execute".as_ref(),
+        ])
+        .unwrap();
+        let mut reports = cli.diagnostic_config.into_reports(StreamOutput::NoOp);
+        let log = reports.sarif_log().unwrap();
+
+        let command = RunPanicking::default();
+        let finished = command.finished.clone();
+        let result = command.exec(sources.clone(), &mut reports, output.path().to_path_buf());
+        reports.clone().consume(&*sources);
+        reports.consume(&GlobalReports);
+
+        assert!(result.is_none());
+        let crashes: Vec<_> = log
+            .reports()
+            .into_iter()
+            .filter(|it| it.code.as_deref() == Some("KC666"))
+            .collect();
+        assert_eq!(crashes.len(), 2);
+        assert!(crashes[0].message.contains("Cannot expand `Boom`"));
+        let bundles = std::fs::read_dir(output.path())
+            .unwrap()
+            .filter(|it| it.as_ref().unwrap().path().extension().unwrap() == "crash")
+            .count();
+        assert_eq!(bundles, 2);
+        let finished = finished.lock().unwrap();
+        assert_eq!(finished.len(), 1);
+        assert!(finished[0].ends_with("main.kd"));
+    }
+}
@@ -0,0 +1,167 @@
+//! Runs `execute` on every program in `tests/ui` and compares the produced diagnostics
+//! with `//~ KIND message` annotations written in the program.
+//! Every annotation should be reported and every reported diagnostic should be annotated.
+//! `//~^ KIND message` refers to the previous line, every additional `^` moves one line up.
+//! `//@ flags: ...` passes additional arguments to `execute`, i.e. lint levels.
+//! Rendered output is snapshotted to `tests/ui/snapshots`
+
+use crate::cli::common::Kodept;
+use clap::Parser;
+use codespan_reporting::term::termcolor::Buffer;
+use kodept::codespan_settings::{ConsumeCollector, StreamOutput};
+use kodept::source_files::GlobalReports;
+use kodept_macros::error::structured::StructuredReport;
+use std::fs::read_dir;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+const UI_TESTS_DIR: &str = "tests/ui";
+
+/// Diagnostic expected by an annotation
+#[derive(Debug)]
+struct Expected {
+    line: usize,
+    severity: String,
+    message: String,
+}
+
+struct Outcome {
+    rendered: String,
+    reports: Vec<StructuredReport>,
+}
+
+fn parse_annotations(source: &str) -> Vec<Expected> {
+    source
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let (_, annotation) = line.split_once("//~")?;
+            let above = annotation.chars().take_while(|it| *it == '^').count();
+            let (kind, message) = annotation[above..].trim().split_once(' ')?;
+            Some(Expected {
+                line: index + 1 - above,
+                severity: kind.to_lowercase(),
+                message: message.trim().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Arguments given by `//@ flags:` directives
+fn parse_flags(source: &str) -> Vec<String> {
+    source
+        .lines()
+        .filter_map(|line| line.trim_start().strip_prefix("//@ flags:"))
+        .flat_map(|it| it.split_whitespace().map(str::to_string))
+        .collect()
+}
+
+/// Line of the primary label, annotations cannot refer to diagnostics without one
+fn report_line(report: &StructuredReport) -> Option<usize> {
+    report.labels.iter().find(|it| it.primary).map(|it| it.line)
+}
+
+impl Expected {
+    fn matches(&self, report: &StructuredReport) -> bool {
+        report_line(report) == Some(self.line)
+            && report.severity == self.severity
+            && report.message.contains(&self.message)
+    }
+}
+
+fn execute(path: &Path, flags: &[String]) -> Outcome {
+    let output = tempfile::tempdir().expect("Cannot create output directory");
+    let sarif = output.path().join("report.sarif");
+    let arguments = [
+        "kodept".as_ref(),
+        "--style=short".as_ref(),
+        "--sarif".as_ref(),
+        sarif.as_os_str(),
+        "--out".as_ref(),
+        output.path().as_os_str(),
+        "execute".as_ref(),
+    ]
+    .into_iter()
+    .chain(flags.iter().map(|it| it.as_ref()))
+    .chain([path.as_os_str()]);
+    let cli = Kodept::try_parse_from(arguments).expect("Invalid arguments");
+
+    let buffer = Arc::new(Mutex::new(Buffer::no_color()));
+    let reports = cli
+        .diagnostic_config
+        .into_reports(StreamOutput::Buffer(buffer.clone()));
+    let log = reports.sarif_log().expect("SARIF log should be enabled");
+    // Failures are checked through the produced diagnostics
+    let _ = cli.subcommands.execute(cli.output, reports.clone());
+    reports.consume(&GlobalReports);
+
+    let rendered = buffer.lock().expect("Lock was poisoned").as_slice().to_vec();
+    Outcome {
+        rendered: String::from_utf8(rendered).expect("Output should be valid UTF-8"),
+        reports: log.reports(),
+    }
+}
+
+fn ui_tests() -> Vec<PathBuf> {
+    let mut paths: Vec<_> = read_dir(UI_TESTS_DIR)
+        .expect("Cannot read UI tests directory")
+        .filter_map(|it| Some(it.ok()?.path()))
+        .filter(|it| it.extension().is_some_and(|ext| ext == "kd"))
+        .collect();
+    paths.sort();
+    paths
+}
+
+#[test]
+fn ui() {
+    let mut failures = vec![];
+
+    for path in ui_tests() {
+        let source = std::fs::read_to_string(&path).expect("Cannot read UI test");
+        let outcome = execute(&path, &parse_flags(&source));
+
+        let annotations = parse_annotations(&source);
+        for expected in &annotations {
+            if !outcome.reports.iter().any(|it| expected.matches(it)) {
+                failures.push(format!(
+                    "{}:{}: expected {} `{}` was not reported",
+                    path.display(),
+                    expected.line,
+                    expected.severity,
+                    expected.message
+                ));
+            }
+        }
+        for report in &outcome.reports {
+            if annotations.iter().any(|it| it.matches(report)) {
+                continue;
+            }
+            match report_line(report) {
+                Some(line) => failures.push(format!(
+                    "{}:{line}: unexpected {} `{}` was reported",
+                    path.display(),
+                    report.severity,
+                    report.message
+                )),
+                None => failures.push(format!(
+                    "{}: {} `{}` was reported without a primary label",
+                    path.display(),
+                    report.severity,
+                    report.message
+                )),
+            }
+        }
+
+        let name = path.file_stem().and_then(|it| it.to_str()).unwrap_or("ui");
+        insta::with_settings!({
+            snapshot_path => "../../tests/ui/snapshots",
+            prepend_module_to_snapshot => false,
+            input_file => &path,
+            omit_expression => true,
+        }, {
+            insta::assert_snapshot!(name, outcome.rendered);
+        });
+    }
+
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
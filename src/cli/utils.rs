@@ -4,6 +4,7 @@ use std::str::FromStr;
 
 use clap::ValueEnum;
 use derive_more::Display;
+use kodept_macros::error::structured::ReportFormat;
 
 #[derive(Debug, Clone, Display, ValueEnum)]
 pub enum DisplayStyle {
@@ -13,6 +14,8 @@ pub enum DisplayStyle {
     Medium,
     /// Adds file, line number, severity and message
     Short,
+    /// Emits one JSON object per diagnostic to stdout
+    Json,
 }
 
 #[derive(Clone, Debug)]
@@ -32,12 +35,30 @@ impl FromStr for Extension {
     }
 }
 
+impl From<&DisplayStyle> for ReportFormat {
+    fn from(value: &DisplayStyle) -> Self {
+        match value {
+            DisplayStyle::Json => ReportFormat::Json,
+            _ => ReportFormat::Terminal,
+        }
+    }
+}
+
 impl From<DisplayStyle> for codespan_reporting::term::DisplayStyle {
     fn from(value: DisplayStyle) -> Self {
         match value {
             DisplayStyle::Rich => Self::Rich,
             DisplayStyle::Medium => Self::Medium,
             DisplayStyle::Short => Self::Short,
+            DisplayStyle::Json => Self::Rich,
         }
     }
 }
+
+#[derive(Debug, Clone, Display, ValueEnum)]
+pub enum TimePassesFormat {
+    /// Prints passes of every file and their totals
+    Table,
+    /// Writes Chrome trace event JSON, viewable in `chrome://tracing` or Perfetto
+    Chrome,
+}
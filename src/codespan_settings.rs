@@ -1,26 +1,30 @@
-use codespan_reporting::files::Files;
-use codespan_reporting::term::termcolor::{ColorSpec, StandardStream, WriteColor};
+use codespan_reporting::term::termcolor::{Buffer, ColorSpec, StandardStream, WriteColor};
+use crate::time_passes::time_pass;
 use kodept_macros::context::FileId;
+use kodept_macros::error::report::Report;
 use kodept_macros::error::report_collector::ReportCollector;
+use kodept_macros::error::structured::{ReportFormat, SarifLog, Utf16Columns};
+use kodept_macros::error::suggestion::FixLog;
 use kodept_macros::error::traits::Reportable;
+use std::collections::{BTreeMap, BTreeSet};
 use std::io::Write;
 use std::mem::take;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, RwLock};
 
 pub trait ProvideCollector<Id> {
     fn provide_collector<'a, T, F>(
         &mut self,
         sources: &'a F,
-        f: impl FnOnce(&mut ReportCollector<F::FileId>) -> T,
+        f: impl FnOnce(&ReportCollector<F::FileId>) -> T,
     ) -> T
     where
-        F: Files<'a, FileId = Id>;
+        F: Utf16Columns<'a, FileId = Id>;
 }
 
 pub trait ConsumeCollector<'a, Id> {
     fn consume<F>(self, sources: &'a F)
     where
-        F: Files<'a, FileId = Id>;
+        F: Utf16Columns<'a, FileId = Id>;
 }
 
 pub type CodespanSettings = kodept_macros::error::traits::CodespanSettings<StreamOutput>;
@@ -28,16 +32,122 @@ pub type CodespanSettings = kodept_macros::error::traits::CodespanSettings<Strea
 #[derive(Debug, Clone)]
 pub enum Reports {
     Disabled,
-    Eager(CodespanSettings),
+    Eager {
+        settings: CodespanSettings,
+        order: Arc<Mutex<EmissionOrder>>,
+    },
     Lazy {
-        // TODO: ReportCollector is thread-safe already
-        //       Maybe remove ArcMutex wrapper 
-        local_reports: Arc<Mutex<ReportCollector>>,
-        global_reports: Arc<Mutex<ReportCollector<()>>>,
+        // Collectors are thread-safe, so files share them through read locks.
+        // Write lock is only taken to emit collected reports
+        local_reports: Arc<RwLock<ReportCollector>>,
+        global_reports: Arc<RwLock<ReportCollector<()>>>,
         settings: CodespanSettings,
     },
 }
 
+/// Holds back eager reports of files processed ahead of their turn,
+/// so reports are emitted in order of files even if files are processed concurrently
+#[derive(Debug, Default)]
+pub struct EmissionOrder {
+    next_file: FileId,
+    finished: BTreeSet<FileId>,
+    held: BTreeMap<FileId, Vec<Report>>,
+}
+
+impl Reports {
+    /// Log that collects emitted reports in SARIF format, if requested
+    pub fn sarif_log(&self) -> Option<SarifLog> {
+        match self {
+            Reports::Disabled => None,
+            Reports::Eager { settings, .. } | Reports::Lazy { settings, .. } => {
+                settings.sarif.clone()
+            }
+        }
+    }
+
+    /// Log that collects suggestions to apply, if `--fix` is requested
+    pub fn fix_log(&self) -> Option<FixLog> {
+        match self {
+            Reports::Disabled => None,
+            Reports::Eager { settings, .. } | Reports::Lazy { settings, .. } => {
+                settings.fixes.clone()
+            }
+        }
+    }
+
+    /// Whether `--max-errors` errors are already collected
+    pub fn is_error_limit_reached(&self) -> bool {
+        match self {
+            Reports::Disabled => false,
+            Reports::Eager { settings, .. } | Reports::Lazy { settings, .. } => settings
+                .error_limit
+                .as_ref()
+                .is_some_and(|it| it.is_reached()),
+        }
+    }
+
+    /// Marks the file as processed, emitting eager reports held back until its turn
+    pub fn finish_file<'a, F>(&mut self, file_id: FileId, sources: &'a F)
+    where
+        F: Utf16Columns<'a, FileId = FileId>,
+    {
+        let Reports::Eager { settings, order } = self else {
+            return;
+        };
+        order
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .finish(file_id, settings, sources)
+    }
+}
+
+impl EmissionOrder {
+    /// Emits reports of the current file right away, reports of the following ones are held
+    fn emit_or_hold<'a, F>(
+        &mut self,
+        reports: Vec<Report>,
+        settings: &mut CodespanSettings,
+        sources: &'a F,
+    ) where
+        F: Utf16Columns<'a, FileId = FileId>,
+    {
+        let (current, following): (Vec<_>, Vec<_>) = reports
+            .into_iter()
+            .partition(|it| *it.file_id() <= self.next_file);
+        for report in following {
+            self.held.entry(*report.file_id()).or_default().push(report);
+        }
+        emit_reports(current, settings, sources);
+    }
+
+    fn finish<'a, F>(&mut self, file_id: FileId, settings: &mut CodespanSettings, sources: &'a F)
+    where
+        F: Utf16Columns<'a, FileId = FileId>,
+    {
+        self.finished.insert(file_id);
+        while self.finished.remove(&self.next_file) {
+            if let Some(reports) = self.held.remove(&self.next_file) {
+                emit_reports(reports, settings, sources);
+            }
+            self.next_file += 1;
+        }
+    }
+}
+
+fn emit_reports<'a, F, Id>(
+    reports: Vec<Report<Id>>,
+    settings: &mut CodespanSettings,
+    sources: &'a F,
+) where
+    F: Utf16Columns<'a, FileId = Id>,
+    Id: Copy,
+{
+    if reports.is_empty() {
+        return;
+    }
+    time_pass("emitting reports", || reports.emit(settings, sources))
+}
+
 #[derive(Clone)]
 pub enum SupportColor {
     Yes,
@@ -47,6 +157,8 @@ pub enum SupportColor {
 #[derive(Clone, Debug)]
 pub enum StreamOutput {
     Standard(Arc<Mutex<StandardStream>>),
+    /// Keeps rendered diagnostics in memory
+    Buffer(Arc<Mutex<Buffer>>),
     NoOp,
 }
 
@@ -55,21 +167,27 @@ const POISON_LOCK_ERROR: &str = "Lock was poisoned";
 impl<'a> ConsumeCollector<'a, FileId> for Reports {
     fn consume<F>(self, sources: &'a F)
     where
-        F: Files<'a, FileId = FileId>,
+        F: Utf16Columns<'a, FileId = FileId>,
     {
         match self {
             Reports::Disabled => {}
-            Reports::Eager(_) => {}
+            Reports::Eager {
+                mut settings,
+                order,
+            } => {
+                // Files left after reaching the error limit are never finished
+                let mut order = order.lock().unwrap_or_else(|e| e.into_inner());
+                let held = take(&mut order.held);
+                emit_reports(held.into_values().flatten().collect(), &mut settings, sources)
+            }
             Reports::Lazy {
                 local_reports,
                 mut settings,
                 ..
             } => {
-                let mut lock = local_reports.lock().unwrap_or_else(|e| e.into_inner());
+                let mut lock = local_reports.write().unwrap_or_else(|e| e.into_inner());
                 let collector = take(&mut *lock);
-                collector
-                    .into_collected_reports()
-                    .emit(&mut settings, sources)
+                emit_reports(collector.into_sorted_reports(), &mut settings, sources)
             }
         }
     }
@@ -78,46 +196,69 @@ impl<'a> ConsumeCollector<'a, FileId> for Reports {
 impl ConsumeCollector<'static, ()> for Reports {
     fn consume<F>(self, sources: &'static F)
     where
-        F: Files<'static, FileId = ()>,
+        F: Utf16Columns<'static, FileId = ()>,
     {
         match self {
             Reports::Disabled => {}
-            Reports::Eager(_) => {}
+            Reports::Eager { mut settings, .. } => write_summary(&mut settings),
             Reports::Lazy {
                 global_reports,
                 mut settings,
                 ..
             } => {
-                let mut lock = global_reports.lock().unwrap_or_else(|e| e.into_inner());
+                let mut lock = global_reports.write().unwrap_or_else(|e| e.into_inner());
                 let collector = take(&mut *lock);
-                collector
-                    .into_collected_reports()
-                    .emit(&mut settings, sources)
+                emit_reports(collector.into_collected_reports(), &mut settings, sources);
+                write_summary(&mut settings)
             }
         }
     }
 }
 
+/// Global reports are consumed last, so the summary line is written after all reports
+fn write_summary(settings: &mut CodespanSettings) {
+    if settings.format != ReportFormat::Terminal || settings.summary.is_empty() {
+        return;
+    }
+    let limit = settings
+        .error_limit
+        .as_ref()
+        .filter(|it| it.is_reached())
+        .map(|it| it.max());
+    let result = match limit {
+        None => writeln!(settings.stream, "{}", settings.summary),
+        Some(max) => writeln!(
+            settings.stream,
+            "{}, compilation stopped after reaching the limit of {max} errors",
+            settings.summary
+        ),
+    };
+    result.expect("Cannot emit diagnostics")
+}
+
 impl ProvideCollector<FileId> for Reports {
     fn provide_collector<'a, T, F>(
         &mut self,
         sources: &'a F,
-        f: impl FnOnce(&mut ReportCollector<F::FileId>) -> T,
+        f: impl FnOnce(&ReportCollector<F::FileId>) -> T,
     ) -> T
     where
-        F: Files<'a, FileId = FileId>,
+        F: Utf16Columns<'a, FileId = FileId>,
     {
         match self {
-            Reports::Disabled => f(&mut ReportCollector::new()),
-            Reports::Eager(settings) => {
-                let mut collector = ReportCollector::new();
-                let result = f(&mut collector);
-                collector.into_collected_reports().emit(settings, sources);
+            Reports::Disabled => f(&ReportCollector::new()),
+            Reports::Eager { settings, order } => {
+                let collector = ReportCollector::with_limit(settings.error_limit.clone());
+                let result = f(&collector);
+                order
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .emit_or_hold(collector.into_sorted_reports(), settings, sources);
                 result
             }
             Reports::Lazy { local_reports, .. } => {
-                let mut lock = local_reports.lock().unwrap_or_else(|e| e.into_inner());
-                f(&mut lock)
+                let lock = local_reports.read().unwrap_or_else(|e| e.into_inner());
+                f(&lock)
             }
         }
     }
@@ -127,22 +268,22 @@ impl ProvideCollector<()> for Reports {
     fn provide_collector<'a, T, F>(
         &mut self,
         sources: &'a F,
-        f: impl FnOnce(&mut ReportCollector<F::FileId>) -> T,
+        f: impl FnOnce(&ReportCollector<F::FileId>) -> T,
     ) -> T
     where
-        F: Files<'a, FileId = ()>,
+        F: Utf16Columns<'a, FileId = ()>,
     {
         match self {
-            Reports::Disabled => f(&mut ReportCollector::new()),
-            Reports::Eager(settings) => {
-                let mut collector = ReportCollector::new();
-                let result = f(&mut collector);
-                collector.into_collected_reports().emit(settings, sources);
+            Reports::Disabled => f(&ReportCollector::new()),
+            Reports::Eager { settings, .. } => {
+                let collector = ReportCollector::with_limit(settings.error_limit.clone());
+                let result = f(&collector);
+                emit_reports(collector.into_collected_reports(), settings, sources);
                 result
             }
             Reports::Lazy { global_reports, .. } => {
-                let mut lock = global_reports.lock().unwrap_or_else(|e| e.into_inner());
-                f(&mut lock)
+                let lock = global_reports.read().unwrap_or_else(|e| e.into_inner());
+                f(&lock)
             }
         }
     }
@@ -152,6 +293,7 @@ impl Write for StreamOutput {
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
         match self {
             StreamOutput::Standard(x) => x.lock().expect(POISON_LOCK_ERROR).write(buf),
+            StreamOutput::Buffer(x) => x.lock().expect(POISON_LOCK_ERROR).write(buf),
             StreamOutput::NoOp => Ok(buf.len()),
         }
     }
@@ -159,6 +301,7 @@ impl Write for StreamOutput {
     fn flush(&mut self) -> std::io::Result<()> {
         match self {
             StreamOutput::Standard(x) => x.lock().expect(POISON_LOCK_ERROR).flush(),
+            StreamOutput::Buffer(x) => x.lock().expect(POISON_LOCK_ERROR).flush(),
             StreamOutput::NoOp => Ok(()),
         }
     }
@@ -174,6 +317,13 @@ impl WriteColor for StreamOutput {
                     Err(it) => it.get_ref().supports_color(),
                 }
             }
+            StreamOutput::Buffer(x) => {
+                let guard = x.lock();
+                match guard {
+                    Ok(it) => it.supports_color(),
+                    Err(it) => it.get_ref().supports_color(),
+                }
+            }
             StreamOutput::NoOp => false,
         }
     }
@@ -181,6 +331,7 @@ impl WriteColor for StreamOutput {
     fn set_color(&mut self, spec: &ColorSpec) -> std::io::Result<()> {
         match self {
             StreamOutput::Standard(x) => x.lock().expect(POISON_LOCK_ERROR).set_color(spec),
+            StreamOutput::Buffer(x) => x.lock().expect(POISON_LOCK_ERROR).set_color(spec),
             StreamOutput::NoOp => Ok(()),
         }
     }
@@ -188,7 +339,68 @@ impl WriteColor for StreamOutput {
     fn reset(&mut self) -> std::io::Result<()> {
         match self {
             StreamOutput::Standard(x) => x.lock().expect(POISON_LOCK_ERROR).reset(),
+            StreamOutput::Buffer(x) => x.lock().expect(POISON_LOCK_ERROR).reset(),
             StreamOutput::NoOp => Ok(()),
         }
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use crate::codespan_settings::{CodespanSettings, ProvideCollector, Reports, StreamOutput};
+    use crate::source_files::SourceFiles;
+    use codespan_reporting::term::termcolor::Buffer;
+    use codespan_reporting::term::{Config, DisplayStyle};
+    use kodept_core::code_point::CodePoint;
+    use kodept_core::code_source::CodeSource;
+    use kodept_macros::error::code::KC000;
+    use kodept_macros::error::report::{Label, Severity};
+    use kodept_macros::error::report_collector::Reporter;
+    use kodept_macros::error::structured::ReportFormat;
+    use kodept_macros::error::Diagnostic;
+    use std::sync::{Arc, Mutex};
+
+    fn diagnostic(message: &str) -> Diagnostic {
+        Diagnostic::new(Severity::Warning, KC000)
+            .with_message(message.to_string())
+            .with_label(Label::primary("here", CodePoint::single_point(0)))
+    }
+
+    #[test]
+    fn test_eager_reports_are_emitted_in_order_of_files() {
+        let sources = SourceFiles::from_sources(vec![
+            CodeSource::memory("first".to_string()),
+            CodeSource::memory("second".to_string()),
+        ]);
+        let buffer = Arc::new(Mutex::new(Buffer::no_color()));
+        let mut reports = Reports::Eager {
+            settings: CodespanSettings {
+                config: Config {
+                    display_style: DisplayStyle::Short,
+                    ..Default::default()
+                },
+                stream: StreamOutput::Buffer(buffer.clone()),
+                format: ReportFormat::Terminal,
+                sarif: None,
+                fixes: None,
+                summary: Default::default(),
+                error_limit: None,
+            },
+            order: Default::default(),
+        };
+        let output = || String::from_utf8(buffer.lock().unwrap().as_slice().to_vec()).unwrap();
+
+        reports.provide_collector(&sources, |it| it.report(1, diagnostic("of second")));
+        assert!(output().is_empty());
+        reports.provide_collector(&sources, |it| it.report(0, diagnostic("of first")));
+        reports.finish_file(1, &sources);
+        assert!(!output().contains("of second"));
+        reports.finish_file(0, &sources);
+
+        let output = output();
+        let first = output.find("of first").unwrap();
+        let second = output.find("of second").unwrap();
+        assert!(first < second);
+    }
+}
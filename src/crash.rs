@@ -0,0 +1,125 @@
+use crate::source_files::SourceView;
+use kodept_core::code_point::CodePoint;
+use kodept_macros::error::compiler_crash::CompilerCrash;
+use std::backtrace::Backtrace;
+use std::cell::{Cell, RefCell};
+use std::fs::{create_dir_all, File};
+use std::io::Write;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+
+/// Number of lines shown around the crash location in the bundle
+const SNIPPET_CONTEXT: usize = 2;
+
+thread_local! {
+    static VISITING: Cell<Option<CodePoint>> = const { Cell::new(None) };
+    static BACKTRACE: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Panic caught while processing a single file
+pub struct Crash {
+    pub payload: CompilerCrash,
+    /// Location of the node that was visited when the panic happened
+    pub location: Option<CodePoint>,
+    pub backtrace: Option<String>,
+}
+
+/// Records backtraces of panics, so they can be put into crash bundles.
+/// Panics are still printed by the previously installed hook
+pub fn install_panic_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let backtrace = format!("{info}\n\n{}", Backtrace::force_capture());
+        BACKTRACE.with(|it| it.replace(Some(backtrace)));
+        previous(info);
+    }));
+}
+
+/// Remembers the node being visited by the current thread, `None` when no node is visited
+pub fn set_visiting(location: Option<CodePoint>) {
+    VISITING.set(location);
+}
+
+/// Runs `f`, turning a panic inside it into a [`Crash`]
+pub fn catch_crash<T>(f: impl FnOnce() -> T) -> Result<T, Crash> {
+    set_visiting(None);
+    BACKTRACE.with(|it| it.take());
+    catch_unwind(AssertUnwindSafe(f)).map_err(|payload| Crash {
+        payload: CompilerCrash::new(payload),
+        location: VISITING.take(),
+        backtrace: BACKTRACE.with(|it| it.take()),
+    })
+}
+
+impl Crash {
+    /// Writes the information needed to reproduce the crash next to the other outputs
+    pub fn write_bundle(&self, source: &SourceView, output: &Path) -> std::io::Result<PathBuf> {
+        let name = source.path();
+        // Files from different directories may share a name, so the id of file is added to it
+        let path = name
+            .build_file_path()
+            .with_extension(format!("{}.kd.crash", *source.id));
+        let filename = path.file_name().unwrap_or("crash".as_ref());
+        create_dir_all(output)?;
+        let bundle_path = output.join(filename);
+        let mut file = File::create(&bundle_path)?;
+
+        writeln!(file, "Kodept {}", env!("CARGO_PKG_VERSION"))?;
+        writeln!(file, "file: {}", source.path())?;
+        writeln!(file, "message: {}", self.payload)?;
+        match self.location {
+            Some(at) => writeln!(file, "visiting: bytes {:?}", at.as_range())?,
+            None => writeln!(file, "visiting: unknown")?,
+        }
+        writeln!(file, "\n-- input --")?;
+        write!(file, "{}", snippet(source.contents(), self.location))?;
+        writeln!(file, "\n-- backtrace --")?;
+        writeln!(file, "{}", self.backtrace.as_deref().unwrap_or("not captured"))?;
+        Ok(bundle_path)
+    }
+}
+
+/// Lines of the node with some context around, or the whole input if the node is unknown
+fn snippet(contents: &str, location: Option<CodePoint>) -> String {
+    let line_of = |offset: usize| {
+        let offset = offset.min(contents.len());
+        contents.as_bytes()[..offset]
+            .iter()
+            .filter(|it| **it == b'\n')
+            .count()
+    };
+    let (first, last) = match location {
+        Some(at) => {
+            let range = at.as_range();
+            (
+                line_of(range.start).saturating_sub(SNIPPET_CONTEXT),
+                line_of(range.end) + SNIPPET_CONTEXT,
+            )
+        }
+        None => (0, usize::MAX),
+    };
+
+    contents
+        .lines()
+        .enumerate()
+        .skip(first)
+        .take_while(|(index, _)| *index <= last)
+        .map(|(index, line)| format!("{:>5} | {line}\n", index + 1))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crash::snippet;
+    use kodept_core::code_point::CodePoint;
+
+    #[test]
+    fn test_snippet_around_location() {
+        let contents = "a\nb\nc\nd\ne\nf\ng";
+
+        assert_eq!(
+            snippet(contents, Some(CodePoint::single_point(6))),
+            "    2 | b\n    3 | c\n    4 | d\n    5 | e\n    6 | f\n"
+        );
+    }
+}
@@ -1,8 +1,11 @@
 pub mod codespan_settings;
 pub mod common_iter;
+pub mod crash;
 pub mod loader;
+pub mod manifest;
 pub mod read_code_source;
 pub mod source_files;
 pub mod steps;
+pub mod time_passes;
 pub mod profiler;
 pub mod hlist;
@@ -7,6 +7,7 @@ use std::path::{Path, PathBuf};
 
 use itertools::Itertools;
 use kodept_core::code_source::{CodeSource, CodeSourceError};
+use kodept_macros::error::code::{CodedError, ErrorCode, KC010, KC011};
 use thiserror::Error;
 use tracing::{debug, warn};
 
@@ -29,6 +30,17 @@ pub enum LoadingError {
     NoInput,
 }
 
+impl CodedError for LoadingError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            LoadingError::StartingPathNotAbsolute
+            | LoadingError::InputDoesNotExists
+            | LoadingError::NoInput => KC010,
+            LoadingError::IOError(_) | LoadingError::MapError(_) => KC011,
+        }
+    }
+}
+
 pub struct LoaderBuilder<'p> {
     starting_path: Cow<'p, Path>,
     extension: Cow<'p, OsStr>,
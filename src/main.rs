@@ -1,8 +1,13 @@
 use clap::Parser;
 use cli::common::Kodept;
 use kodept::codespan_settings::{ConsumeCollector, Reports};
+use kodept::crash::install_panic_hook;
 use kodept::profiler::HeapProfiler;
 use kodept::source_files::GlobalReports;
+use kodept::time_passes;
+use cli::utils::TimePassesFormat;
+use std::fs::{create_dir_all, File};
+use std::path::Path;
 
 mod cli;
 
@@ -11,17 +16,50 @@ type WideError = anyhow::Error;
 fn main() -> Result<(), WideError> {
     let mut lock = HeapProfiler::install();
     lock.consume_on_ctrlc();
+    install_panic_hook();
 
     let cli_arguments: Kodept = Kodept::parse();
+    // Stdout is left for JSON diagnostics and formatted code
     tracing_subscriber::fmt()
         .with_max_level(cli_arguments.level())
+        .with_writer(std::io::stderr)
         .init();
+    if cli_arguments.time_passes.is_some() {
+        time_passes::enable();
+    }
 
+    let sarif_path = cli_arguments
+        .diagnostic_config
+        .sarif_path()
+        .map(Path::to_path_buf);
+    let time_passes_format = cli_arguments.time_passes;
+    let trace_dir = cli_arguments.output.clone();
     let reports: Reports = cli_arguments.diagnostic_config.into();
+    let sarif = reports.sarif_log();
+    let fixes = reports.fix_log();
     let result = cli_arguments
         .subcommands
         .execute(cli_arguments.output, reports.clone());
     reports.consume(&GlobalReports);
+    if let (Some(path), Some(log)) = (sarif_path, sarif) {
+        log.write(File::create(path)?)?;
+    }
+    if let (Some(format), Some(timings)) = (time_passes_format, time_passes::timings()) {
+        match format {
+            TimePassesFormat::Table => timings.write_table(std::io::stderr().lock())?,
+            TimePassesFormat::Chrome => {
+                create_dir_all(&trace_dir)?;
+                let trace_path = trace_dir.join("time-passes.json");
+                timings.write_chrome_trace(File::create(&trace_path)?)?;
+                tracing::info!("Wrote pass timings to {}", trace_path.display());
+            }
+        }
+    }
+    if let Some(fixes) = fixes {
+        for (path, count) in fixes.apply()? {
+            tracing::info!("Applied {count} fix(es) to {}", path.display());
+        }
+    }
 
     Ok(result?)
 }
@@ -0,0 +1,121 @@
+use kodept_macros::error::code::{CodedError, ErrorCode, KC012};
+use kodept_macros::lint::{LintLevel, UnknownLintLevel};
+use std::path::Path;
+use thiserror::Error;
+
+pub const DEFAULT_MANIFEST: &str = "kodept.toml";
+
+/// Project settings read from `kodept.toml`.
+/// Only a subset of TOML is understood: tables and `key = "string"` pairs
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Manifest {
+    /// Levels from the `[lints]` table in the order they are written
+    pub lints: Vec<(String, LintLevel)>,
+}
+
+#[derive(Debug, Error)]
+pub enum ManifestError {
+    #[error("Cannot read manifest: {0}")]
+    IOError(#[from] std::io::Error),
+    #[error("Line {line}: expected `key = \"value\"` or `[table]`")]
+    UnexpectedLine { line: usize },
+    #[error("Line {line}: value of `{key}` should be a quoted string")]
+    UnquotedValue { line: usize, key: String },
+    #[error("Line {line}: {source}")]
+    InvalidLevel {
+        line: usize,
+        #[source]
+        source: UnknownLintLevel,
+    },
+}
+
+impl CodedError for ManifestError {
+    fn code(&self) -> ErrorCode {
+        KC012
+    }
+}
+
+impl Manifest {
+    pub fn read(path: &Path) -> Result<Self, ManifestError> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, ManifestError> {
+        let mut manifest = Manifest::default();
+        let mut table = String::new();
+
+        for (index, line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.split_once('#').map_or(line, |(it, _)| it).trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|it| it.strip_suffix(']')) {
+                table = name.trim().to_string();
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(ManifestError::UnexpectedLine { line: line_number });
+            };
+            let key = key.trim();
+            let Some(value) = value
+                .trim()
+                .strip_prefix('"')
+                .and_then(|it| it.strip_suffix('"'))
+            else {
+                return Err(ManifestError::UnquotedValue {
+                    line: line_number,
+                    key: key.to_string(),
+                });
+            };
+
+            if table == "lints" {
+                let level = value
+                    .parse()
+                    .map_err(|source| ManifestError::InvalidLevel {
+                        line: line_number,
+                        source,
+                    })?;
+                manifest.lints.push((key.to_string(), level));
+            }
+        }
+        Ok(manifest)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use crate::manifest::{Manifest, ManifestError};
+    use kodept_macros::lint::LintLevel;
+
+    #[test]
+    fn test_parse_lints_table() {
+        let text = r#"
+            # project settings
+            [package]
+            name = "example"
+
+            [lints]
+            unused_variables = "deny" # fail on unused variables
+            shadowed-bindings = "warn"
+        "#;
+        let manifest = Manifest::parse(text).unwrap();
+
+        assert_eq!(
+            manifest.lints,
+            vec![
+                ("unused_variables".to_string(), LintLevel::Deny),
+                ("shadowed-bindings".to_string(), LintLevel::Warn),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_level() {
+        let error = Manifest::parse("[lints]\nunused_variables = \"forbid\"").unwrap_err();
+
+        assert!(matches!(error, ManifestError::InvalidLevel { line: 2, .. }));
+    }
+}
@@ -5,6 +5,10 @@ use std::sync::atomic::{AtomicBool, Ordering};
 #[cfg(feature = "profiler")]
 static ALLOC: dhat::Alloc = dhat::Alloc;
 
+#[global_allocator]
+#[cfg(not(feature = "profiler"))]
+static ALLOC: crate::time_passes::CountingAlloc = crate::time_passes::CountingAlloc;
+
 #[allow(dead_code)]
 static PROFILER: HeapProfiler = HeapProfiler::new();
 
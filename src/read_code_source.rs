@@ -1,8 +1,10 @@
-use codespan_reporting::files::{line_starts, Error, Files};
+use codespan_reporting::files::{Error, Files};
 use kodept_core::code_point::CodePoint;
 use kodept_core::code_source::CodeSource;
 use kodept_core::file_name::FileName;
+use kodept_core::line_index::LineIndex;
 use kodept_core::structure::span::CodeHolder;
+use kodept_macros::error::structured::Utf16Columns;
 use mmap_rs::Mmap;
 use std::borrow::Cow;
 use std::env::current_dir;
@@ -30,7 +32,7 @@ pub enum ReadCodeSourceError {
 pub struct ReadCodeSource {
     source_contents: ReadImpl,
     source_path: FileName,
-    line_starts: Vec<usize>,
+    line_index: LineIndex,
 }
 
 impl ReadCodeSource {
@@ -45,8 +47,9 @@ impl ReadCodeSource {
         }
     }
 
-    pub(crate) fn line_starts(&self) -> &[usize] {
-        &self.line_starts
+    /// Positions of lines in the contents, built once on reading
+    pub fn lines(&self) -> &LineIndex {
+        &self.line_index
     }
 }
 
@@ -57,22 +60,22 @@ impl TryFrom<CodeSource> for ReadCodeSource {
         let path = value.path().get_relative_path(&current_dir()?);
         match value {
             CodeSource::Memory { contents, .. } => {
-                let starts = line_starts(contents.get_ref()).collect();
+                let line_index = LineIndex::new(contents.get_ref());
                 Ok(Self {
                     source_contents: ReadImpl::Explicit(contents.into_inner()),
                     source_path: path,
-                    line_starts: starts,
+                    line_index,
                 })
             }
             CodeSource::File { mut file, .. } => {
                 let mut buf = Vec::with_capacity(1024);
                 file.read_to_end(&mut buf)?;
                 let buf = String::from_utf8(buf)?;
-                let starts = line_starts(&buf).collect();
+                let line_index = LineIndex::new(&buf);
                 Ok(Self {
                     source_contents: ReadImpl::Explicit(buf),
                     source_path: path,
-                    line_starts: starts,
+                    line_index,
                 })
             }
             CodeSource::MappedFile { map, .. } => {
@@ -80,11 +83,11 @@ impl TryFrom<CodeSource> for ReadCodeSource {
                     Result::<_, ReadCodeSourceError>::Ok(Cow::Borrowed(from_utf8(it)?))
                 })?;
                 let contents: &Cow<_> = buf.get();
-                let starts = line_starts(contents).collect();
+                let line_index = LineIndex::new(contents);
                 Ok(Self {
                     source_contents: ReadImpl::Implicit(buf),
                     source_path: path,
-                    line_starts: starts,
+                    line_index,
                 })
             }
         }
@@ -116,35 +119,31 @@ impl<'a> Files<'a> for ReadCodeSource {
     }
 
     fn line_index(&'a self, (): (), byte_index: usize) -> Result<usize, Error> {
-        Ok(self
-            .line_starts()
-            .binary_search(&byte_index)
-            .unwrap_or_else(|next_line| next_line - 1))
+        u32::try_from(byte_index)
+            .ok()
+            .and_then(|it| self.line_index.line_of(it))
+            .map(|it| it as usize)
+            .ok_or(Error::IndexTooLarge {
+                given: byte_index,
+                max: self.contents().len(),
+            })
     }
 
     fn line_range(&'a self, (): (), line_index: usize) -> Result<Range<usize>, Error> {
-        let line_start = self.line_start(line_index)?;
-        let next_line_start = self.line_start(line_index + 1)?;
-
-        Ok(line_start..next_line_start)
+        u32::try_from(line_index)
+            .ok()
+            .and_then(|it| self.line_index.line_range(it))
+            .map(|it| it.start as usize..it.end as usize)
+            .ok_or(Error::LineTooLarge {
+                given: line_index,
+                max: self.line_index.line_count() - 1,
+            })
     }
 }
 
-impl ReadCodeSource {
-    fn line_start(&self, line_index: usize) -> Result<usize, Error> {
-        use std::cmp::Ordering;
-
-        match line_index.cmp(&self.line_starts().len()) {
-            Ordering::Less => Ok(self
-                .line_starts()
-                .get(line_index)
-                .cloned()
-                .expect("failed despite previous check")),
-            Ordering::Equal => Ok(self.contents().len()),
-            Ordering::Greater => Err(Error::LineTooLarge {
-                given: line_index,
-                max: self.line_starts().len() - 1,
-            }),
-        }
+impl<'a> Utf16Columns<'a> for ReadCodeSource {
+    fn utf16_column(&'a self, (): (), byte_index: usize) -> Option<usize> {
+        let position = self.line_index.position(u32::try_from(byte_index).ok()?)?;
+        Some(position.utf16_column as usize)
     }
 }
@@ -5,7 +5,8 @@ use kodept_core::code_source::CodeSource;
 use kodept_core::file_name::FileName;
 use kodept_core::Freeze;
 use kodept_macros::context::{FileDescriptor, FileId};
-use std::collections::HashMap;
+use kodept_macros::error::structured::Utf16Columns;
+use std::collections::BTreeMap;
 use std::ops::{Deref, Range};
 use std::sync::Arc;
 use tracing::error;
@@ -21,7 +22,8 @@ pub struct SourceView {
 
 #[derive(Debug)]
 pub struct SourceFiles {
-    contents: HashMap<FileId, ReadCodeSource>,
+    /// Ordered by id, so files are visited in the order they were loaded
+    contents: BTreeMap<FileId, ReadCodeSource>,
 }
 
 impl Deref for SourceView {
@@ -54,6 +56,8 @@ impl Files<'static> for GlobalReports {
     }
 }
 
+impl Utf16Columns<'static> for GlobalReports {}
+
 impl SourceView {
     pub fn all_files(&self) -> &SourceFiles {
         self.source.backing_cart()
@@ -165,3 +169,15 @@ impl<'a> Files<'a> for SourceView {
         self.source.get().line_range((), line_index)
     }
 }
+
+impl<'a> Utf16Columns<'a> for SourceFiles {
+    fn utf16_column(&'a self, id: Self::FileId, byte_index: usize) -> Option<usize> {
+        self.contents.get(&id)?.utf16_column((), byte_index)
+    }
+}
+
+impl<'a> Utf16Columns<'a> for SourceView {
+    fn utf16_column(&'a self, _: Self::FileId, byte_index: usize) -> Option<usize> {
+        self.source.get().utf16_column((), byte_index)
+    }
+}
@@ -1,41 +1,378 @@
 use crate::steps::pipeline::Pipeline;
 use crate::steps::Step;
+use crate::time_passes::time_pass;
 use derive_more::Constructor;
 use kodept_interpret::operator_desugaring::{
     AccessExpander, BinaryOperatorExpander, UnaryOperatorExpander,
 };
+use kodept_inference::assumption::{Environment, TypeTableOps};
 use kodept_macros::context::Context;
 use std::num::NonZeroU16;
 use tracing::info;
+use kodept_interpret::foreign::ForeignFnCollector;
+use kodept_interpret::interpolation::InterpolationExpander;
+use kodept_interpret::kind_checker::KindChecker;
+use kodept_interpret::lints::{
+    NonPascalCaseTypes, RedundantElse, ShadowedBindings, UnusedFunctions, UnusedVariables,
+};
+use kodept_interpret::literal::LiteralDecoder;
+use kodept_interpret::record::RecordChecker;
+use kodept_interpret::recursive_enum::RecursiveEnumChecker;
 use kodept_interpret::scope_analyzer::ScopeAnalyzer;
+use kodept_interpret::type_alias::TypeAliasExpander;
 
 #[derive(Constructor)]
 pub struct Config {
     pub recursion_depth: NonZeroU16,
 }
 
+/// Facts about the program collected by the common steps, passed on to type checking
+pub struct Analysis {
+    pub aliases: TypeAliasExpander,
+    /// Signatures type inference starts with: operators from the prelude and `foreign` functions
+    pub environment: Environment,
+    /// Values of numeric, char and string literals
+    pub literals: LiteralDecoder,
+}
+
 pub fn run_common_steps(
     ctx: &mut Context,
     config: &Config,
-) -> Option<()> {
+) -> Option<Analysis> {
     info!("Step 1: Simplify AST");
-    let (_, _, _) = Pipeline
-        .define_step((
-            AccessExpander::new(),
-            BinaryOperatorExpander::new(),
-            UnaryOperatorExpander::new(),
-        ))
-        .apply_with_context(ctx)?;
+    let (_, _, _, _) = time_pass("simplifying AST", || {
+        Pipeline
+            .define_step((
+                AccessExpander::new(),
+                BinaryOperatorExpander::new(),
+                UnaryOperatorExpander::new(),
+                InterpolationExpander::new(),
+            ))
+            .apply_with_context(ctx)
+    })?;
 
     info!("Step 2: Split by scopes and resolve symbols");
-    let (scopes,) = Pipeline
-        .define_step((ScopeAnalyzer::new(),))
-        .apply_with_context(ctx)?;
+    let (scopes, aliases, _, _, _, literals) = time_pass("resolving symbols", || {
+        Pipeline
+            .define_step((
+                ScopeAnalyzer::new(),
+                TypeAliasExpander::new(),
+                KindChecker::new(),
+                RecursiveEnumChecker::new(),
+                RecordChecker::new(),
+                LiteralDecoder::new(),
+            ))
+            .apply_with_context(ctx)
+    })?;
+    // Needs expansions of all aliases, which are known only after the whole file is visited
+    let (foreign,) = time_pass("resolving symbols", || {
+        Pipeline
+            .define_step((ForeignFnCollector::new(&aliases),))
+            .apply_with_context(ctx)
+    })?;
+    let mut environment = Environment::prelude();
+    environment.merge(foreign.into_signatures());
+
+    info!("Step 3: Run lints");
+    let (_, _, _, _, _) = time_pass("running lints", || {
+        Pipeline
+            .define_step((
+                UnusedVariables::new(),
+                UnusedFunctions::new(),
+                ShadowedBindings::new(),
+                RedundantElse::new(),
+                NonPascalCaseTypes::new(),
+            ))
+            .apply_with_context(ctx)
+    })?;
     
-    // info!("Step 3: Infer and check types");
+    // Type checking is disabled until the conversion of the AST to the inference model is ported
+    // to the current scope analyzer, so aliases, foreign signatures, type arguments and record
+    // fields are only checked syntactically and collected into `Analysis` for it
+    // info!("Step 4: Infer and check types");
     // let (_,) = Pipeline
     //     .define_step((TypeChecker::new(&scopes, config.recursion_depth),))
     //     .apply_with_context(ctx)?;
 
-    Some(())
+    Some(Analysis {
+        aliases,
+        environment,
+        literals,
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use crate::read_code_source::ReadCodeSource;
+    use crate::steps::common::{run_common_steps, Analysis, Config};
+    use kodept_ast::graph::SyntaxTree;
+    use kodept_ast::interning::InterningCodeHolder;
+    use kodept_core::code_source::CodeSource;
+    use kodept_core::file_name::FileName;
+    use kodept_core::Freeze;
+    use kodept_interpret::interpolation::InterpolationExpander;
+    use kodept_interpret::literal::LiteralValue;
+    use crate::steps::pipeline::Pipeline;
+    use crate::steps::Step;
+    use itertools::Itertools;
+    use kodept_ast::graph::AnyNode;
+    use kodept_ast::traits::AsEnum;
+    use kodept_ast::{
+        Appl, ExpressionEnum, Identifier, LitEnum, Operation, OperationEnum, Ref,
+        ReferenceContext, TermEnum,
+    };
+    use kodept_ast::utils::Skip;
+    use kodept_ast::utils::Skip::Skipped;
+    use kodept_ast::visit_side::VisitSide;
+    use kodept_ast::ModDecl;
+    use kodept_macros::context::{Context, FileDescriptor, FileId};
+    use kodept_macros::error::report_collector::ReportCollector;
+    use kodept_macros::visit_guard::VisitGuard;
+    use kodept_macros::Macro;
+    use kodept_parse::common::RecoveringRLTProducer;
+    use kodept_parse::lexer::PegLexer;
+    use kodept_parse::parser::PegParser;
+    use kodept_parse::token_stream::PackedTokenStream;
+    use kodept_parse::tokenizer::{LazyTokenizer, Tok, TokCtor};
+    use std::convert::Infallible;
+    use std::num::NonZeroU16;
+    use std::sync::mpsc;
+    use std::sync::mpsc::Receiver;
+    use std::thread;
+
+    /// Runs `f` with the context of a well-formed program, returning the number of reported errors
+    fn with_context<T>(source: &str, f: impl FnOnce(&mut Context) -> T) -> (T, usize) {
+        let collector = ReportCollector::new();
+        let result = with_collector(source, &collector, 0, f);
+        (result, collector.error_count())
+    }
+
+    /// Runs `f` with the context of a well-formed program reporting to `collector` as file `id`
+    fn with_collector<T>(
+        source: &str,
+        collector: &ReportCollector,
+        id: FileId,
+        f: impl FnOnce(&mut Context) -> T,
+    ) -> T {
+        let source = ReadCodeSource::try_from(CodeSource::memory(source.to_string())).unwrap();
+        let tokens = LazyTokenizer::new(source.contents(), PegLexer::<false>::new()).into_vec();
+        let stream = PackedTokenStream::new(&tokens);
+        let (rlt, errors) = PegParser::<false>::new().parse_recovering(&stream).unwrap();
+        assert!(errors.is_empty(), "Program should be well-formed");
+
+        let (ast, rlt) = SyntaxTree::recursively_build(&rlt, InterningCodeHolder::new(&source));
+        let mut context = Context {
+            ast,
+            rlt,
+            collector,
+            current_file: Freeze::new(FileDescriptor {
+                name: FileName::Anon,
+                id,
+            }),
+            lints: Default::default(),
+        };
+        f(&mut context)
+    }
+
+    fn analyze(source: &str) -> (Option<Analysis>, usize) {
+        let config = Config::new(NonZeroU16::new(256).unwrap());
+        with_context(source, |ctx| run_common_steps(ctx, &config))
+    }
+
+    fn signature(analysis: &Analysis, name: &str) -> Option<String> {
+        analysis
+            .environment
+            .iter()
+            .find(|(it, _)| it.name == name)
+            .map(|(_, ty)| ty.to_string())
+    }
+
+    #[test]
+    fn test_aliases_are_expanded() {
+        let (analysis, errors) = analyze(
+            "module Main {
+                type Pair = (Id, Int)
+                type Id = Int
+                fun main() => 1
+            }",
+        );
+        let analysis = analysis.unwrap();
+
+        assert_eq!(errors, 0);
+        assert_eq!(analysis.aliases.expand("Main", "Id").unwrap().to_string(), "Int");
+        assert_eq!(
+            analysis.aliases.expand("Main", "Pair").unwrap().to_string(),
+            "(Int, Int)"
+        );
+    }
+
+    #[test]
+    fn test_cyclic_aliases_are_reported_at_every_alias_of_cycle() {
+        let (analysis, errors) = analyze(
+            "module Main {
+                type A = (B, Int)
+                type B = A
+                type C = A
+                fun main() => 1
+            }",
+        );
+
+        assert!(analysis.is_none());
+        // `C` only refers to the cycle and is not a part of it
+        assert_eq!(errors, 2);
+    }
+
+    /// Waits for the signal when entering the first module
+    struct WaitFor(Option<Receiver<()>>);
+
+    impl Macro for WaitFor {
+        type Error = Infallible;
+        type Node = ModDecl;
+        type Ctx<'a> = Context<'a>;
+
+        fn apply(
+            &mut self,
+            guard: VisitGuard<Self::Node>,
+            _: &mut Self::Ctx<'_>,
+        ) -> Result<(), Skip<Self::Error>> {
+            guard.allow_only(VisitSide::Entering).ok_or(Skipped)?;
+            if let Some(receiver) = self.0.take() {
+                receiver.recv().unwrap();
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_errors_of_other_file_do_not_stop_analysis() {
+        let config = Config::new(NonZeroU16::new(256).unwrap());
+        let collector = ReportCollector::new();
+        let (sender, receiver) = mpsc::channel();
+
+        let analysis = thread::scope(|scope| {
+            let (collector, config) = (&collector, &config);
+            scope.spawn(move || {
+                let source = "module Other {\n    type A = B\n    type B = A\n}";
+                with_collector(source, collector, 1, |ctx| run_common_steps(ctx, config));
+                sender.send(()).unwrap();
+            });
+            with_collector("module Main {\n    fun main() => 1\n}", collector, 0, |ctx| {
+                // Errors of the other file are reported while this step runs
+                let (_,) = Pipeline
+                    .define_step((WaitFor(Some(receiver)),))
+                    .apply_with_context(ctx)?;
+                run_common_steps(ctx, config)
+            })
+        });
+
+        assert!(analysis.is_some());
+        assert_eq!(collector.file_error_count(&1), 2);
+        assert_eq!(collector.file_error_count(&0), 0);
+    }
+
+    #[test]
+    fn test_foreign_signatures_are_collected() {
+        let (analysis, errors) = analyze(
+            "module Main {
+                type Text = List<Char>
+                enum class List<T> { Nil, Cons(T, List<T>) }
+                foreign fun print(text: Text)
+                foreign fun first<T>(items: List<T>, fallback: T): T
+                foreign fun pi: (Float, Text)
+                fun main() => 1
+            }",
+        );
+        let analysis = analysis.unwrap();
+
+        assert_eq!(errors, 0);
+        assert_eq!(signature(&analysis, "print").unwrap(), "List<Char> -> ()");
+        assert_eq!(signature(&analysis, "first").unwrap(), "∀τ0 => List<τ0> -> τ0 -> τ0");
+        assert_eq!(signature(&analysis, "pi").unwrap(), "(Float, List<Char>)");
+        // Prelude is kept along with foreign functions
+        assert!(signature(&analysis, "__add_internal").is_some());
+        assert_eq!(signature(&analysis, "main"), None);
+    }
+
+    #[test]
+    fn test_literals_are_decoded() {
+        let (analysis, errors) = analyze(
+            r#"module Main {
+                fun main() {
+                    val mask = 0xFF_FF
+                    val text = "tab\tA\u{42}"
+                    val letter = '\n'
+                    mask
+                }
+            }"#,
+        );
+        let analysis = analysis.unwrap();
+        let values: Vec<_> = analysis.literals.values().map(|(_, it)| it.clone()).collect();
+
+        assert_eq!(errors, 0);
+        assert_eq!(values.len(), 3);
+        assert!(values.contains(&LiteralValue::Integral(0xFFFF)));
+        assert!(values.contains(&LiteralValue::String("tab\tAB".to_string())));
+        assert!(values.contains(&LiteralValue::Char('\n')));
+    }
+
+    fn render_appl(appl: &Appl, ast: &SyntaxTree) -> String {
+        format!(
+            "{}({})",
+            render(appl.expr(ast), ast),
+            appl.params(ast).into_iter().map(|it| render(it, ast)).join(", ")
+        )
+    }
+
+    fn render(operation: &Operation, ast: &SyntaxTree) -> String {
+        match operation.as_enum() {
+            OperationEnum::Appl(x) => render_appl(x, ast),
+            OperationEnum::Expr(x) => match x.as_enum() {
+                ExpressionEnum::Lit(x) => match x.as_enum() {
+                    LitEnum::Str(x) => x.value.to_string(),
+                    _ => panic!("Unexpected literal"),
+                },
+                ExpressionEnum::Term(x) => match x.as_enum() {
+                    TermEnum::Ref(Ref {
+                        context,
+                        ident: Identifier::Reference { name },
+                        ..
+                    }) if *context == ReferenceContext::global(["Prelude"]) => {
+                        format!("Prelude::{name}")
+                    }
+                    TermEnum::Ref(Ref {
+                        ident: Identifier::Reference { name },
+                        ..
+                    }) => name.to_string(),
+                    _ => panic!("Unexpected reference"),
+                },
+                _ => panic!("Unexpected expression"),
+            },
+            _ => panic!("Unexpected operation"),
+        }
+    }
+
+    #[test]
+    fn test_interpolation_is_desugared_to_concatenation() {
+        let source = r#"module Main {
+            fun main(name, count) => "Hi, ${name}! ${"${count}"} left"
+        }"#;
+        let (body, errors) = with_context(source, |ctx| {
+            let (_,) = Pipeline
+                .define_step((InterpolationExpander::new(),))
+                .apply_with_context(ctx)
+                .unwrap();
+            // The outermost application is visited first
+            ctx.ast.dfs().find_map(|(id, _)| match ctx.ast.get(id)? {
+                AnyNode::Appl(x) => Some(render_appl(x, &ctx.ast)),
+                _ => None,
+            })
+        });
+
+        assert_eq!(errors, 0);
+        assert_eq!(
+            body.unwrap(),
+            r#"Prelude::__concat_internal("Hi, ${, name, }! ${, Prelude::__concat_internal("${, count, }"), } left")"#
+        );
+    }
 }
@@ -1,3 +1,4 @@
+use crate::crash::set_visiting;
 use crate::hlist::{FromHList, HCons, HList, HNil};
 use kodept_ast::graph::{AnyNodeId};
 use kodept_ast::graph::node_props::Node;
@@ -86,11 +87,16 @@ where
     let mut iter = ctx.ast.dfs().detach();
 
     while let Some((node_id, side)) = iter.next(&ctx.ast) {
+        if ctx.collector.is_limit_reached() {
+            break;
+        }
+        set_visiting(ctx.rlt.get_unknown(node_id).map(|it| it.location()));
         match macros.apply(Pack { node_id, side, ctx }) {
             Some(e) => e.into_iter().for_each(|it| ctx.collector.push_report(it)),
             None => continue
         }
     }
+    set_visiting(None);
 }
 
 pub trait Step
@@ -107,8 +113,12 @@ where
         for<'a> Self::Inputs: RunMacros<Ctx<'a> = Context<'a>>,
     {
         let mut contents = self.into_contents();
+        // Collector may be shared with other files, so only new errors of this file are taken
+        // into account
+        let file_id = ctx.current_file.id;
+        let errors_before = ctx.collector.file_error_count(&file_id);
         run_macros(ctx, &mut contents);
-        if ctx.collector.has_errors() {
+        if ctx.collector.file_error_count(&file_id) != errors_before {
             None
         } else {
             Some(O::from_hlist(contents))
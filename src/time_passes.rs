@@ -0,0 +1,348 @@
+use serde_json::json;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+static TIMINGS: OnceLock<PassTimings> = OnceLock::new();
+static NEXT_THREAD_INDEX: AtomicU64 = AtomicU64::new(0);
+/// Set by [`enable`], so allocations are not counted when passes are not recorded
+static COUNTING: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static ALLOCATIONS: Cell<AllocStats> = const { Cell::new(AllocStats { count: 0, bytes: 0 }) };
+    static CURRENT_FILE: RefCell<Option<Arc<str>>> = const { RefCell::new(None) };
+    static THREAD_INDEX: Cell<Option<u64>> = const { Cell::new(None) };
+}
+
+/// System allocator that counts allocations made by every thread
+pub struct CountingAlloc;
+
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct AllocStats {
+    pub count: u64,
+    pub bytes: u64,
+}
+
+/// Single run of a compiler pass
+#[derive(Debug, Clone)]
+pub struct PassRecord {
+    pub name: Cow<'static, str>,
+    /// File being processed, `None` for passes that handle all files at once
+    pub file: Option<Arc<str>>,
+    pub thread: u64,
+    /// Time since the recording is enabled
+    pub start: Duration,
+    pub duration: Duration,
+    /// Allocations made by the thread running the pass
+    pub allocations: AllocStats,
+}
+
+/// Collects records of passes when `--time-passes` is given
+#[derive(Debug)]
+pub struct PassTimings {
+    origin: Instant,
+    records: Mutex<Vec<PassRecord>>,
+}
+
+#[allow(unsafe_code)]
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        count_allocation(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        count_allocation(layout.size());
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        count_allocation(new_size);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+fn count_allocation(bytes: usize) {
+    if !COUNTING.load(Ordering::Relaxed) {
+        return;
+    }
+    let _ = ALLOCATIONS.try_with(|it| {
+        let stats = it.get();
+        it.set(AllocStats {
+            count: stats.count + 1,
+            bytes: stats.bytes + bytes as u64,
+        })
+    });
+}
+
+fn thread_index() -> u64 {
+    THREAD_INDEX.with(|it| match it.get() {
+        Some(index) => index,
+        None => {
+            let index = NEXT_THREAD_INDEX.fetch_add(1, Ordering::Relaxed);
+            it.set(Some(index));
+            index
+        }
+    })
+}
+
+impl AllocStats {
+    /// Allocations made by the current thread since the recording was enabled.
+    /// Always zero if the counting allocator is replaced by the heap profiler
+    pub fn current() -> Self {
+        ALLOCATIONS.with(Cell::get)
+    }
+
+    fn since(self, earlier: Self) -> Self {
+        Self {
+            count: self.count - earlier.count,
+            bytes: self.bytes - earlier.bytes,
+        }
+    }
+}
+
+/// Starts recording of passes, repeated calls do nothing
+pub fn enable() {
+    TIMINGS.get_or_init(|| PassTimings {
+        origin: Instant::now(),
+        records: Default::default(),
+    });
+    COUNTING.store(true, Ordering::Relaxed);
+}
+
+/// Recorded passes, if the recording is enabled
+pub fn timings() -> Option<&'static PassTimings> {
+    TIMINGS.get()
+}
+
+/// Runs the pass, recording its wall time and allocations if the recording is enabled
+pub fn time_pass<T>(name: impl Into<Cow<'static, str>>, f: impl FnOnce() -> T) -> T {
+    let Some(timings) = TIMINGS.get() else {
+        return f();
+    };
+    let allocations = AllocStats::current();
+    let start = Instant::now();
+    let result = f();
+    let duration = start.elapsed();
+
+    let record = PassRecord {
+        name: name.into(),
+        file: CURRENT_FILE.with(|it| it.borrow().clone()),
+        thread: thread_index(),
+        start: start.duration_since(timings.origin),
+        duration,
+        allocations: AllocStats::current().since(allocations),
+    };
+    timings
+        .records
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(record);
+    result
+}
+
+/// Passes timed inside `f` are attributed to the file
+pub fn in_file<T>(file: impl Into<Arc<str>>, f: impl FnOnce() -> T) -> T {
+    /// Restores the previous file even if `f` panics
+    struct Guard(Option<Arc<str>>);
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            let _ = CURRENT_FILE.try_with(|it| it.replace(previous));
+        }
+    }
+
+    let _guard = Guard(CURRENT_FILE.with(|it| it.replace(Some(file.into()))));
+    f()
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    match unit {
+        0 => format!("{bytes} B"),
+        _ => format!("{value:.1} {}", UNITS[unit]),
+    }
+}
+
+impl PassTimings {
+    pub fn records(&self) -> Vec<PassRecord> {
+        let mut records = self
+            .records
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        records.sort_by_key(|it| it.start);
+        records
+    }
+
+    /// Records summed up by pass names, in order of the first run
+    pub fn aggregated(&self) -> Vec<(Cow<'static, str>, Duration, AllocStats)> {
+        let mut result: Vec<(Cow<'static, str>, Duration, AllocStats)> = vec![];
+        for record in self.records() {
+            match result.iter_mut().find(|(name, ..)| *name == record.name) {
+                Some((_, duration, allocations)) => {
+                    *duration += record.duration;
+                    allocations.count += record.allocations.count;
+                    allocations.bytes += record.allocations.bytes;
+                }
+                None => result.push((record.name, record.duration, record.allocations)),
+            }
+        }
+        result
+    }
+
+    /// Every pass of every file followed by totals of the passes
+    pub fn write_table<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+        let records = self.records();
+        let name_width = records
+            .iter()
+            .map(|it| it.name.len())
+            .chain(["pass".len()])
+            .max()
+            .unwrap_or_default();
+        let file_width = records
+            .iter()
+            .filter_map(|it| it.file.as_ref().map(|it| it.len()))
+            .chain(["file".len(), "total".len()])
+            .max()
+            .unwrap_or_default();
+        let row =
+            |writer: &mut W, name: &str, file: &str, duration: Duration, stats: AllocStats| {
+                writeln!(
+                    writer,
+                    "{name:<name_width$}  {file:<file_width$}  {:>10.2}ms  {:>10}  {:>10}",
+                    duration.as_secs_f64() * 1000.0,
+                    stats.count,
+                    format_bytes(stats.bytes)
+                )
+            };
+
+        writeln!(
+            writer,
+            "{:<name_width$}  {:<file_width$}  {:>12}  {:>10}  {:>10}",
+            "pass", "file", "time", "allocs", "allocated"
+        )?;
+        for record in &records {
+            let file = record.file.as_deref().unwrap_or("-");
+            row(
+                &mut writer,
+                &record.name,
+                file,
+                record.duration,
+                record.allocations,
+            )?;
+        }
+        writeln!(writer)?;
+        for (name, duration, allocations) in self.aggregated() {
+            row(&mut writer, &name, "total", duration, allocations)?;
+        }
+        Ok(())
+    }
+
+    /// Trace in the Chrome trace event format, can be opened in `chrome://tracing` or Perfetto
+    pub fn write_chrome_trace<W: Write>(&self, writer: W) -> std::io::Result<()> {
+        let events: Vec<_> = self
+            .records()
+            .into_iter()
+            .map(|it| {
+                json!({
+                    "name": it.name,
+                    "cat": "pass",
+                    "ph": "X",
+                    "ts": it.start.as_micros() as u64,
+                    "dur": it.duration.as_micros() as u64,
+                    "pid": 1,
+                    "tid": it.thread,
+                    "args": {
+                        "file": it.file.as_deref(),
+                        "allocations": it.allocations.count,
+                        "allocated_bytes": it.allocations.bytes,
+                    },
+                })
+            })
+            .collect();
+        serde_json::to_writer(writer, &json!({ "traceEvents": events }))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use crate::time_passes::{format_bytes, AllocStats, PassRecord, PassTimings};
+    use rstest::rstest;
+    use std::time::{Duration, Instant};
+
+    fn record(name: &'static str, file: &str, start: u64, count: u64) -> PassRecord {
+        PassRecord {
+            name: name.into(),
+            file: Some(file.into()),
+            thread: 0,
+            start: Duration::from_millis(start),
+            duration: Duration::from_millis(1),
+            allocations: AllocStats {
+                count,
+                bytes: count * 8,
+            },
+        }
+    }
+
+    #[rstest]
+    #[case(0, "0 B")]
+    #[case(1023, "1023 B")]
+    #[case(1536, "1.5 KiB")]
+    #[case(3 * 1024 * 1024, "3.0 MiB")]
+    fn test_format_bytes(#[case] bytes: u64, #[case] expected: &str) {
+        assert_eq!(format_bytes(bytes), expected);
+    }
+
+    #[test]
+    fn test_aggregated_by_pass() {
+        let timings = PassTimings {
+            origin: Instant::now(),
+            records: vec![
+                record("parsing", "b.kd", 3, 4),
+                record("lexing", "a.kd", 0, 1),
+                record("parsing", "a.kd", 1, 2),
+                record("lexing", "b.kd", 2, 3),
+            ]
+            .into(),
+        };
+
+        let aggregated = timings.aggregated();
+        assert_eq!(aggregated.len(), 2);
+        assert_eq!(aggregated[0].0, "lexing");
+        assert_eq!(aggregated[0].1, Duration::from_millis(2));
+        assert_eq!(
+            aggregated[0].2,
+            AllocStats {
+                count: 4,
+                bytes: 32
+            }
+        );
+        assert_eq!(aggregated[1].0, "parsing");
+        assert_eq!(aggregated[1].2.count, 6);
+
+        let mut table = vec![];
+        timings.write_table(&mut table).unwrap();
+        let table = String::from_utf8(table).unwrap();
+        assert_eq!(table.lines().count(), 1 + 4 + 1 + 2);
+        assert!(table.lines().nth(1).unwrap().starts_with("lexing   a.kd"));
+    }
+}
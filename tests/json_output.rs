@@ -0,0 +1,25 @@
+use std::process::Command;
+
+#[test]
+fn test_json_diagnostics_are_written_to_stdout_line_by_line() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("main.kd");
+    std::fs::write(&path, "module M {\n    fun f(x: ) => 1\n    fun g( => 1\n}\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_kodept"))
+        .args(["--style", "json", "--out"])
+        .arg(dir.path())
+        .arg("execute")
+        .arg(&path)
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let reports: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(reports.len(), 2);
+    assert!(reports.iter().all(|it| it["code"] == "KC001"));
+}